@@ -1,10 +1,33 @@
 //! PulsarTrack - Ad Content Registry (Soroban)
 //! Manages ad creative assets, validation, and performance tracking on Stellar.
+//!
+//! Events:
+//! - ("register", "content"): [content_id: u64, campaign_id: u64]
+//! - ("content", "variant"): [parent_content_id: u64, variant_id: u64]
+//! - ("content", "unflagged"): [content_id: u64, reporter: Address, flags_count: u32]
+//! - ("content", "reviewed"): [content_id: u64, reviewer: Address, approved: bool]
+//! - ("campaign", "suspend"): [campaign_id: u64, suspended_count: u32]
+//! - ("campaign", "restore"): [campaign_id: u64, restored_count: u32]
+//! - ("content", "status"): [content_id: u64, old_status: ContentStatus, new_status: ContentStatus]
+//! - ("content", "flag"): [content_id: u64, reporter: Address, flags_count: u32]
+//! - ("content", "suspend"): [content_id: u64, flags_count: u32] on auto-suspension
+//! - ("content", "archive"): content_id: u64
+//! - ("content", "milestone"): [content_id: u64, total_views: u64] at 10, 100, 1000, ... views
+//! - ("admin", "changed"): [old_admin: Address, new_admin: Address]
+//!
+//! Flags are weighted by the reporter's track record: a reporter with fewer
+//! than `min_reviewed_flags` reviewed flags counts at `new_reporter_weight_bps`
+//! (20% of a full flag by default, so fresh addresses cannot suspend content
+//! on their own), otherwise at their verified share of reviewed flags, or zero below
+//! `min_accuracy_bps`. Content is auto-suspended once the summed weight of its
+//! standing flags reaches FlagThreshold full flags. Each reporter may submit
+//! at most `max_flags_per_day` flags per 24h window.
 
 
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short,
+    contract, contracterror, contractimpl, contracttype, symbol_short,
     Address, Bytes, BytesN, Env, Map, String, Symbol, Vec,
 };
 
@@ -43,6 +66,8 @@ pub struct AdContent {
     pub created_at: u64,
     pub updated_at: u64,
     pub flags_count: u32,
+    /// Ledger timestamp after which the content stops serving; 0 = never
+    pub expires_at: u64,
 }
 
 #[contracttype]
@@ -54,6 +79,42 @@ pub struct ContentMetadata {
     pub landing_url: String,
 }
 
+/// A creative that was replaced by update_content, numbered from 1
+#[contracttype]
+#[derive(Clone)]
+pub struct ContentRevision {
+    pub ipfs_hash: String,
+    pub metadata: ContentMetadata,
+    pub live_from: u64,
+    pub replaced_at: u64,
+}
+
+/// Size bounds in bytes for one content format
+#[contracttype]
+#[derive(Clone)]
+pub struct SizeLimits {
+    pub min_size: u64,
+    pub max_size: u64,
+}
+
+/// Cumulative performance as of `taken_at`; consecutive snapshots give per-window deltas
+#[contracttype]
+#[derive(Clone)]
+pub struct PerfSnapshot {
+    pub performance: ContentPerformance,
+    pub taken_at: u64,
+}
+
+/// Outcome of a reviewer's decision on pending content
+#[contracttype]
+#[derive(Clone)]
+pub struct ReviewRecord {
+    pub reviewer: Address,
+    pub approved: bool,
+    pub note: String,
+    pub reviewed_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct ContentPerformance {
@@ -70,6 +131,32 @@ pub struct FlagRecord {
     pub reason: String,
     pub timestamp: u64,
     pub verified: bool,
+    /// Set when an admin rules the flag invalid
+    pub dismissed: bool,
+    /// Contribution towards FlagThreshold in bps of a full flag
+    pub weight_bps: u32,
+}
+
+/// A reporter's flag history; `window_start`/`window_flags` count the flags
+/// submitted in the current 24h rate-limit window
+#[contracttype]
+#[derive(Clone)]
+pub struct ReporterStats {
+    pub flags_submitted: u32,
+    pub flags_verified: u32,
+    pub flags_dismissed: u32,
+    pub window_start: u64,
+    pub window_flags: u32,
+}
+
+/// Admin-configurable flag weighting and rate limit
+#[contracttype]
+#[derive(Clone)]
+pub struct ReporterPolicy {
+    pub new_reporter_weight_bps: u32,
+    pub min_reviewed_flags: u32,
+    pub min_accuracy_bps: u32,
+    pub max_flags_per_day: u32,
 }
 
 // ============================================================
@@ -89,20 +176,61 @@ pub enum DataKey {
     Flag(u64, Address),
     CampaignContents(u64),
     ContentVariants(u64),
+    Tracker(Address),
+    Seen(u64, BytesN<32>),
+    OpenTracking,
+    FlagIndex(u64),
+    PreSuspendStatus(u64),
+    ContentVersion(u64, u32),
+    VersionCount(u64),
+    Reviewer(Address),
+    Review(u64),
+    SnapshotInterval,
+    PerfSnapshot(u64, u32),
+    SnapshotCount(u64),
+    LastSnapshotAt(u64),
+    CampaignHold(u64),
+    CampaignSuspended(u64),
+    CampaignCursor(u64),
+    FormatLimits(ContentFormat),
+    Clicked(u64, BytesN<32>),
+    ReporterStats(Address),
+    ReporterPolicy,
+    FlagWeight(u64),
+    ApprovedCount(u64, Address), // campaign_id, owner
+    CampaignOwner(u64),
 }
 
 // ============================================================
-// Error Codes
+// Errors
 // ============================================================
 
-pub const ERR_UNAUTHORIZED: u32 = 1;
-pub const ERR_NOT_FOUND: u32 = 2;
-pub const ERR_ALREADY_EXISTS: u32 = 3;
-pub const ERR_INVALID_CONTENT: u32 = 4;
-pub const ERR_INVALID_STATUS: u32 = 5;
-pub const ERR_CONTENT_FLAGGED: u32 = 6;
-pub const ERR_INVALID_FORMAT: u32 = 7;
-pub const ERR_NOT_INITIALIZED: u32 = 8;
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AdRegistryError {
+    Unauthorized = 1,
+    NotFound = 2,
+    AlreadyExists = 3,
+    InvalidContent = 4,
+    InvalidStatus = 5,
+    ContentFlagged = 6,
+    InvalidFormat = 7,
+    NotInitialized = 8,
+    AlreadyInitialized = 9,
+    CannotFlagOwnContent = 10,
+    NotApproved = 11,
+    UnauthorizedTracker = 12,
+    NothingToUpdate = 13,
+    FlagAlreadyReviewed = 14,
+    SnapshotTooSoon = 15,
+    ContentExpired = 16,
+    NotExpired = 17,
+    InvalidMetadata = 18,
+    NoViews = 19,
+    AlreadyClicked = 20,
+    RateLimited = 21,
+}
 
 // ============================================================
 // Contract
@@ -112,6 +240,13 @@ const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
+const MAX_LIST_LIMIT: u32 = 100;
+const DEFAULT_SNAPSHOT_INTERVAL: u64 = 86_400;
+// CIDv0 hashes are 46 characters; CIDv1 strings are longer
+const MIN_IPFS_HASH_LEN: u32 = 46;
+const MAX_IPFS_HASH_LEN: u32 = 128;
+const BPS: u32 = 10_000;
+const FLAG_WINDOW: u64 = 86_400;
 
 #[contract]
 pub struct AdRegistryContract;
@@ -119,10 +254,10 @@ pub struct AdRegistryContract;
 #[contractimpl]
 impl AdRegistryContract {
     /// Initialize the contract with an admin address
-    pub fn initialize(env: Env, admin: Address) {
+    pub fn initialize(env: Env, admin: Address) -> Result<(), AdRegistryError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("already initialized");
+            return Err(AdRegistryError::AlreadyInitialized);
         }
         admin.require_auth();
         env.storage().instance().set(&DataKey::Admin, &admin);
@@ -130,11 +265,15 @@ impl AdRegistryContract {
         env.storage().instance().set(&DataKey::MinContentSize, &100u64);
         env.storage().instance().set(&DataKey::MaxContentSize, &10_485_760u64); // 10MB
         env.storage().instance().set(&DataKey::FlagThreshold, &5u32);
+        Ok(())
     }
 
-    /// Register new ad content
+    /// Register new ad content owned by `owner`. `expires_at` is a ledger
+    /// timestamp after which the content stops serving (0 = never). The first
+    /// registrant for a campaign becomes its owner; only they can add to it.
     pub fn register_content(
         env: Env,
+        owner: Address,
         campaign_id: u64,
         ipfs_hash: String,
         format: ContentFormat,
@@ -143,47 +282,10 @@ impl AdRegistryContract {
         description: String,
         call_to_action: String,
         landing_url: String,
-    ) -> u64 {
+        expires_at: u64,
+    ) -> Result<u64, AdRegistryError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        let caller = env.current_contract_address();
-        let _ = caller; // will be overridden by auth
-        // Use invoker auth pattern
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        let _ = admin;
-
-        let min_size: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::MinContentSize)
-            .unwrap_or(100);
-        let max_size: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::MaxContentSize)
-            .unwrap_or(10_485_760);
-
-        if size < min_size || size > max_size {
-            panic!("invalid content size");
-        }
-
-        let nonce: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::ContentNonce)
-            .unwrap_or(0);
-        let content_id = nonce + 1;
-
-        let content = AdContent {
-            campaign_id,
-            owner: env.current_contract_address(),
-            ipfs_hash,
-            format,
-            size,
-            status: ContentStatus::Pending,
-            created_at: env.ledger().timestamp(),
-            updated_at: env.ledger().timestamp(),
-            flags_count: 0,
-        };
+        owner.require_auth();
 
         let metadata = ContentMetadata {
             title,
@@ -191,64 +293,21 @@ impl AdRegistryContract {
             call_to_action,
             landing_url,
         };
-
-        let performance = ContentPerformance {
-            total_views: 0,
-            total_clicks: 0,
-            unique_viewers: 0,
-            click_through_rate: 0,
-            last_shown: 0,
-        };
-
-        let _ttl_key = DataKey::Content(content_id);
-        env.storage()
-            .persistent()
-            .set(&_ttl_key, &content);
-        env.storage()
-            .persistent()
-            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        let _ttl_key = DataKey::Metadata(content_id);
-        env.storage()
-            .persistent()
-            .set(&_ttl_key, &metadata);
-        env.storage()
-            .persistent()
-            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        let _ttl_key = DataKey::Performance(content_id);
-        env.storage()
-            .persistent()
-            .set(&_ttl_key, &performance);
-        env.storage()
-            .persistent()
-            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        env.storage()
-            .instance()
-            .set(&DataKey::ContentNonce, &content_id);
-
-        env.events().publish(
-            (symbol_short!("register"), symbol_short!("content")),
-            (content_id, campaign_id),
-        );
-
-        content_id
+        Self::_store_content(&env, owner, campaign_id, ipfs_hash, format, size, metadata, expires_at)
     }
 
     /// Update content status (admin only)
-    pub fn update_status(env: Env, admin: Address, content_id: u64, new_status: ContentStatus) {
+    pub fn update_status(env: Env, admin: Address, content_id: u64, new_status: ContentStatus) -> Result<(), AdRegistryError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        admin.require_auth();
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if admin != stored_admin {
-            panic!("unauthorized");
-        }
+        Self::_require_admin(&env, &admin)?;
 
-        let mut content: AdContent = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Content(content_id))
-            .expect("content not found");
-        content.status = new_status;
+        let mut content = Self::_load_content(&env, content_id)?;
+        let old_status = content.status.clone();
+        Self::_set_status(&env, &mut content, new_status);
         content.updated_at = env.ledger().timestamp();
+        // A manual decision overrides any pending auto-suspension or campaign restore
+        env.storage().persistent().remove(&DataKey::PreSuspendStatus(content_id));
+        env.storage().persistent().remove(&DataKey::CampaignHold(content_id));
         let _ttl_key = DataKey::Content(content_id);
         env.storage()
             .persistent()
@@ -256,27 +315,49 @@ impl AdRegistryContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        Self::_emit_status(&env, content_id, old_status, content.status);
+        Ok(())
     }
 
     /// Flag content for review
-    pub fn flag_content(env: Env, reporter: Address, content_id: u64, reason: String) {
+    pub fn flag_content(env: Env, reporter: Address, content_id: u64, reason: String) -> Result<(), AdRegistryError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         reporter.require_auth();
 
-        let mut content: AdContent = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Content(content_id))
-            .expect("content not found");
+        let mut content = Self::_load_content(&env, content_id)?;
 
         if content.owner == reporter {
-            panic!("cannot flag own content");
+            return Err(AdRegistryError::CannotFlagOwnContent);
+        }
+        if matches!(content.status, ContentStatus::Archived) {
+            return Err(AdRegistryError::InvalidStatus);
+        }
+        if env.storage().persistent().has(&DataKey::Flag(content_id, reporter.clone())) {
+            return Err(AdRegistryError::AlreadyExists);
+        }
+
+        let now = env.ledger().timestamp();
+        let policy = Self::_reporter_policy(&env);
+        let mut stats = Self::_reporter_stats(&env, &reporter);
+        if now >= stats.window_start.saturating_add(FLAG_WINDOW) {
+            stats.window_start = now;
+            stats.window_flags = 0;
         }
+        if stats.window_flags >= policy.max_flags_per_day {
+            return Err(AdRegistryError::RateLimited);
+        }
+        let weight_bps = Self::_flag_weight(&stats, &policy);
+        stats.flags_submitted += 1;
+        stats.window_flags += 1;
+        Self::_save_reporter_stats(&env, &reporter, &stats);
 
         let flag = FlagRecord {
             reason,
-            timestamp: env.ledger().timestamp(),
+            timestamp: now,
             verified: false,
+            dismissed: false,
+            weight_bps,
         };
 
         let _ttl_key = DataKey::Flag(content_id, reporter.clone());
@@ -287,19 +368,41 @@ impl AdRegistryContract {
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 
-        content.flags_count += 1;
-
-        let threshold: u32 = env
+        let _ttl_key = DataKey::FlagIndex(content_id);
+        let mut reporters: Vec<Address> = env
             .storage()
-            .instance()
-            .get(&DataKey::FlagThreshold)
-            .unwrap_or(5);
+            .persistent()
+            .get(&_ttl_key)
+            .unwrap_or(Vec::new(&env));
+        reporters.push_back(reporter.clone());
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &reporters);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        content.flags_count += 1;
+        let flag_weight = Self::_content_flag_weight(&env, content_id) + weight_bps as u64;
+        Self::_set_content_flag_weight(&env, content_id, flag_weight);
 
-        if content.flags_count >= threshold {
-            content.status = ContentStatus::Suspended;
+        if flag_weight >= Self::_threshold_weight(&env) && !matches!(content.status, ContentStatus::Suspended) {
+            // Remember what to restore if enough flags are later dismissed
+            let _ttl_key = DataKey::PreSuspendStatus(content_id);
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &content.status);
+            env.storage()
+                .persistent()
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            Self::_set_status(&env, &mut content, ContentStatus::Suspended);
+            env.events().publish(
+                (symbol_short!("content"), symbol_short!("suspend")),
+                (content_id, content.flags_count),
+            );
         }
 
-        content.updated_at = env.ledger().timestamp();
+        content.updated_at = now;
         let _ttl_key = DataKey::Content(content_id);
         env.storage()
             .persistent()
@@ -307,35 +410,120 @@ impl AdRegistryContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("content"), symbol_short!("flag")),
+            (content_id, reporter, content.flags_count),
+        );
+        Ok(())
     }
 
-    /// Track a content view
-    pub fn track_view(env: Env, content_id: u64) {
+    /// Rule on a reporter's flag (admin only). Dismissing a flag lowers the
+    /// count and weight and lifts an auto-suspension once the weight falls
+    /// below the threshold. Either ruling updates the reporter's stats.
+    pub fn verify_flag(env: Env, admin: Address, content_id: u64, reporter: Address, valid: bool) -> Result<(), AdRegistryError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        let content: AdContent = env
+        Self::_require_admin(&env, &admin)?;
+
+        let flag_key = DataKey::Flag(content_id, reporter.clone());
+        let mut flag: FlagRecord = env
             .storage()
             .persistent()
-            .get(&DataKey::Content(content_id))
-            .expect("content not found");
+            .get(&flag_key)
+            .ok_or(AdRegistryError::NotFound)?;
+        if flag.verified || flag.dismissed {
+            return Err(AdRegistryError::FlagAlreadyReviewed);
+        }
+
+        let mut stats = Self::_reporter_stats(&env, &reporter);
+        if valid {
+            flag.verified = true;
+            stats.flags_verified += 1;
+        } else {
+            flag.dismissed = true;
+            stats.flags_dismissed += 1;
+
+            let mut content = Self::_load_content(&env, content_id)?;
+            content.flags_count -= 1;
+            let flag_weight = Self::_content_flag_weight(&env, content_id).saturating_sub(flag.weight_bps as u64);
+            Self::_set_content_flag_weight(&env, content_id, flag_weight);
+
+            let restore_key = DataKey::PreSuspendStatus(content_id);
+            if flag_weight < Self::_threshold_weight(&env) && matches!(content.status, ContentStatus::Suspended) {
+                if let Some(previous) = env.storage().persistent().get::<DataKey, ContentStatus>(&restore_key) {
+                    env.storage().persistent().remove(&restore_key);
+                    // Under a campaign suspension, restore_campaign brings it back later
+                    let hold_key = DataKey::CampaignHold(content_id);
+                    if env.storage().persistent().has(&hold_key) {
+                        env.storage().persistent().set(&hold_key, &previous);
+                    } else {
+                        Self::_set_status(&env, &mut content, previous.clone());
+                        Self::_emit_status(&env, content_id, ContentStatus::Suspended, previous);
+                    }
+                }
+            }
+
+            content.updated_at = env.ledger().timestamp();
+            let _ttl_key = DataKey::Content(content_id);
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &content);
+            env.storage()
+                .persistent()
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+            env.events().publish(
+                (symbol_short!("content"), symbol_short!("unflagged")),
+                (content_id, reporter.clone(), content.flags_count),
+            );
+        }
+
+        env.storage().persistent().set(&flag_key, &flag);
+        env.storage()
+            .persistent()
+            .extend_ttl(&flag_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Self::_save_reporter_stats(&env, &reporter, &stats);
+        Ok(())
+    }
+
+    /// Track a content view. `viewer_id` is an opaque per-viewer hash; each one
+    /// counts once towards unique_viewers.
+    pub fn track_view(env: Env, tracker: Address, content_id: u64, viewer_id: BytesN<32>) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_tracker(&env, &tracker)?;
+        let content = Self::_load_content(&env, content_id)?;
 
         match content.status {
             ContentStatus::Approved => {}
-            _ => panic!("content not approved"),
+            _ => return Err(AdRegistryError::NotApproved),
+        }
+        if Self::_is_expired(&env, &content) {
+            return Err(AdRegistryError::ContentExpired);
         }
 
         let mut perf: ContentPerformance = env
             .storage()
             .persistent()
             .get(&DataKey::Performance(content_id))
-            .expect("performance not found");
+            .ok_or(AdRegistryError::NotFound)?;
 
         perf.total_views += 1;
-        perf.unique_viewers += 1;
-        perf.last_shown = env.ledger().timestamp();
-
-        if perf.total_views > 0 {
-            perf.click_through_rate = (perf.total_clicks * 10_000) / perf.total_views;
+        if Self::_is_view_milestone(perf.total_views) {
+            env.events().publish(
+                (symbol_short!("content"), symbol_short!("milestone")),
+                (content_id, perf.total_views),
+            );
         }
+        let seen_key = DataKey::Seen(content_id, viewer_id);
+        if !env.storage().persistent().has(&seen_key) {
+            perf.unique_viewers += 1;
+            env.storage().persistent().set(&seen_key, &true);
+            env.storage()
+                .persistent()
+                .extend_ttl(&seen_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+        perf.last_shown = env.ledger().timestamp();
+        Self::_update_ctr(&mut perf);
 
         let _ttl_key = DataKey::Performance(content_id);
         env.storage()
@@ -344,23 +532,51 @@ impl AdRegistryContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Ok(())
     }
 
-    /// Track a content click
-    pub fn track_click(env: Env, content_id: u64) {
+    /// Track a click on approved content that has been viewed. With a
+    /// `viewer_id` the click is attributed to a viewer seen by track_view, and
+    /// each viewer counts at most one click.
+    pub fn track_click(env: Env, tracker: Address, content_id: u64, viewer_id: Option<BytesN<32>>) -> Result<(), AdRegistryError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_tracker(&env, &tracker)?;
+        let content = Self::_load_content(&env, content_id)?;
+
+        match content.status {
+            ContentStatus::Approved => {}
+            _ => return Err(AdRegistryError::NotApproved),
+        }
+        if Self::_is_expired(&env, &content) {
+            return Err(AdRegistryError::ContentExpired);
+        }
+
         let mut perf: ContentPerformance = env
             .storage()
             .persistent()
             .get(&DataKey::Performance(content_id))
-            .expect("performance not found");
-
-        perf.total_clicks += 1;
+            .ok_or(AdRegistryError::NotFound)?;
+        if perf.total_views == 0 {
+            return Err(AdRegistryError::NoViews);
+        }
 
-        if perf.total_views > 0 {
-            perf.click_through_rate = (perf.total_clicks * 10_000) / perf.total_views;
+        if let Some(viewer_id) = viewer_id {
+            if !env.storage().persistent().has(&DataKey::Seen(content_id, viewer_id.clone())) {
+                return Err(AdRegistryError::NoViews);
+            }
+            let clicked_key = DataKey::Clicked(content_id, viewer_id);
+            if env.storage().persistent().has(&clicked_key) {
+                return Err(AdRegistryError::AlreadyClicked);
+            }
+            env.storage().persistent().set(&clicked_key, &true);
+            env.storage()
+                .persistent()
+                .extend_ttl(&clicked_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
         }
 
+        perf.total_clicks += 1;
+        Self::_update_ctr(&mut perf);
+
         let _ttl_key = DataKey::Performance(content_id);
         env.storage()
             .persistent()
@@ -368,88 +584,1120 @@ impl AdRegistryContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Ok(())
     }
 
-    /// Archive content (owner only)
-    pub fn archive_content(env: Env, owner: Address, content_id: u64) {
+    /// Register an A/B variant of existing content (parent owner only). The
+    /// variant shares the parent's campaign, format, size and expiry.
+    pub fn create_variant(
+        env: Env,
+        owner: Address,
+        parent_content_id: u64,
+        ipfs_hash: String,
+        title: String,
+        description: String,
+        call_to_action: String,
+        landing_url: String,
+    ) -> Result<u64, AdRegistryError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         owner.require_auth();
 
-        let mut content: AdContent = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Content(content_id))
-            .expect("content not found");
-
-        if content.owner != owner {
-            panic!("unauthorized");
+        let parent = Self::_load_content(&env, parent_content_id)?;
+        if parent.owner != owner {
+            return Err(AdRegistryError::Unauthorized);
+        }
+        if matches!(parent.status, ContentStatus::Rejected | ContentStatus::Suspended) {
+            return Err(AdRegistryError::InvalidStatus);
         }
 
-        content.status = ContentStatus::Archived;
-        content.updated_at = env.ledger().timestamp();
-        let _ttl_key = DataKey::Content(content_id);
+        let metadata = ContentMetadata {
+            title,
+            description,
+            call_to_action,
+            landing_url,
+        };
+        let variant_id = Self::_store_content(
+            &env,
+            owner,
+            parent.campaign_id,
+            ipfs_hash,
+            parent.format,
+            parent.size,
+            metadata,
+            parent.expires_at,
+        )?;
+
+        let _ttl_key = DataKey::ContentVariants(parent_content_id);
+        let mut variants: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&_ttl_key)
+            .unwrap_or(Vec::new(&env));
+        variants.push_back(variant_id);
         env.storage()
             .persistent()
-            .set(&_ttl_key, &content);
+            .set(&_ttl_key, &variants);
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-    }
 
-    // ============================================================
-    // Read-Only Functions
-    // ============================================================
+        env.events().publish(
+            (symbol_short!("content"), symbol_short!("variant")),
+            (parent_content_id, variant_id),
+        );
 
-    pub fn get_content(env: Env, content_id: u64) -> Option<AdContent> {
-        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage().persistent().get(&DataKey::Content(content_id))
+        Ok(variant_id)
     }
 
-    pub fn get_metadata(env: Env, content_id: u64) -> Option<ContentMetadata> {
-        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage()
-            .persistent()
-            .get(&DataKey::Metadata(content_id))
+    /// Replace the content's metadata (owner only); same review rules as update_content
+    pub fn update_metadata(
+        env: Env,
+        owner: Address,
+        content_id: u64,
+        title: String,
+        description: String,
+        call_to_action: String,
+        landing_url: String,
+    ) -> Result<(), AdRegistryError> {
+        let metadata = ContentMetadata {
+            title,
+            description,
+            call_to_action,
+            landing_url,
+        };
+        Self::update_content(env, owner, content_id, None, Some(metadata))
     }
 
-    pub fn get_performance(env: Env, content_id: u64) -> Option<ContentPerformance> {
+    /// Swap the creative and/or metadata (owner only). The replaced version is
+    /// kept for audit and the content goes back to Pending for re-review;
+    /// performance history carries over.
+    pub fn update_content(
+        env: Env,
+        owner: Address,
+        content_id: u64,
+        new_ipfs_hash: Option<String>,
+        new_metadata: Option<ContentMetadata>,
+    ) -> Result<(), AdRegistryError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage()
-            .persistent()
-            .get(&DataKey::Performance(content_id))
-    }
+        owner.require_auth();
 
-    pub fn is_approved(env: Env, content_id: u64) -> bool {
-        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        if let Some(content) = env
+        let mut content = Self::_load_content(&env, content_id)?;
+        if content.owner != owner {
+            return Err(AdRegistryError::Unauthorized);
+        }
+        if matches!(content.status, ContentStatus::Suspended | ContentStatus::Archived) {
+            return Err(AdRegistryError::InvalidStatus);
+        }
+        if new_ipfs_hash.is_none() && new_metadata.is_none() {
+            return Err(AdRegistryError::NothingToUpdate);
+        }
+        if let Some(ipfs_hash) = &new_ipfs_hash {
+            Self::_validate_ipfs_hash(ipfs_hash)?;
+        }
+        if let Some(metadata) = &new_metadata {
+            Self::_validate_metadata(metadata)?;
+        }
+
+        let metadata: ContentMetadata = env
             .storage()
             .persistent()
-            .get::<DataKey, AdContent>(&DataKey::Content(content_id))
-        {
-            matches!(content.status, ContentStatus::Approved)
+            .get(&DataKey::Metadata(content_id))
+            .unwrap();
+        let now = env.ledger().timestamp();
+        let version: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::VersionCount(content_id))
+            .unwrap_or(0);
+        let live_from = if version == 0 {
+            content.created_at
         } else {
-            false
-        }
-    }
-
-    pub fn get_nonce(env: Env) -> u64 {
-        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+            env.storage()
+                .persistent()
+                .get::<DataKey, ContentRevision>(&DataKey::ContentVersion(content_id, version))
+                .map(|previous| previous.replaced_at)
+                .unwrap_or(content.created_at)
+        };
+        let revision = ContentRevision {
+            ipfs_hash: content.ipfs_hash.clone(),
+            metadata: metadata.clone(),
+            live_from,
+            replaced_at: now,
+        };
+        let version = version + 1;
+        let _ttl_key = DataKey::ContentVersion(content_id, version);
         env.storage()
-            .instance()
-            .get(&DataKey::ContentNonce)
-            .unwrap_or(0)
-    }
+            .persistent()
+            .set(&_ttl_key, &revision);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::VersionCount(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &version);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 
-    pub fn set_flag_threshold(env: Env, admin: Address, threshold: u32) {
-        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        admin.require_auth();
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if admin != stored_admin {
-            panic!("unauthorized");
+        if let Some(ipfs_hash) = new_ipfs_hash {
+            content.ipfs_hash = ipfs_hash;
         }
+        let _ttl_key = DataKey::Metadata(content_id);
         env.storage()
-            .instance()
-            .set(&DataKey::FlagThreshold, &threshold);
+            .persistent()
+            .set(&_ttl_key, &new_metadata.unwrap_or(metadata));
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let old_status = content.status.clone();
+        Self::_set_status(&env, &mut content, ContentStatus::Pending);
+        content.updated_at = now;
+        let _ttl_key = DataKey::Content(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &content);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        if !matches!(old_status, ContentStatus::Pending) {
+            Self::_emit_status(&env, content_id, old_status, ContentStatus::Pending);
+        }
+        Ok(())
+    }
+
+    /// Archive content (owner only)
+    pub fn archive_content(env: Env, owner: Address, content_id: u64) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        owner.require_auth();
+
+        let mut content = Self::_load_content(&env, content_id)?;
+
+        if content.owner != owner {
+            return Err(AdRegistryError::Unauthorized);
+        }
+
+        Self::_set_status(&env, &mut content, ContentStatus::Archived);
+        content.updated_at = env.ledger().timestamp();
+        let _ttl_key = DataKey::Content(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &content);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish((symbol_short!("content"), symbol_short!("archive")), content_id);
+        Ok(())
+    }
+
+    /// Approve or reject Pending content (reviewers only). Status changes
+    /// beyond the initial review stay with the admin via update_status.
+    pub fn review_content(
+        env: Env,
+        reviewer: Address,
+        content_id: u64,
+        approve: bool,
+        note: String,
+    ) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        reviewer.require_auth();
+        if !env.storage().persistent().has(&DataKey::Reviewer(reviewer.clone())) {
+            return Err(AdRegistryError::Unauthorized);
+        }
+
+        let mut content = Self::_load_content(&env, content_id)?;
+        if !matches!(content.status, ContentStatus::Pending) {
+            return Err(AdRegistryError::InvalidStatus);
+        }
+
+        let now = env.ledger().timestamp();
+        let old_status = content.status.clone();
+        let new_status = if approve {
+            ContentStatus::Approved
+        } else {
+            ContentStatus::Rejected
+        };
+        Self::_set_status(&env, &mut content, new_status);
+        content.updated_at = now;
+        let _ttl_key = DataKey::Content(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &content);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let review = ReviewRecord {
+            reviewer: reviewer.clone(),
+            approved: approve,
+            note,
+            reviewed_at: now,
+        };
+        let _ttl_key = DataKey::Review(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &review);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        Self::_emit_status(&env, content_id, old_status, content.status);
+        env.events().publish(
+            (symbol_short!("content"), symbol_short!("reviewed")),
+            (content_id, reviewer, approve),
+        );
+        Ok(())
+    }
+
+    /// Archive content past its expiry (anyone may call)
+    pub fn archive_expired(env: Env, content_id: u64) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut content = Self::_load_content(&env, content_id)?;
+        if matches!(content.status, ContentStatus::Archived) {
+            return Err(AdRegistryError::InvalidStatus);
+        }
+        if !Self::_is_expired(&env, &content) {
+            return Err(AdRegistryError::NotExpired);
+        }
+
+        Self::_set_status(&env, &mut content, ContentStatus::Archived);
+        content.updated_at = env.ledger().timestamp();
+        let _ttl_key = DataKey::Content(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &content);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish((symbol_short!("content"), symbol_short!("archive")), content_id);
+        Ok(())
+    }
+
+    /// Suspend every non-archived content of a campaign (admin only), up to
+    /// `max_items` index entries per call (default and cap 100). Returns the
+    /// number of entries processed; call again until it returns 0.
+    pub fn suspend_campaign(env: Env, admin: Address, campaign_id: u64, max_items: Option<u32>) -> Result<u32, AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        let (processed, suspended) = Self::_sweep_campaign(&env, campaign_id, true, max_items);
+        env.events().publish(
+            (symbol_short!("campaign"), symbol_short!("suspend")),
+            (campaign_id, suspended),
+        );
+        Ok(processed)
+    }
+
+    /// Undo suspend_campaign (admin only), returning each content to the status
+    /// it had when suspended unless it has since been flagged past the threshold.
+    /// Batched the same way as suspend_campaign.
+    pub fn restore_campaign(env: Env, admin: Address, campaign_id: u64, max_items: Option<u32>) -> Result<u32, AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        let (processed, restored) = Self::_sweep_campaign(&env, campaign_id, false, max_items);
+        env.events().publish(
+            (symbol_short!("campaign"), symbol_short!("restore")),
+            (campaign_id, restored),
+        );
+        Ok(processed)
+    }
+
+    /// Record the content's current performance (anyone may call, at most
+    /// once per snapshot interval). Returns the snapshot index, from 0.
+    pub fn snapshot_performance(env: Env, content_id: u64) -> Result<u32, AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let performance: ContentPerformance = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Performance(content_id))
+            .ok_or(AdRegistryError::NotFound)?;
+
+        let now = env.ledger().timestamp();
+        let last_key = DataKey::LastSnapshotAt(content_id);
+        if let Some(last) = env.storage().persistent().get::<DataKey, u64>(&last_key) {
+            let interval: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::SnapshotInterval)
+                .unwrap_or(DEFAULT_SNAPSHOT_INTERVAL);
+            if now < last.saturating_add(interval) {
+                return Err(AdRegistryError::SnapshotTooSoon);
+            }
+        }
+
+        let index: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SnapshotCount(content_id))
+            .unwrap_or(0);
+        let snapshot = PerfSnapshot {
+            performance,
+            taken_at: now,
+        };
+        let _ttl_key = DataKey::PerfSnapshot(content_id, index);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &snapshot);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::SnapshotCount(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &(index + 1));
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage().persistent().set(&last_key, &now);
+        env.storage()
+            .persistent()
+            .extend_ttl(&last_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        Ok(index)
+    }
+
+    // ============================================================
+    // Read-Only Functions
+    // ============================================================
+
+    pub fn get_content(env: Env, content_id: u64) -> Option<AdContent> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Content(content_id))
+    }
+
+    pub fn get_metadata(env: Env, content_id: u64) -> Option<ContentMetadata> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::Metadata(content_id))
+    }
+
+    pub fn get_performance(env: Env, content_id: u64) -> Option<ContentPerformance> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::Performance(content_id))
+    }
+
+    /// Content ids registered for a campaign, oldest first, whatever their status.
+    /// `limit` is capped at 100.
+    pub fn get_campaign_contents(env: Env, campaign_id: u64, offset: u32, limit: u32) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CampaignContents(campaign_id))
+            .unwrap_or(Vec::new(&env));
+        if offset >= ids.len() {
+            return Vec::new(&env);
+        }
+        let end = offset.saturating_add(limit.min(MAX_LIST_LIMIT)).min(ids.len());
+        ids.slice(offset..end)
+    }
+
+    /// Approved content `owner` has registered for a campaign, in one read.
+    /// Expired content still counts until archive_expired archives it.
+    pub fn get_approved_count(env: Env, campaign_id: u64, owner: Address) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::ApprovedCount(campaign_id, owner))
+            .unwrap_or(0)
+    }
+
+    /// The account that registered the campaign's first content, if any
+    pub fn get_campaign_owner(env: Env, campaign_id: u64) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::CampaignOwner(campaign_id))
+    }
+
+    pub fn get_campaign_content_count(env: Env, campaign_id: u64) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get::<DataKey, Vec<u64>>(&DataKey::CampaignContents(campaign_id))
+            .map(|ids| ids.len())
+            .unwrap_or(0)
+    }
+
+    /// Flags raised on a content, in the order they were raised; `limit` is capped at 100
+    pub fn get_flags(env: Env, content_id: u64, offset: u32, limit: u32) -> Vec<(Address, FlagRecord)> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut result = Vec::new(&env);
+        let reporters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::FlagIndex(content_id))
+            .unwrap_or(Vec::new(&env));
+        let end = offset.saturating_add(limit.min(MAX_LIST_LIMIT)).min(reporters.len());
+        for i in offset..end {
+            let reporter = reporters.get(i).unwrap();
+            if let Some(flag) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, FlagRecord>(&DataKey::Flag(content_id, reporter.clone()))
+            {
+                result.push_back((reporter, flag));
+            }
+        }
+        result
+    }
+
+    /// Performance snapshots, oldest first; `limit` is capped at 100
+    pub fn get_snapshots(env: Env, content_id: u64, offset: u32, limit: u32) -> Vec<PerfSnapshot> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut result = Vec::new(&env);
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SnapshotCount(content_id))
+            .unwrap_or(0);
+        let end = offset.saturating_add(limit.min(MAX_LIST_LIMIT)).min(count);
+        for index in offset..end {
+            if let Some(snapshot) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, PerfSnapshot>(&DataKey::PerfSnapshot(content_id, index))
+            {
+                result.push_back(snapshot);
+            }
+        }
+        result
+    }
+
+    pub fn is_campaign_suspended(env: Env, campaign_id: u64) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::CampaignSuspended(campaign_id))
+            .unwrap_or(false)
+    }
+
+    pub fn get_snapshot_count(env: Env, content_id: u64) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::SnapshotCount(content_id))
+            .unwrap_or(0)
+    }
+
+    /// The latest reviewer decision on the content, if any
+    pub fn get_review(env: Env, content_id: u64) -> Option<ReviewRecord> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::Review(content_id))
+    }
+
+    /// A replaced version of the content (1 = as originally registered)
+    pub fn get_content_version(env: Env, content_id: u64, version: u32) -> Option<ContentRevision> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::ContentVersion(content_id, version))
+    }
+
+    pub fn get_content_version_count(env: Env, content_id: u64) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::VersionCount(content_id))
+            .unwrap_or(0)
+    }
+
+    pub fn get_variants(env: Env, content_id: u64) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::ContentVariants(content_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Highest click-through rate among the content and its variants; ties keep the earlier id
+    pub fn get_best_variant(env: Env, content_id: u64) -> Option<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut best_ctr = env
+            .storage()
+            .persistent()
+            .get::<DataKey, ContentPerformance>(&DataKey::Performance(content_id))?
+            .click_through_rate;
+        let mut best_id = content_id;
+
+        let variants: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ContentVariants(content_id))
+            .unwrap_or(Vec::new(&env));
+        for variant_id in variants.iter() {
+            if let Some(perf) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, ContentPerformance>(&DataKey::Performance(variant_id))
+            {
+                if perf.click_through_rate > best_ctr {
+                    best_ctr = perf.click_through_rate;
+                    best_id = variant_id;
+                }
+            }
+        }
+        Some(best_id)
+    }
+
+    pub fn is_approved(env: Env, content_id: u64) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        if let Some(content) = env
+            .storage()
+            .persistent()
+            .get::<DataKey, AdContent>(&DataKey::Content(content_id))
+        {
+            matches!(content.status, ContentStatus::Approved) && !Self::_is_expired(&env, &content)
+        } else {
+            false
+        }
+    }
+
+    pub fn get_nonce(env: Env) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .get(&DataKey::ContentNonce)
+            .unwrap_or(0)
+    }
+
+    /// Hand the admin role to another address, such as the governance DAO
+    pub fn set_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.events().publish(
+            (symbol_short!("admin"), symbol_short!("changed")),
+            (admin, new_admin),
+        );
+        Ok(())
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn get_flag_threshold(env: Env) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::FlagThreshold).unwrap_or(5)
+    }
+
+    pub fn set_flag_threshold(env: Env, admin: Address, threshold: u32) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::FlagThreshold, &threshold);
+        Ok(())
+    }
+
+    /// Flag weighting and rate limit (admin only); bps values are capped at
+    /// 10000 and at least one flag per day must be allowed
+    pub fn set_reporter_policy(env: Env, admin: Address, policy: ReporterPolicy) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        if policy.new_reporter_weight_bps > BPS || policy.min_accuracy_bps > BPS || policy.max_flags_per_day == 0 {
+            return Err(AdRegistryError::InvalidContent);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::ReporterPolicy, &policy);
+        Ok(())
+    }
+
+    pub fn get_reporter_policy(env: Env) -> ReporterPolicy {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_reporter_policy(&env)
+    }
+
+    pub fn get_reporter_stats(env: Env, reporter: Address) -> ReporterStats {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_reporter_stats(&env, &reporter)
+    }
+
+    /// Summed weight of a content's standing flags, in bps of a full flag
+    pub fn get_flag_weight(env: Env, content_id: u64) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_content_flag_weight(&env, content_id)
+    }
+
+    /// Minimum seconds between two snapshots of the same content (default one day)
+    pub fn set_snapshot_interval(env: Env, admin: Address, interval: u64) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::SnapshotInterval, &interval);
+        Ok(())
+    }
+
+    /// Size bounds for one format, overriding the global min/max (admin only)
+    pub fn set_format_limits(env: Env, admin: Address, format: ContentFormat, min_size: u64, max_size: u64) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        if min_size == 0 || min_size > max_size {
+            return Err(AdRegistryError::InvalidContent);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::FormatLimits(format), &SizeLimits { min_size, max_size });
+        Ok(())
+    }
+
+    /// Size bounds that apply to a format, after falling back to the globals
+    pub fn get_format_limits(env: Env, format: ContentFormat) -> SizeLimits {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_size_limits(&env, &format)
+    }
+
+    pub fn add_tracker(env: Env, admin: Address, tracker: Address) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        let _ttl_key = DataKey::Tracker(tracker);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Ok(())
+    }
+
+    pub fn remove_tracker(env: Env, admin: Address, tracker: Address) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().persistent().remove(&DataKey::Tracker(tracker));
+        Ok(())
+    }
+
+    /// Let any signer track views/clicks (test networks only; off by default)
+    pub fn set_open_tracking(env: Env, admin: Address, open: bool) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::OpenTracking, &open);
+        Ok(())
+    }
+
+    pub fn is_tracker(env: Env, tracker: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().has(&DataKey::Tracker(tracker))
+    }
+
+    pub fn add_reviewer(env: Env, admin: Address, reviewer: Address) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        let _ttl_key = DataKey::Reviewer(reviewer);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Ok(())
+    }
+
+    pub fn remove_reviewer(env: Env, admin: Address, reviewer: Address) -> Result<(), AdRegistryError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().persistent().remove(&DataKey::Reviewer(reviewer));
+        Ok(())
+    }
+
+    pub fn is_reviewer(env: Env, reviewer: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().has(&DataKey::Reviewer(reviewer))
+    }
+
+    // ============================================================
+    // Internal Helpers
+    // ============================================================
+
+    fn _require_admin(env: &Env, admin: &Address) -> Result<(), AdRegistryError> {
+        admin.require_auth();
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(AdRegistryError::NotInitialized)?;
+        if *admin != stored_admin {
+            return Err(AdRegistryError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn _reporter_policy(env: &Env) -> ReporterPolicy {
+        env.storage()
+            .instance()
+            .get(&DataKey::ReporterPolicy)
+            .unwrap_or(ReporterPolicy {
+                new_reporter_weight_bps: 2_000,
+                min_reviewed_flags: 3,
+                min_accuracy_bps: 5_000,
+                max_flags_per_day: 10,
+            })
+    }
+
+    fn _reporter_stats(env: &Env, reporter: &Address) -> ReporterStats {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ReporterStats(reporter.clone()))
+            .unwrap_or(ReporterStats {
+                flags_submitted: 0,
+                flags_verified: 0,
+                flags_dismissed: 0,
+                window_start: 0,
+                window_flags: 0,
+            })
+    }
+
+    fn _save_reporter_stats(env: &Env, reporter: &Address, stats: &ReporterStats) {
+        let _ttl_key = DataKey::ReporterStats(reporter.clone());
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, stats);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Weight of a new flag from a reporter with these stats, in bps
+    fn _flag_weight(stats: &ReporterStats, policy: &ReporterPolicy) -> u32 {
+        let reviewed = stats.flags_verified + stats.flags_dismissed;
+        if reviewed < policy.min_reviewed_flags || reviewed == 0 {
+            return policy.new_reporter_weight_bps;
+        }
+        let accuracy = (stats.flags_verified as u64 * BPS as u64 / reviewed as u64) as u32;
+        if accuracy < policy.min_accuracy_bps {
+            0
+        } else {
+            accuracy
+        }
+    }
+
+    /// Change a content's status, keeping its owner's approved count for the
+    /// campaign in step. Callers still save the content.
+    fn _set_status(env: &Env, content: &mut AdContent, status: ContentStatus) {
+        let was_approved = matches!(content.status, ContentStatus::Approved);
+        let is_approved = matches!(status, ContentStatus::Approved);
+        content.status = status;
+        if was_approved == is_approved {
+            return;
+        }
+        let _ttl_key = DataKey::ApprovedCount(content.campaign_id, content.owner.clone());
+        let count: u32 = env.storage().persistent().get(&_ttl_key).unwrap_or(0);
+        let count = if is_approved { count + 1 } else { count.saturating_sub(1) };
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &count);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    fn _content_flag_weight(env: &Env, content_id: u64) -> u64 {
+        env.storage().persistent().get(&DataKey::FlagWeight(content_id)).unwrap_or(0)
+    }
+
+    fn _set_content_flag_weight(env: &Env, content_id: u64, weight: u64) {
+        let _ttl_key = DataKey::FlagWeight(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &weight);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// FlagThreshold expressed as a summed flag weight
+    fn _threshold_weight(env: &Env) -> u64 {
+        let threshold: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::FlagThreshold)
+            .unwrap_or(5);
+        threshold as u64 * BPS as u64
+    }
+
+    /// Trackers sign every view/click; with open tracking any signer counts
+    fn _require_tracker(env: &Env, tracker: &Address) -> Result<(), AdRegistryError> {
+        tracker.require_auth();
+        let open: bool = env.storage().instance().get(&DataKey::OpenTracking).unwrap_or(false);
+        if !open && !env.storage().persistent().has(&DataKey::Tracker(tracker.clone())) {
+            return Err(AdRegistryError::UnauthorizedTracker);
+        }
+        Ok(())
+    }
+
+    fn _load_content(env: &Env, content_id: u64) -> Result<AdContent, AdRegistryError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Content(content_id))
+            .ok_or(AdRegistryError::NotFound)
+    }
+
+    /// Per-format limits when set, else the global MinContentSize/MaxContentSize
+    fn _size_limits(env: &Env, format: &ContentFormat) -> SizeLimits {
+        if let Some(limits) = env.storage().instance().get(&DataKey::FormatLimits(format.clone())) {
+            return limits;
+        }
+        SizeLimits {
+            min_size: env
+                .storage()
+                .instance()
+                .get(&DataKey::MinContentSize)
+                .unwrap_or(100),
+            max_size: env
+                .storage()
+                .instance()
+                .get(&DataKey::MaxContentSize)
+                .unwrap_or(10_485_760),
+        }
+    }
+
+    fn _validate_ipfs_hash(ipfs_hash: &String) -> Result<(), AdRegistryError> {
+        if ipfs_hash.len() < MIN_IPFS_HASH_LEN || ipfs_hash.len() > MAX_IPFS_HASH_LEN {
+            return Err(AdRegistryError::InvalidContent);
+        }
+        Ok(())
+    }
+
+    fn _validate_metadata(metadata: &ContentMetadata) -> Result<(), AdRegistryError> {
+        if metadata.landing_url.is_empty() {
+            return Err(AdRegistryError::InvalidMetadata);
+        }
+        Ok(())
+    }
+
+    fn _emit_status(env: &Env, content_id: u64, old_status: ContentStatus, new_status: ContentStatus) {
+        env.events().publish(
+            (symbol_short!("content"), symbol_short!("status")),
+            (content_id, old_status, new_status),
+        );
+    }
+
+    /// Powers of ten from 10 up
+    fn _is_view_milestone(total_views: u64) -> bool {
+        let mut milestone = 10u64;
+        while milestone < total_views {
+            milestone = match milestone.checked_mul(10) {
+                Some(next) => next,
+                None => return false,
+            };
+        }
+        milestone == total_views
+    }
+
+    fn _is_expired(env: &Env, content: &AdContent) -> bool {
+        content.expires_at != 0 && env.ledger().timestamp() >= content.expires_at
+    }
+
+    fn _update_ctr(perf: &mut ContentPerformance) {
+        // Unattributed clicks can outnumber views; CTR stays within 100%
+        if let Some(ctr) = (perf.total_clicks * 10_000).checked_div(perf.total_views) {
+            perf.click_through_rate = ctr.min(10_000);
+        }
+    }
+
+    /// Walk the campaign index from the saved cursor, suspending or restoring
+    /// each entry. Switching direction restarts from the beginning. Returns
+    /// (entries processed, contents changed).
+    fn _sweep_campaign(env: &Env, campaign_id: u64, suspend: bool, max_items: Option<u32>) -> (u32, u32) {
+        let mode_key = DataKey::CampaignSuspended(campaign_id);
+        let cursor_key = DataKey::CampaignCursor(campaign_id);
+        let suspended: bool = env.storage().persistent().get(&mode_key).unwrap_or(false);
+        let mut cursor: u32 = if suspended == suspend {
+            env.storage().persistent().get(&cursor_key).unwrap_or(0)
+        } else {
+            0
+        };
+
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CampaignContents(campaign_id))
+            .unwrap_or(Vec::new(env));
+        let end = cursor
+            .saturating_add(max_items.unwrap_or(MAX_LIST_LIMIT).min(MAX_LIST_LIMIT))
+            .min(ids.len());
+        let processed = end.saturating_sub(cursor);
+        let mut changed = 0u32;
+        let now = env.ledger().timestamp();
+
+        while cursor < end {
+            let content_id = ids.get(cursor).unwrap();
+            cursor += 1;
+            let mut content: AdContent = match env.storage().persistent().get(&DataKey::Content(content_id)) {
+                Some(content) => content,
+                None => continue,
+            };
+            let hold_key = DataKey::CampaignHold(content_id);
+            if suspend {
+                if matches!(content.status, ContentStatus::Archived) || env.storage().persistent().has(&hold_key) {
+                    continue;
+                }
+                env.storage().persistent().set(&hold_key, &content.status);
+                env.storage()
+                    .persistent()
+                    .extend_ttl(&hold_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+                Self::_set_status(env, &mut content, ContentStatus::Suspended);
+            } else {
+                let previous: ContentStatus = match env.storage().persistent().get(&hold_key) {
+                    Some(previous) => previous,
+                    None => continue,
+                };
+                env.storage().persistent().remove(&hold_key);
+                // Content archived by its owner meanwhile stays archived
+                if !matches!(content.status, ContentStatus::Suspended) {
+                    continue;
+                }
+                // Content flagged past the threshold meanwhile stays suspended
+                // until enough flags are dismissed
+                if Self::_content_flag_weight(env, content_id) >= Self::_threshold_weight(env) {
+                    if !matches!(previous, ContentStatus::Suspended) {
+                        let _ttl_key = DataKey::PreSuspendStatus(content_id);
+                        env.storage().persistent().set(&_ttl_key, &previous);
+                        env.storage()
+                            .persistent()
+                            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+                    }
+                    continue;
+                }
+                Self::_set_status(env, &mut content, previous);
+            }
+            content.updated_at = now;
+            let _ttl_key = DataKey::Content(content_id);
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &content);
+            env.storage()
+                .persistent()
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            changed += 1;
+        }
+
+        env.storage().persistent().set(&mode_key, &suspend);
+        env.storage()
+            .persistent()
+            .extend_ttl(&mode_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage().persistent().set(&cursor_key, &cursor);
+        env.storage()
+            .persistent()
+            .extend_ttl(&cursor_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        (processed, changed)
+    }
+
+    /// Validate and store a new content entry with fresh performance counters
+    fn _store_content(
+        env: &Env,
+        owner: Address,
+        campaign_id: u64,
+        ipfs_hash: String,
+        format: ContentFormat,
+        size: u64,
+        metadata: ContentMetadata,
+        expires_at: u64,
+    ) -> Result<u64, AdRegistryError> {
+        let limits = Self::_size_limits(env, &format);
+        if size < limits.min_size || size > limits.max_size {
+            return Err(AdRegistryError::InvalidContent);
+        }
+        Self::_validate_ipfs_hash(&ipfs_hash)?;
+        Self::_validate_metadata(&metadata)?;
+        if expires_at != 0 && expires_at <= env.ledger().timestamp() {
+            return Err(AdRegistryError::InvalidContent);
+        }
+
+        // The first registrant claims the campaign so nobody else can grow its index
+        let _ttl_key = DataKey::CampaignOwner(campaign_id);
+        match env.storage().persistent().get::<DataKey, Address>(&_ttl_key) {
+            Some(campaign_owner) if campaign_owner != owner => return Err(AdRegistryError::Unauthorized),
+            Some(_) => {}
+            None => env.storage().persistent().set(&_ttl_key, &owner),
+        }
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let nonce: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ContentNonce)
+            .unwrap_or(0);
+        let content_id = nonce + 1;
+
+        let content = AdContent {
+            campaign_id,
+            owner,
+            ipfs_hash,
+            format,
+            size,
+            status: ContentStatus::Pending,
+            created_at: env.ledger().timestamp(),
+            updated_at: env.ledger().timestamp(),
+            flags_count: 0,
+            expires_at,
+        };
+
+        let performance = ContentPerformance {
+            total_views: 0,
+            total_clicks: 0,
+            unique_viewers: 0,
+            click_through_rate: 0,
+            last_shown: 0,
+        };
+
+        let _ttl_key = DataKey::Content(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &content);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::Metadata(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &metadata);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::Performance(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &performance);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .set(&DataKey::ContentNonce, &content_id);
+
+        let _ttl_key = DataKey::CampaignContents(campaign_id);
+        let mut campaign_contents: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&_ttl_key)
+            .unwrap_or(Vec::new(env));
+        campaign_contents.push_back(content_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &campaign_contents);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("register"), symbol_short!("content")),
+            (content_id, campaign_id),
+        );
+
+        Ok(content_id)
     }
 }
 
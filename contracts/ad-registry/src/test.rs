@@ -1,6 +1,6 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, vec, String};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, Address, BytesN, Env, IntoVal, vec, String};
 
 #[test]
 fn test_initialize() {
@@ -16,7 +16,6 @@ fn test_initialize() {
 }
 
 #[test]
-#[should_panic(expected = "already initialized")]
 fn test_initialize_twice() {
     let env = Env::default();
     env.mock_all_auths();
@@ -27,7 +26,7 @@ fn test_initialize_twice() {
     let admin = Address::generate(&env);
 
     client.initialize(&admin);
-    client.initialize(&admin);
+    assert_eq!(client.try_initialize(&admin), Err(Ok(AdRegistryError::AlreadyInitialized)));
 }
 
 #[test]
@@ -43,3 +42,1153 @@ fn test_initialize_non_admin_fails() {
     // This should panic because admin didn't authorize it and we haven't mocked it
     client.initialize(&admin);
 }
+
+// ============================================================
+// Content Ownership
+// ============================================================
+
+fn setup(env: &Env) -> (AdRegistryContractClient<'_>, Address) {
+    env.mock_all_auths();
+    let client = AdRegistryContractClient::new(env, &env.register(AdRegistryContract, ()));
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (client, admin)
+}
+
+/// Count every reporter as a full flag, for tests of the suspension mechanics
+fn full_weight_flags(client: &AdRegistryContractClient, admin: &Address) {
+    client.set_reporter_policy(
+        admin,
+        &ReporterPolicy { new_reporter_weight_bps: 10_000, ..client.get_reporter_policy() },
+    );
+}
+
+fn register(env: &Env, client: &AdRegistryContractClient, owner: &Address, campaign_id: u64) -> u64 {
+    client.register_content(
+        owner,
+        &campaign_id,
+        &String::from_str(env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+        &ContentFormat::Image,
+        &50_000,
+        &String::from_str(env, "Summer sale"),
+        &String::from_str(env, "Everything must go"),
+        &String::from_str(env, "Shop now"),
+        &String::from_str(env, "https://example.com"),
+        &0,
+    )
+}
+
+#[test]
+fn test_register_content_records_owner() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owner = Address::generate(&env);
+
+    let content_id = register(&env, &client, &owner, 1);
+    // The owner signed the registration
+    assert!(env.auths().iter().any(|(address, _)| *address == owner));
+    assert_eq!(client.get_content(&content_id).unwrap().owner, owner);
+
+    client.archive_content(&owner, &content_id);
+    assert!(matches!(client.get_content(&content_id).unwrap().status, ContentStatus::Archived));
+}
+
+#[test]
+#[should_panic]
+fn test_register_content_requires_owner_auth() {
+    let env = Env::default();
+    let client = AdRegistryContractClient::new(&env, &env.register(AdRegistryContract, ()));
+    let admin = Address::generate(&env);
+    client.mock_all_auths().initialize(&admin);
+
+    register(&env, &client, &Address::generate(&env), 1);
+}
+
+#[test]
+fn test_non_owner_cannot_archive() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let content_id = register(&env, &client, &Address::generate(&env), 1);
+
+    assert_eq!(
+        client.try_archive_content(&Address::generate(&env), &content_id),
+        Err(Ok(AdRegistryError::Unauthorized))
+    );
+    assert_eq!(client.try_archive_content(&Address::generate(&env), &99), Err(Ok(AdRegistryError::NotFound)));
+}
+
+#[test]
+fn test_owner_updates_metadata() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owner = Address::generate(&env);
+    let content_id = register(&env, &client, &owner, 1);
+
+    client.update_metadata(
+        &owner,
+        &content_id,
+        &String::from_str(&env, "Winter sale"),
+        &String::from_str(&env, "New stock"),
+        &String::from_str(&env, "Browse"),
+        &String::from_str(&env, "https://example.com/winter"),
+    );
+    let metadata = client.get_metadata(&content_id).unwrap();
+    assert_eq!(metadata.title, String::from_str(&env, "Winter sale"));
+    assert_eq!(metadata.landing_url, String::from_str(&env, "https://example.com/winter"));
+
+    let stranger = Address::generate(&env);
+    let empty = String::from_str(&env, "");
+    assert_eq!(
+        client.try_update_metadata(&stranger, &content_id, &empty, &empty, &empty, &empty),
+        Err(Ok(AdRegistryError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_set_admin_hands_over_role() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let dao = Address::generate(&env);
+
+    assert_eq!(client.try_set_admin(&dao, &dao), Err(Ok(AdRegistryError::Unauthorized)));
+    client.set_admin(&admin, &dao);
+    assert_eq!(client.get_admin(), dao);
+    assert_eq!(client.try_set_flag_threshold(&admin, &9), Err(Ok(AdRegistryError::Unauthorized)));
+    client.set_flag_threshold(&dao, &9);
+    assert_eq!(client.get_flag_threshold(), 9);
+}
+
+// ============================================================
+// Campaign Index
+// ============================================================
+
+#[test]
+fn test_campaign_contents_pagination() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+
+    let mut first_campaign = vec![&env];
+    for i in 0..5u64 {
+        env.ledger().set_timestamp(100 * i);
+        first_campaign.push_back(register(&env, &client, &owner, 1));
+        register(&env, &client, &owner, 2);
+    }
+    client.update_status(&admin, &first_campaign.get(1).unwrap(), &ContentStatus::Rejected);
+    client.archive_content(&owner, &first_campaign.get(2).unwrap());
+
+    assert_eq!(client.get_campaign_content_count(&1), 5);
+    assert_eq!(client.get_campaign_content_count(&2), 5);
+    assert_eq!(client.get_campaign_content_count(&3), 0);
+
+    // Rejected and archived content stays listed, in registration order
+    assert_eq!(client.get_campaign_contents(&1, &0, &10), first_campaign);
+    assert_eq!(client.get_campaign_contents(&1, &1, &2), first_campaign.slice(1..3));
+    assert_eq!(client.get_campaign_contents(&1, &4, &10), first_campaign.slice(4..5));
+    assert_eq!(client.get_campaign_contents(&1, &5, &10).len(), 0);
+    assert_eq!(client.get_campaign_contents(&1, &1, &u32::MAX).len(), 4);
+    assert_eq!(client.get_campaign_contents(&2, &0, &10), vec![&env, 2u64, 4, 6, 8, 10]);
+}
+
+#[test]
+fn test_only_campaign_owner_adds_contents() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owner = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let text = String::from_str(&env, "Summer sale");
+    let hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+
+    assert_eq!(client.get_campaign_owner(&1), None);
+    let content_id = register(&env, &client, &owner, 1);
+    assert_eq!(client.get_campaign_owner(&1), Some(owner.clone()));
+    assert_eq!(
+        client.try_register_content(&stranger, &1, &hash, &ContentFormat::Text, &500, &text, &text, &text, &text, &0),
+        Err(Ok(AdRegistryError::Unauthorized))
+    );
+    assert_eq!(client.get_campaign_contents(&1, &0, &10), vec![&env, content_id]);
+
+    // Other campaigns are still open to claim
+    register(&env, &client, &stranger, 2);
+    assert_eq!(client.get_campaign_owner(&2), Some(stranger));
+}
+
+#[test]
+fn test_approved_count_follows_status_changes() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+    let first = register(&env, &client, &owner, 1);
+    let second = register(&env, &client, &owner, 1);
+    let foreign = register(&env, &client, &other, 2);
+    assert_eq!(client.get_approved_count(&1, &owner), 0);
+
+    client.update_status(&admin, &first, &ContentStatus::Approved);
+    client.update_status(&admin, &second, &ContentStatus::Approved);
+    client.update_status(&admin, &foreign, &ContentStatus::Approved);
+    // Re-approving approved content doesn't count twice
+    client.update_status(&admin, &first, &ContentStatus::Approved);
+    assert_eq!(client.get_approved_count(&1, &owner), 2);
+    assert_eq!(client.get_approved_count(&2, &other), 1);
+    assert_eq!(client.get_approved_count(&2, &owner), 0);
+
+    client.archive_content(&owner, &second);
+    assert_eq!(client.get_approved_count(&1, &owner), 1);
+
+    // A campaign suspension takes the approval away until the restore
+    client.suspend_campaign(&admin, &1, &None);
+    assert_eq!(client.get_approved_count(&1, &owner), 0);
+    assert_eq!(client.get_approved_count(&2, &other), 1);
+    client.restore_campaign(&admin, &1, &None);
+    assert_eq!(client.get_approved_count(&1, &owner), 1);
+}
+
+// ============================================================
+// Variants
+// ============================================================
+
+fn variant(env: &Env, client: &AdRegistryContractClient, owner: &Address, parent_id: u64) -> u64 {
+    client.create_variant(
+        owner,
+        &parent_id,
+        &String::from_str(env, "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o"),
+        &String::from_str(env, "Summer sale, B"),
+        &String::from_str(env, "Everything must go"),
+        &String::from_str(env, "Buy today"),
+        &String::from_str(env, "https://example.com"),
+    )
+}
+
+#[test]
+fn test_best_variant_follows_click_through_rate() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let parent_id = register(&env, &client, &owner, 9);
+    let variant_id = variant(&env, &client, &owner, parent_id);
+
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("content"), symbol_short!("variant")).into_val(&env),
+                (parent_id, variant_id).into_val(&env),
+            ),
+        ]
+    );
+    let stored = client.get_content(&variant_id).unwrap();
+    assert_eq!(stored.campaign_id, 9);
+    assert_eq!(client.get_variants(&parent_id), vec![&env, variant_id]);
+    assert_eq!(client.get_campaign_contents(&9, &0, &10), vec![&env, parent_id, variant_id]);
+
+    client.update_status(&admin, &parent_id, &ContentStatus::Approved);
+    client.update_status(&admin, &variant_id, &ContentStatus::Approved);
+    assert_eq!(client.get_best_variant(&parent_id), Some(parent_id));
+
+    let tracker = Address::generate(&env);
+    client.add_tracker(&admin, &tracker);
+    for i in 0..4u8 {
+        client.track_view(&tracker, &parent_id, &viewer(&env, i));
+        client.track_view(&tracker, &variant_id, &viewer(&env, i));
+    }
+    client.track_click(&tracker, &parent_id, &None);
+    assert_eq!(client.get_best_variant(&parent_id), Some(parent_id));
+
+    client.track_click(&tracker, &variant_id, &None);
+    client.track_click(&tracker, &variant_id, &None);
+    assert_eq!(client.get_best_variant(&parent_id), Some(variant_id));
+    assert_eq!(client.get_best_variant(&99), None);
+}
+
+#[test]
+fn test_variant_of_rejected_content() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let parent_id = register(&env, &client, &owner, 9);
+    client.update_status(&admin, &parent_id, &ContentStatus::Rejected);
+
+    let empty = String::from_str(&env, "");
+    assert_eq!(
+        client.try_create_variant(&owner, &parent_id, &empty, &empty, &empty, &empty, &empty),
+        Err(Ok(AdRegistryError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_variant_requires_parent_owner() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let parent_id = register(&env, &client, &Address::generate(&env), 9);
+
+    let empty = String::from_str(&env, "");
+    assert_eq!(
+        client.try_create_variant(&Address::generate(&env), &parent_id, &empty, &empty, &empty, &empty, &empty),
+        Err(Ok(AdRegistryError::Unauthorized))
+    );
+}
+
+// ============================================================
+// Tracking
+// ============================================================
+
+fn viewer(env: &Env, seed: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[seed; 32])
+}
+
+fn approved_content(env: &Env, client: &AdRegistryContractClient, admin: &Address) -> u64 {
+    let owner = client.get_campaign_owner(&1).unwrap_or_else(|| Address::generate(env));
+    let content_id = register(env, client, &owner, 1);
+    client.update_status(admin, &content_id, &ContentStatus::Approved);
+    content_id
+}
+
+#[test]
+fn test_unique_viewers_count_distinct_hashes() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let tracker = Address::generate(&env);
+    client.add_tracker(&admin, &tracker);
+    assert!(client.is_tracker(&tracker));
+    let content_id = approved_content(&env, &client, &admin);
+
+    client.track_view(&tracker, &content_id, &viewer(&env, 1));
+    client.track_view(&tracker, &content_id, &viewer(&env, 1));
+    client.track_view(&tracker, &content_id, &viewer(&env, 2));
+    client.track_click(&tracker, &content_id, &None);
+    // The tracker signed the click
+    assert!(env.auths().iter().any(|(address, _)| *address == tracker));
+
+    let perf = client.get_performance(&content_id).unwrap();
+    assert_eq!(perf.total_views, 3);
+    assert_eq!(perf.unique_viewers, 2);
+    assert_eq!(perf.total_clicks, 1);
+    assert_eq!(perf.click_through_rate, 3_333);
+}
+
+#[test]
+fn test_click_requires_approved_viewed_content() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let tracker = Address::generate(&env);
+    client.add_tracker(&admin, &tracker);
+    let content_id = approved_content(&env, &client, &admin);
+
+    assert_eq!(client.try_track_click(&tracker, &content_id, &None), Err(Ok(AdRegistryError::NoViews)));
+    client.track_view(&tracker, &content_id, &viewer(&env, 1));
+    client.track_click(&tracker, &content_id, &None);
+
+    // Suspended creatives stop accruing engagement
+    client.update_status(&admin, &content_id, &ContentStatus::Suspended);
+    assert_eq!(client.try_track_click(&tracker, &content_id, &None), Err(Ok(AdRegistryError::NotApproved)));
+    client.update_status(&admin, &content_id, &ContentStatus::Rejected);
+    assert_eq!(client.try_track_click(&tracker, &content_id, &None), Err(Ok(AdRegistryError::NotApproved)));
+    assert_eq!(client.get_performance(&content_id).unwrap().total_clicks, 1);
+}
+
+#[test]
+fn test_attributed_clicks_once_per_viewer() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let tracker = Address::generate(&env);
+    client.add_tracker(&admin, &tracker);
+    let content_id = approved_content(&env, &client, &admin);
+    client.track_view(&tracker, &content_id, &viewer(&env, 1));
+
+    client.track_click(&tracker, &content_id, &Some(viewer(&env, 1)));
+    assert_eq!(
+        client.try_track_click(&tracker, &content_id, &Some(viewer(&env, 1))),
+        Err(Ok(AdRegistryError::AlreadyClicked))
+    );
+    // A viewer the tracker never reported can't click
+    assert_eq!(
+        client.try_track_click(&tracker, &content_id, &Some(viewer(&env, 2))),
+        Err(Ok(AdRegistryError::NoViews))
+    );
+
+    // Unattributed clicks can outnumber views, but CTR stays at 100%
+    client.track_click(&tracker, &content_id, &None);
+    let perf = client.get_performance(&content_id).unwrap();
+    assert_eq!(perf.total_clicks, 2);
+    assert_eq!(perf.click_through_rate, 10_000);
+}
+
+#[test]
+fn test_unregistered_tracker_rejected() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let content_id = approved_content(&env, &client, &admin);
+
+    assert_eq!(
+        client.try_track_view(&Address::generate(&env), &content_id, &viewer(&env, 1)),
+        Err(Ok(AdRegistryError::UnauthorizedTracker))
+    );
+}
+
+#[test]
+fn test_track_view_requires_approved_content() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    client.set_open_tracking(&admin, &true);
+    let content_id = register(&env, &client, &Address::generate(&env), 1);
+    let anyone = Address::generate(&env);
+
+    assert_eq!(client.try_track_view(&anyone, &content_id, &viewer(&env, 1)), Err(Ok(AdRegistryError::NotApproved)));
+    assert_eq!(client.try_track_view(&anyone, &99, &viewer(&env, 1)), Err(Ok(AdRegistryError::NotFound)));
+    assert_eq!(client.try_track_click(&anyone, &99, &None), Err(Ok(AdRegistryError::NotFound)));
+}
+
+#[test]
+fn test_removed_tracker_cannot_click() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let tracker = Address::generate(&env);
+    client.add_tracker(&admin, &tracker);
+    let content_id = approved_content(&env, &client, &admin);
+    client.track_view(&tracker, &content_id, &viewer(&env, 1));
+    client.track_click(&tracker, &content_id, &None);
+
+    client.remove_tracker(&admin, &tracker);
+    assert_eq!(client.try_track_click(&tracker, &content_id, &None), Err(Ok(AdRegistryError::UnauthorizedTracker)));
+}
+
+#[test]
+fn test_open_tracking_toggle() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let content_id = approved_content(&env, &client, &admin);
+    let anyone = Address::generate(&env);
+
+    client.set_open_tracking(&admin, &true);
+    client.track_view(&anyone, &content_id, &viewer(&env, 1));
+    client.track_click(&anyone, &content_id, &None);
+    assert_eq!(client.get_performance(&content_id).unwrap().total_clicks, 1);
+
+    client.set_open_tracking(&admin, &false);
+    assert_eq!(client.try_track_click(&anyone, &content_id, &None), Err(Ok(AdRegistryError::UnauthorizedTracker)));
+}
+
+// ============================================================
+// Flag Moderation
+// ============================================================
+
+#[test]
+fn test_dismissed_flag_lifts_auto_suspension() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    full_weight_flags(&client, &admin);
+    client.set_flag_threshold(&admin, &2);
+    let content_id = approved_content(&env, &client, &admin);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+
+    client.flag_content(&first, &content_id, &String::from_str(&env, "misleading"));
+    client.flag_content(&second, &content_id, &String::from_str(&env, "spam"));
+    assert!(matches!(client.get_content(&content_id).unwrap().status, ContentStatus::Suspended));
+
+    let flags = client.get_flags(&content_id, &0, &10);
+    assert_eq!(flags.len(), 2);
+    let (reporter, record) = flags.get(0).unwrap();
+    assert_eq!(reporter, first);
+    assert_eq!(record.reason, String::from_str(&env, "misleading"));
+    assert_eq!(client.get_flags(&content_id, &1, &10).get(0).unwrap().0, second);
+
+    client.verify_flag(&admin, &content_id, &second, &true);
+    assert!(matches!(client.get_content(&content_id).unwrap().status, ContentStatus::Suspended));
+
+    client.verify_flag(&admin, &content_id, &first, &false);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("content"), symbol_short!("unflagged")).into_val(&env),
+                (content_id, first.clone(), 1u32).into_val(&env),
+            ),
+        ]
+    );
+    let content = client.get_content(&content_id).unwrap();
+    assert_eq!(content.flags_count, 1);
+    assert!(matches!(content.status, ContentStatus::Approved));
+
+    let flags = client.get_flags(&content_id, &0, &10);
+    assert!(flags.get(0).unwrap().1.dismissed);
+    assert!(flags.get(1).unwrap().1.verified);
+    assert_eq!(
+        client.try_verify_flag(&admin, &content_id, &first, &true),
+        Err(Ok(AdRegistryError::FlagAlreadyReviewed))
+    );
+    assert_eq!(
+        client.try_verify_flag(&admin, &content_id, &Address::generate(&env), &true),
+        Err(Ok(AdRegistryError::NotFound))
+    );
+}
+
+#[test]
+fn test_manual_status_overrides_restore() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    client.set_flag_threshold(&admin, &1);
+    let content_id = approved_content(&env, &client, &admin);
+    let reporter = Address::generate(&env);
+
+    client.flag_content(&reporter, &content_id, &String::from_str(&env, "spam"));
+    client.update_status(&admin, &content_id, &ContentStatus::Rejected);
+    client.verify_flag(&admin, &content_id, &reporter, &false);
+    assert!(matches!(client.get_content(&content_id).unwrap().status, ContentStatus::Rejected));
+}
+
+#[test]
+fn test_reporter_flags_once() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let content_id = approved_content(&env, &client, &admin);
+    let reporter = Address::generate(&env);
+    let reason = String::from_str(&env, "spam");
+
+    client.flag_content(&reporter, &content_id, &reason);
+    assert_eq!(client.try_flag_content(&reporter, &content_id, &reason), Err(Ok(AdRegistryError::AlreadyExists)));
+}
+
+#[test]
+fn test_flag_rejections() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owner = Address::generate(&env);
+    let content_id = register(&env, &client, &owner, 1);
+    let reason = String::from_str(&env, "spam");
+
+    assert_eq!(
+        client.try_flag_content(&owner, &content_id, &reason),
+        Err(Ok(AdRegistryError::CannotFlagOwnContent))
+    );
+    assert_eq!(client.try_flag_content(&Address::generate(&env), &99, &reason), Err(Ok(AdRegistryError::NotFound)));
+
+    client.archive_content(&owner, &content_id);
+    assert_eq!(
+        client.try_flag_content(&Address::generate(&env), &content_id, &reason),
+        Err(Ok(AdRegistryError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_low_reputation_reporter_cannot_suspend_alone() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let reporter = Address::generate(&env);
+    let reason = String::from_str(&env, "spam");
+
+    // Three dismissed flags drop the reporter below the accuracy floor
+    for _ in 0..3 {
+        let content_id = approved_content(&env, &client, &admin);
+        client.flag_content(&reporter, &content_id, &reason);
+        client.verify_flag(&admin, &content_id, &reporter, &false);
+    }
+    let stats = client.get_reporter_stats(&reporter);
+    assert_eq!(stats.flags_submitted, 3);
+    assert_eq!(stats.flags_verified, 0);
+    assert_eq!(stats.flags_dismissed, 3);
+
+    client.set_flag_threshold(&admin, &1);
+    let content_id = approved_content(&env, &client, &admin);
+    client.flag_content(&reporter, &content_id, &reason);
+    let content = client.get_content(&content_id).unwrap();
+    assert_eq!(content.flags_count, 1);
+    assert_eq!(client.get_flag_weight(&content_id), 0);
+    assert!(matches!(content.status, ContentStatus::Approved));
+    assert_eq!(client.get_flags(&content_id, &0, &1).get(0).unwrap().1.weight_bps, 0);
+
+    // A reporter with no history counts at the default 20% of a flag
+    client.flag_content(&Address::generate(&env), &content_id, &reason);
+    assert_eq!(client.get_flag_weight(&content_id), 2_000);
+    assert!(matches!(client.get_content(&content_id).unwrap().status, ContentStatus::Approved));
+}
+
+#[test]
+fn test_fresh_reporters_cannot_suspend_content() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let content_id = approved_content(&env, &client, &admin);
+
+    // Five fresh reporters against the default threshold of five flags
+    for _ in 0..5 {
+        client.flag_content(&Address::generate(&env), &content_id, &String::from_str(&env, "spam"));
+    }
+    let content = client.get_content(&content_id).unwrap();
+    assert_eq!(content.flags_count, 5);
+    assert_eq!(client.get_flag_weight(&content_id), 10_000);
+    assert!(matches!(content.status, ContentStatus::Approved));
+}
+
+#[test]
+fn test_reporter_policy_weights_and_rate_limit() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let reporter = Address::generate(&env);
+    let reason = String::from_str(&env, "spam");
+    let policy = ReporterPolicy {
+        new_reporter_weight_bps: 5_000,
+        min_reviewed_flags: 2,
+        min_accuracy_bps: 5_000,
+        max_flags_per_day: 3,
+    };
+    assert_eq!(client.try_set_reporter_policy(&reporter, &policy), Err(Ok(AdRegistryError::Unauthorized)));
+    let invalid = ReporterPolicy { max_flags_per_day: 0, ..policy.clone() };
+    assert_eq!(client.try_set_reporter_policy(&admin, &invalid), Err(Ok(AdRegistryError::InvalidContent)));
+    client.set_reporter_policy(&admin, &policy);
+    assert_eq!(client.get_reporter_policy().max_flags_per_day, 3);
+
+    let first = approved_content(&env, &client, &admin);
+    let second = approved_content(&env, &client, &admin);
+    client.flag_content(&reporter, &first, &reason);
+    client.flag_content(&reporter, &second, &reason);
+    assert_eq!(client.get_flag_weight(&first), 5_000);
+    client.verify_flag(&admin, &first, &reporter, &true);
+    client.verify_flag(&admin, &second, &reporter, &false);
+
+    // Half the reviewed flags held up: weighted at 50%
+    env.ledger().set_timestamp(3_600);
+    let third = approved_content(&env, &client, &admin);
+    client.flag_content(&reporter, &third, &reason);
+    assert_eq!(client.get_flag_weight(&third), 5_000);
+
+    let fourth = approved_content(&env, &client, &admin);
+    assert_eq!(client.try_flag_content(&reporter, &fourth, &reason), Err(Ok(AdRegistryError::RateLimited)));
+    env.ledger().set_timestamp(86_400);
+    client.flag_content(&reporter, &fourth, &reason);
+    let stats = client.get_reporter_stats(&reporter);
+    assert_eq!(stats.flags_submitted, 4);
+    assert_eq!(stats.window_start, 86_400);
+    assert_eq!(stats.window_flags, 1);
+}
+
+// ============================================================
+// Content Updates
+// ============================================================
+
+#[test]
+fn test_update_content_keeps_versions() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let tracker = Address::generate(&env);
+    client.add_tracker(&admin, &tracker);
+    let owner = Address::generate(&env);
+    env.ledger().set_timestamp(100);
+    let content_id = register(&env, &client, &owner, 1);
+    client.update_status(&admin, &content_id, &ContentStatus::Approved);
+    client.track_view(&tracker, &content_id, &viewer(&env, 1));
+    let original = client.get_content(&content_id).unwrap();
+    let original_metadata = client.get_metadata(&content_id).unwrap();
+
+    env.ledger().set_timestamp(200);
+    let new_hash = String::from_str(&env, "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o");
+    client.update_content(&owner, &content_id, &Some(new_hash.clone()), &None);
+    let content = client.get_content(&content_id).unwrap();
+    assert_eq!(content.ipfs_hash, new_hash);
+    assert_eq!(content.updated_at, 200);
+    assert!(matches!(content.status, ContentStatus::Pending));
+    assert_eq!(client.get_performance(&content_id).unwrap().total_views, 1);
+
+    let first = client.get_content_version(&content_id, &1).unwrap();
+    assert_eq!(first.ipfs_hash, original.ipfs_hash);
+    assert_eq!(first.metadata.title, original_metadata.title);
+    assert_eq!(first.live_from, 100);
+    assert_eq!(first.replaced_at, 200);
+
+    env.ledger().set_timestamp(300);
+    let mut metadata = original_metadata.clone();
+    metadata.title = String::from_str(&env, "Autumn sale");
+    client.update_content(&owner, &content_id, &None, &Some(metadata));
+    assert_eq!(client.get_content_version_count(&content_id), 2);
+    let second = client.get_content_version(&content_id, &2).unwrap();
+    assert_eq!(second.ipfs_hash, new_hash);
+    assert_eq!(second.live_from, 200);
+    assert_eq!(second.replaced_at, 300);
+    assert_eq!(client.get_metadata(&content_id).unwrap().title, String::from_str(&env, "Autumn sale"));
+    assert!(client.get_content_version(&content_id, &3).is_none());
+}
+
+#[test]
+fn test_update_suspended_content_rejected() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let content_id = register(&env, &client, &owner, 1);
+    client.update_status(&admin, &content_id, &ContentStatus::Suspended);
+
+    assert_eq!(
+        client.try_update_content(&owner, &content_id, &Some(String::from_str(&env, "QmNew")), &None),
+        Err(Ok(AdRegistryError::InvalidStatus))
+    );
+    let pending_id = register(&env, &client, &owner, 1);
+    assert_eq!(client.try_update_content(&owner, &pending_id, &None, &None), Err(Ok(AdRegistryError::NothingToUpdate)));
+}
+
+#[test]
+fn test_register_rejects_out_of_range_size() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let text = String::from_str(&env, "x");
+
+    assert_eq!(
+        client.try_register_content(&owner, &1, &hash, &ContentFormat::Text, &10, &text, &text, &text, &text, &0),
+        Err(Ok(AdRegistryError::InvalidContent))
+    );
+    assert_eq!(
+        client.try_update_status(&Address::generate(&env), &1, &ContentStatus::Approved),
+        Err(Ok(AdRegistryError::Unauthorized))
+    );
+    assert_eq!(client.try_update_status(&admin, &1, &ContentStatus::Approved), Err(Ok(AdRegistryError::NotFound)));
+}
+
+// ============================================================
+// Review Workflow
+// ============================================================
+
+#[test]
+fn test_reviewer_approves_pending_content() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let reviewer = Address::generate(&env);
+    client.add_reviewer(&admin, &reviewer);
+    assert!(client.is_reviewer(&reviewer));
+    let owner = Address::generate(&env);
+    let approved_id = register(&env, &client, &owner, 1);
+    let rejected_id = register(&env, &client, &owner, 1);
+
+    env.ledger().set_timestamp(500);
+    client.review_content(&reviewer, &approved_id, &true, &String::from_str(&env, "looks good"));
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("content"), symbol_short!("reviewed")).into_val(&env),
+                (approved_id, reviewer.clone(), true).into_val(&env),
+            ),
+        ]
+    );
+    assert!(client.is_approved(&approved_id));
+    let review = client.get_review(&approved_id).unwrap();
+    assert_eq!(review.reviewer, reviewer);
+    assert!(review.approved);
+    assert_eq!(review.note, String::from_str(&env, "looks good"));
+    assert_eq!(review.reviewed_at, 500);
+
+    client.review_content(&reviewer, &rejected_id, &false, &String::from_str(&env, "blurry"));
+    assert!(matches!(client.get_content(&rejected_id).unwrap().status, ContentStatus::Rejected));
+    assert!(!client.get_review(&rejected_id).unwrap().approved);
+    assert!(client.get_review(&99).is_none());
+
+    // Only Pending content goes through review; the admin handles overrides
+    assert_eq!(
+        client.try_review_content(&reviewer, &approved_id, &false, &String::from_str(&env, "")),
+        Err(Ok(AdRegistryError::InvalidStatus))
+    );
+    client.update_status(&admin, &approved_id, &ContentStatus::Suspended);
+}
+
+#[test]
+fn test_removed_reviewer_cannot_approve() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let reviewer = Address::generate(&env);
+    client.add_reviewer(&admin, &reviewer);
+    let content_id = register(&env, &client, &Address::generate(&env), 1);
+    let note = String::from_str(&env, "ok");
+
+    client.remove_reviewer(&admin, &reviewer);
+    assert!(!client.is_reviewer(&reviewer));
+    assert_eq!(client.try_review_content(&reviewer, &content_id, &true, &note), Err(Ok(AdRegistryError::Unauthorized)));
+    assert_eq!(
+        client.try_review_content(&Address::generate(&env), &content_id, &true, &note),
+        Err(Ok(AdRegistryError::Unauthorized))
+    );
+    assert!(matches!(client.get_content(&content_id).unwrap().status, ContentStatus::Pending));
+    assert!(client.get_review(&content_id).is_none());
+}
+
+// ============================================================
+// Performance Snapshots
+// ============================================================
+
+#[test]
+fn test_snapshots_respect_interval() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let tracker = Address::generate(&env);
+    client.add_tracker(&admin, &tracker);
+    let content_id = approved_content(&env, &client, &admin);
+
+    env.ledger().set_timestamp(1_000);
+    client.track_view(&tracker, &content_id, &viewer(&env, 1));
+    assert_eq!(client.snapshot_performance(&content_id), 0);
+    assert_eq!(client.try_snapshot_performance(&content_id), Err(Ok(AdRegistryError::SnapshotTooSoon)));
+
+    env.ledger().set_timestamp(1_000 + 86_399);
+    assert_eq!(client.try_snapshot_performance(&content_id), Err(Ok(AdRegistryError::SnapshotTooSoon)));
+
+    env.ledger().set_timestamp(1_000 + 86_400);
+    client.track_view(&tracker, &content_id, &viewer(&env, 2));
+    client.track_click(&tracker, &content_id, &None);
+    assert_eq!(client.snapshot_performance(&content_id), 1);
+
+    client.set_snapshot_interval(&admin, &3_600);
+    env.ledger().set_timestamp(1_000 + 86_400 + 3_600);
+    assert_eq!(client.snapshot_performance(&content_id), 2);
+    assert_eq!(client.get_snapshot_count(&content_id), 3);
+
+    let snapshots = client.get_snapshots(&content_id, &0, &10);
+    assert_eq!(snapshots.len(), 3);
+    let first = snapshots.get(0).unwrap();
+    assert_eq!(first.taken_at, 1_000);
+    assert_eq!(first.performance.total_views, 1);
+    assert_eq!(first.performance.total_clicks, 0);
+    let second = snapshots.get(1).unwrap();
+    assert_eq!(second.taken_at, 87_400);
+    assert_eq!(second.performance.total_views, 2);
+    assert_eq!(second.performance.click_through_rate, 5_000);
+
+    assert_eq!(client.get_snapshots(&content_id, &2, &10).get(0).unwrap().taken_at, 91_000);
+    assert_eq!(client.get_snapshots(&content_id, &3, &10).len(), 0);
+    assert_eq!(client.try_snapshot_performance(&99), Err(Ok(AdRegistryError::NotFound)));
+}
+
+// ============================================================
+// Campaign Moderation
+// ============================================================
+
+#[test]
+fn test_suspend_and_restore_campaign_in_batches() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let approved = approved_content(&env, &client, &admin);
+    let owner = client.get_campaign_owner(&1).unwrap();
+    let pending = register(&env, &client, &owner, 1);
+    let archived = register(&env, &client, &owner, 1);
+    client.archive_content(&owner, &archived);
+    let rejected = register(&env, &client, &owner, 1);
+    client.update_status(&admin, &rejected, &ContentStatus::Rejected);
+    let other_campaign = register(&env, &client, &owner, 2);
+
+    assert_eq!(client.suspend_campaign(&admin, &1, &Some(3)), 3);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("campaign"), symbol_short!("suspend")).into_val(&env),
+                (1u64, 2u32).into_val(&env),
+            ),
+        ]
+    );
+    assert!(client.is_campaign_suspended(&1));
+    assert!(matches!(client.get_content(&rejected).unwrap().status, ContentStatus::Rejected));
+    assert_eq!(client.suspend_campaign(&admin, &1, &None), 1);
+    assert_eq!(client.suspend_campaign(&admin, &1, &None), 0);
+
+    assert!(matches!(client.get_content(&approved).unwrap().status, ContentStatus::Suspended));
+    assert!(matches!(client.get_content(&pending).unwrap().status, ContentStatus::Suspended));
+    assert!(matches!(client.get_content(&rejected).unwrap().status, ContentStatus::Suspended));
+    assert!(matches!(client.get_content(&archived).unwrap().status, ContentStatus::Archived));
+    assert!(matches!(client.get_content(&other_campaign).unwrap().status, ContentStatus::Pending));
+
+    assert_eq!(client.restore_campaign(&admin, &1, &None), 4);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("campaign"), symbol_short!("restore")).into_val(&env),
+                (1u64, 3u32).into_val(&env),
+            ),
+        ]
+    );
+    assert!(!client.is_campaign_suspended(&1));
+    assert!(client.is_approved(&approved));
+    assert!(matches!(client.get_content(&pending).unwrap().status, ContentStatus::Pending));
+    assert!(matches!(client.get_content(&rejected).unwrap().status, ContentStatus::Rejected));
+    assert!(matches!(client.get_content(&archived).unwrap().status, ContentStatus::Archived));
+    assert_eq!(client.restore_campaign(&admin, &1, &None), 0);
+}
+
+#[test]
+fn test_campaign_restore_keeps_flag_suspension() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    full_weight_flags(&client, &admin);
+    client.set_flag_threshold(&admin, &1);
+    let flagged = approved_content(&env, &client, &admin);
+    let clean = approved_content(&env, &client, &admin);
+    let reporter = Address::generate(&env);
+    client.flag_content(&reporter, &flagged, &String::from_str(&env, "spam"));
+
+    client.suspend_campaign(&admin, &1, &None);
+    client.restore_campaign(&admin, &1, &None);
+    assert!(matches!(client.get_content(&flagged).unwrap().status, ContentStatus::Suspended));
+    assert!(client.is_approved(&clean));
+
+    // Dismissing the flag mid-suspension defers the restore to restore_campaign
+    client.suspend_campaign(&admin, &1, &None);
+    client.verify_flag(&admin, &flagged, &reporter, &false);
+    assert!(matches!(client.get_content(&flagged).unwrap().status, ContentStatus::Suspended));
+    client.restore_campaign(&admin, &1, &None);
+    assert!(client.is_approved(&flagged));
+
+    // Flagged past the threshold mid-suspension: restore keeps it suspended
+    // and a later dismissal brings back the held status
+    client.suspend_campaign(&admin, &1, &None);
+    client.flag_content(&reporter, &clean, &String::from_str(&env, "spam"));
+    client.restore_campaign(&admin, &1, &None);
+    assert!(matches!(client.get_content(&clean).unwrap().status, ContentStatus::Suspended));
+    client.verify_flag(&admin, &clean, &reporter, &false);
+    assert!(client.is_approved(&clean));
+
+    assert_eq!(
+        client.try_suspend_campaign(&Address::generate(&env), &1, &None),
+        Err(Ok(AdRegistryError::Unauthorized))
+    );
+}
+
+// ============================================================
+// Expiry
+// ============================================================
+
+#[test]
+fn test_content_expires_between_views() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let tracker = Address::generate(&env);
+    client.add_tracker(&admin, &tracker);
+    let owner = Address::generate(&env);
+    let hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let text = String::from_str(&env, "Flash sale");
+    env.ledger().set_timestamp(1_000);
+    let content_id = client.register_content(&owner, &1, &hash, &ContentFormat::Text, &500, &text, &text, &text, &text, &2_000);
+    client.update_status(&admin, &content_id, &ContentStatus::Approved);
+    let variant_id = variant(&env, &client, &owner, content_id);
+    assert_eq!(client.get_content(&variant_id).unwrap().expires_at, 2_000);
+
+    env.ledger().set_timestamp(1_999);
+    client.track_view(&tracker, &content_id, &viewer(&env, 1));
+    assert!(client.is_approved(&content_id));
+    assert_eq!(client.try_archive_expired(&content_id), Err(Ok(AdRegistryError::NotExpired)));
+
+    env.ledger().set_timestamp(2_000);
+    assert_eq!(
+        client.try_track_view(&tracker, &content_id, &viewer(&env, 2)),
+        Err(Ok(AdRegistryError::ContentExpired))
+    );
+    assert!(!client.is_approved(&content_id));
+    // The stored record is untouched until someone archives it
+    assert!(matches!(client.get_content(&content_id).unwrap().status, ContentStatus::Approved));
+    assert_eq!(client.get_performance(&content_id).unwrap().total_views, 1);
+
+    client.archive_expired(&content_id);
+    assert!(matches!(client.get_content(&content_id).unwrap().status, ContentStatus::Archived));
+    assert_eq!(client.try_archive_expired(&content_id), Err(Ok(AdRegistryError::InvalidStatus)));
+
+    // Content without an expiry never lapses, and past expiries are rejected up front
+    let forever = register(&env, &client, &owner, 1);
+    assert_eq!(client.try_archive_expired(&forever), Err(Ok(AdRegistryError::NotExpired)));
+    assert_eq!(
+        client.try_register_content(&owner, &1, &hash, &ContentFormat::Text, &500, &text, &text, &text, &text, &2_000),
+        Err(Ok(AdRegistryError::InvalidContent))
+    );
+}
+
+// ============================================================
+// Content Validation
+// ============================================================
+
+#[test]
+fn test_format_limits_override_globals() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let text = String::from_str(&env, "Summer sale");
+    let url = String::from_str(&env, "https://example.com");
+
+    client.set_format_limits(&admin, &ContentFormat::Text, &10, &4_096);
+    client.set_format_limits(&admin, &ContentFormat::Video, &1_000_000, &52_428_800);
+    let limits = client.get_format_limits(&ContentFormat::Image);
+    assert_eq!((limits.min_size, limits.max_size), (100, 10_485_760));
+
+    // Overrides apply to Text and Video; Image and Native fall back to the globals
+    client.register_content(&owner, &1, &hash, &ContentFormat::Text, &50, &text, &text, &text, &url, &0);
+    client.register_content(&owner, &1, &hash, &ContentFormat::Video, &20_000_000, &text, &text, &text, &url, &0);
+    client.register_content(&owner, &1, &hash, &ContentFormat::Image, &50_000, &text, &text, &text, &url, &0);
+    client.register_content(&owner, &1, &hash, &ContentFormat::Native, &100, &text, &text, &text, &url, &0);
+    assert_eq!(client.get_campaign_content_count(&1), 4);
+
+    assert_eq!(
+        client.try_register_content(&owner, &1, &hash, &ContentFormat::Text, &5_000, &text, &text, &text, &url, &0),
+        Err(Ok(AdRegistryError::InvalidContent))
+    );
+    assert_eq!(
+        client.try_register_content(&owner, &1, &hash, &ContentFormat::Video, &50_000, &text, &text, &text, &url, &0),
+        Err(Ok(AdRegistryError::InvalidContent))
+    );
+    assert_eq!(
+        client.try_register_content(&owner, &1, &hash, &ContentFormat::Image, &20_000_000, &text, &text, &text, &url, &0),
+        Err(Ok(AdRegistryError::InvalidContent))
+    );
+    assert_eq!(
+        client.try_set_format_limits(&admin, &ContentFormat::Native, &500, &100),
+        Err(Ok(AdRegistryError::InvalidContent))
+    );
+}
+
+#[test]
+fn test_register_validates_hash_and_landing_url() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owner = Address::generate(&env);
+    let hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let text = String::from_str(&env, "Summer sale");
+    let url = String::from_str(&env, "https://example.com");
+    let empty = String::from_str(&env, "");
+
+    assert_eq!(
+        client.try_register_content(&owner, &1, &String::from_str(&env, "QmShort"), &ContentFormat::Image, &50_000, &text, &text, &text, &url, &0),
+        Err(Ok(AdRegistryError::InvalidContent))
+    );
+    assert_eq!(
+        client.try_register_content(&owner, &1, &hash, &ContentFormat::Image, &50_000, &text, &text, &text, &empty, &0),
+        Err(Ok(AdRegistryError::InvalidMetadata))
+    );
+
+    let content_id = register(&env, &client, &owner, 1);
+    assert_eq!(
+        client.try_update_content(&owner, &content_id, &Some(String::from_str(&env, "QmShort")), &None),
+        Err(Ok(AdRegistryError::InvalidContent))
+    );
+    assert_eq!(
+        client.try_update_metadata(&owner, &content_id, &text, &text, &text, &empty),
+        Err(Ok(AdRegistryError::InvalidMetadata))
+    );
+}
+
+// ============================================================
+// Events
+// ============================================================
+
+#[test]
+fn test_status_and_archive_events() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let content_id = register(&env, &client, &owner, 1);
+
+    client.update_status(&admin, &content_id, &ContentStatus::Approved);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("content"), symbol_short!("status")).into_val(&env),
+                (content_id, ContentStatus::Pending, ContentStatus::Approved).into_val(&env),
+            ),
+        ]
+    );
+
+    client.archive_content(&owner, &content_id);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("content"), symbol_short!("archive")).into_val(&env),
+                content_id.into_val(&env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_flag_and_auto_suspend_events() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    full_weight_flags(&client, &admin);
+    client.set_flag_threshold(&admin, &2);
+    let content_id = approved_content(&env, &client, &admin);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+
+    client.flag_content(&first, &content_id, &String::from_str(&env, "spam"));
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("content"), symbol_short!("flag")).into_val(&env),
+                (content_id, first.clone(), 1u32).into_val(&env),
+            ),
+        ]
+    );
+
+    client.flag_content(&second, &content_id, &String::from_str(&env, "spam"));
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("content"), symbol_short!("suspend")).into_val(&env),
+                (content_id, 2u32).into_val(&env),
+            ),
+            (
+                client.address.clone(),
+                (symbol_short!("content"), symbol_short!("flag")).into_val(&env),
+                (content_id, second.clone(), 2u32).into_val(&env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_view_milestone_events() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let tracker = Address::generate(&env);
+    client.add_tracker(&admin, &tracker);
+    let content_id = approved_content(&env, &client, &admin);
+    let milestone = |views: u64| {
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("content"), symbol_short!("milestone")).into_val(&env),
+                (content_id, views).into_val(&env),
+            ),
+        ]
+    };
+
+    for i in 1..=100u64 {
+        client.track_view(&tracker, &content_id, &viewer(&env, (i % 7) as u8));
+        let events = env.events().all();
+        match i {
+            10 => assert_eq!(events, milestone(10)),
+            100 => assert_eq!(events, milestone(100)),
+            _ => assert_eq!(events.len(), 0),
+        }
+    }
+}
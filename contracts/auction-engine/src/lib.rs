@@ -5,15 +5,71 @@
 //! - ("auction", "created"): [auction_id: u64, publisher: Address]
 //! - ("bid", "placed"): [auction_id: u64, bidder: Address, amount: i128]
 //! - ("auction", "settle"): [auction_id: u64, winner: Option<Address>, amount: Option<i128>]
+//!   (one per winner; a single event with None values when nothing sold)
+//! - ("bid", "refund"): [auction_id: u64, bidder: Address, amount: i128]
+//! - ("auction", "extended"): [auction_id: u64, end_time: u64]
+//! - ("auction", "cancel"): [auction_id: u64, cancelled_by: Address]
+//! - ("auction", "closed"): [auction_id: u64]
+//! - ("auction", "fee"): [auction_id: u64, fee: i128]
+//! - ("bid", "commit"): [auction_id: u64, bidder: Address, deposit: i128]
+//! - ("bid", "reveal"): [auction_id: u64, bidder: Address, amount: i128]
+//! - ("bid", "slashed"): [auction_id: u64, bidder: Address, deposit: i128]
+//! - ("admin", "changed"): [old_admin: Address, new_admin: Address]
+//! - ("admin", "paused"): [admin: Address]
+//! - ("admin", "unpaused"): [admin: Address]
+//!
+//! Bids are escrowed: place_bid moves the amount into the contract and the
+//! outbid bidder is refunded at once. Settlement pays the publisher from the
+//! escrowed funds; if the auction is cancelled the high bid becomes
+//! claimable through claim_refund. Any account can settle an ended auction.
+//!
+//! A new bid must beat the high bid by the auction's min_increment. A bid
+//! landing within anti_snipe_window seconds of end_time pushes end_time back
+//! by the window, at most MAX_EXTENSIONS times per auction.
+//!
+//! Batch auctions sell `units` identical impressions. Each bidder holds one
+//! standing bid; the top `units` bids at or above the reserve each win a unit
+//! and pay either the lowest winning bid (uniform price) or their own bid
+//! (pay-as-bid). Anything a winner bid above their price becomes refundable.
+//!
+//! On settlement a protocol fee of fee_bps (rounded down) of the amount paid
+//! goes to the fee recipient and the remainder to the publisher.
+//!
+//! Sealed auctions take commitments instead of bids: commit_bid escrows a
+//! deposit of at least the floor price alongside sha256(amount || salt), with
+//! amount as 16 big-endian bytes and a 32-byte salt. After end_time bidders
+//! reveal until reveal_deadline; settlement then picks the highest revealed
+//! bid and pays unrevealed deposits to the publisher.
+//!
+//! With a payout contract configured, settlement moves the publisher's share
+//! to payout-automation and credits it as pending earnings through
+//! credit_earnings, so publishers are paid on its schedule and thresholds.
+//! The auction token must be the payout contract's default token, and this
+//! contract must be registered there as a crediting contract.
+//!
+//! With the budget check enabled, place_bid asks the configured escrow vault
+//! how much the bidder has locked for the bid's campaign and rejects bids it
+//! can't cover. Every standing bid reserves its amount against that balance
+//! until it is outbid, settled or cancelled, so one deposit can't back
+//! concurrent bids across auctions.
+//!
+//! Publishers can restrict an auction to an allowlist of campaign ids, set at
+//! create_auction or edited until the first bid; an empty allowlist leaves
+//! the auction open. Sealed commitments carry no campaign, so sealed
+//! auctions take no allowlist. With an ad registry configured, place_bid also
+//! rejects bids unless the bidder owns Approved content for the campaign there.
+//! The registry and the budget check are what tie a bidder to a campaign id,
+//! so an allowlisted auction only takes bids while one of them is on.
 
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env, String,
+    contract, contracterror, contractimpl, contracttype, symbol_short,
+    token, vec, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Vec,
 };
 
 #[contracttype]
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum AuctionStatus {
     Open,
     Closed,
@@ -21,6 +77,13 @@ pub enum AuctionStatus {
     Cancelled,
 }
 
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub enum AuctionKind {
+    Standard,
+    Sealed,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct Auction {
@@ -35,6 +98,22 @@ pub struct Auction {
     pub winning_bid: Option<i128>,
     pub winner: Option<Address>,
     pub bid_count: u32,
+    pub min_increment: i128,
+    pub anti_snipe_window: u64,
+    pub extensions: u32,
+    pub units: u32,
+    pub pay_as_bid: bool,
+    pub fee_paid: i128,
+    pub kind: AuctionKind,
+    pub reveal_deadline: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SealedBid {
+    pub commitment: BytesN<32>,
+    pub deposit: i128,
+    pub revealed: bool,
 }
 
 #[contracttype]
@@ -46,6 +125,67 @@ pub struct Bid {
     pub timestamp: u64,
 }
 
+/// Dashboard view of an auction returned by `get_auction_summary`. The top
+/// bid is the current winner's latest bid, flattened into its own fields.
+/// `seconds_remaining` is zero once bidding has ended or the auction closed.
+#[contracttype]
+#[derive(Clone)]
+pub struct AuctionSummary {
+    pub auction: Auction,
+    pub bid_count: u32,
+    pub top_bid: Option<i128>,
+    pub top_bidder: Option<Address>,
+    pub top_campaign_id: Option<u64>,
+    pub seconds_remaining: u64,
+    pub campaign_leads: bool,
+}
+
+/// Outcome of settle_auction. For batch auctions `winner` is the top bidder
+/// and `amount_paid` the total paid across all units sold.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettlementResult {
+    pub status: AuctionStatus,
+    pub winner: Option<Address>,
+    pub amount_paid: i128,
+    pub fee: i128,
+    pub units_sold: u32,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AuctionError {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    Unauthorized = 3,
+    NotFound = 4,
+    NotOpen = 5,
+    Ended = 6,
+    StillRunning = 7,
+    BelowFloor = 8,
+    BidTooLow = 9,
+    AlreadySettled = 10,
+    InvalidFee = 11,
+    InvalidConfig = 12,
+    HasBids = 13,
+    NothingToRefund = 14,
+    CampaignUnderfunded = 15,
+    EscrowVaultNotSet = 16,
+    WrongAuctionKind = 17,
+    AlreadyCommitted = 18,
+    TooManyBidders = 19,
+    NoCommitment = 20,
+    AlreadyRevealed = 21,
+    CommitmentMismatch = 22,
+    RevealOpen = 23,
+    RevealClosed = 24,
+    Paused = 25,
+    CampaignNotAllowed = 26,
+    NoApprovedContent = 27,
+    CampaignNotLinked = 28,
+}
+
 #[contracttype]
 pub enum DataKey {
     Admin,
@@ -56,6 +196,26 @@ pub enum DataKey {
     Bid(u64, u32),       // auction_id, bid_index
     HighestBid(u64),
     BidderBid(u64, Address),  // auction_id, bidder
+    Refund(u64, Address),     // auction_id, bidder
+    Leaders(u64),             // standing batch bids, highest first
+    Winners(u64),
+    FeeBps,
+    FeeRecipient,
+    TotalFeesCollected,
+    PublisherAuctions(Address),
+    BidderAuctions(Address),
+    BidderBids(u64, Address), // auction_id, bidder -> bid indexes
+    SealedBid(u64, Address),
+    Committers(u64),
+    EscrowVault,
+    BudgetCheck,
+    PayoutContract,
+    Paused,
+    AllowedCampaign(u64, u64), // auction_id, campaign_id
+    AllowlistSize(u64),
+    AdRegistry,
+    Reservation(u64, Address),     // auction_id, bidder -> (campaign_id, amount)
+    CampaignReserved(u64, Address), // campaign_id, bidder
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
@@ -63,22 +223,127 @@ const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17_280;
 const PERSISTENT_BUMP_AMOUNT: u32 = 86_400;
 
+const MAX_EXTENSIONS: u32 = 10;
+const MAX_BATCH_UNITS: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 100;
+const MAX_SEALED_BIDDERS: u32 = 100;
+const MAX_RECENT_BIDS: u32 = 50;
+const MAX_ALLOWED_CAMPAIGNS: u32 = 100;
+
 #[contract]
 pub struct AuctionEngineContract;
 
 #[contractimpl]
 impl AuctionEngineContract {
-    pub fn initialize(env: Env, admin: Address, token: Address) {
+    pub fn initialize(env: Env, admin: Address, token: Address, fee_bps: u32, fee_recipient: Address) -> Result<(), AuctionError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("already initialized");
+            return Err(AuctionError::AlreadyInitialized);
         }
         admin.require_auth();
+        if fee_bps > 10_000 {
+            return Err(AuctionError::InvalidFee);
+        }
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage().instance().set(&DataKey::TokenAddress, &token);
         env.storage().instance().set(&DataKey::AuctionCounter, &0u64);
+        env.storage().instance().set(&DataKey::FeeBps, &fee_bps);
+        env.storage().instance().set(&DataKey::FeeRecipient, &fee_recipient);
+        Ok(())
+    }
+
+    /// Hand the admin role to another address, such as the governance DAO
+    pub fn set_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.events().publish(
+            (symbol_short!("admin"), symbol_short!("changed")),
+            (admin, new_admin),
+        );
+        Ok(())
     }
 
+    /// Emergency stop: block new auctions, bids, commitments and settlement.
+    /// Reveals, cancellations, closing expired auctions and refunds stay open.
+    pub fn pause(env: Env, admin: Address) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::Paused, &true);
+        env.events().publish((symbol_short!("admin"), symbol_short!("paused")), admin);
+        Ok(())
+    }
+
+    pub fn unpause(env: Env, admin: Address) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().remove(&DataKey::Paused);
+        env.events().publish((symbol_short!("admin"), symbol_short!("unpaused")), admin);
+        Ok(())
+    }
+
+    pub fn set_fee_bps(env: Env, admin: Address, fee_bps: u32) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        if fee_bps > 10_000 {
+            return Err(AuctionError::InvalidFee);
+        }
+        env.storage().instance().set(&DataKey::FeeBps, &fee_bps);
+        Ok(())
+    }
+
+    pub fn set_fee_recipient(env: Env, admin: Address, fee_recipient: Address) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::FeeRecipient, &fee_recipient);
+        Ok(())
+    }
+
+    pub fn set_escrow_vault(env: Env, admin: Address, escrow_vault: Address) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::EscrowVault, &escrow_vault);
+        Ok(())
+    }
+
+    /// Route publisher proceeds through payout-automation, or pay publishers
+    /// directly again with None
+    pub fn set_payout_contract(env: Env, admin: Address, payout_contract: Option<Address>) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        match payout_contract {
+            Some(addr) => env.storage().instance().set(&DataKey::PayoutContract, &addr),
+            None => env.storage().instance().remove(&DataKey::PayoutContract),
+        }
+        Ok(())
+    }
+
+    /// Only accept bids from campaigns with Approved content in this ad
+    /// registry; None turns the check off
+    pub fn set_ad_registry(env: Env, admin: Address, ad_registry: Option<Address>) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        match ad_registry {
+            Some(addr) => env.storage().instance().set(&DataKey::AdRegistry, &addr),
+            None => env.storage().instance().remove(&DataKey::AdRegistry),
+        }
+        Ok(())
+    }
+
+    /// Require bids to be covered by their campaign's locked escrow funds.
+    /// Off by default so standalone deployments need no escrow vault.
+    pub fn set_budget_check(env: Env, admin: Address, enabled: bool) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        if enabled && !env.storage().instance().has(&DataKey::EscrowVault) {
+            return Err(AuctionError::EscrowVaultNotSet);
+        }
+        env.storage().instance().set(&DataKey::BudgetCheck, &enabled);
+        Ok(())
+    }
+
+    /// Open a standard auction. A non-empty `allowed_campaigns` (at most 100
+    /// ids) restricts bidding to those campaigns.
     pub fn create_auction(
         env: Env,
         publisher: Address,
@@ -86,72 +351,171 @@ impl AuctionEngineContract {
         floor_price: i128,
         reserve_price: i128,
         duration_secs: u64,
-    ) -> u64 {
+        min_increment: i128,
+        anti_snipe_window: u64,
+        allowed_campaigns: Vec<u64>,
+    ) -> Result<u64, AuctionError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
         publisher.require_auth();
 
-        let counter: u64 = env.storage().instance().get(&DataKey::AuctionCounter).unwrap_or(0);
-        let auction_id = counter + 1;
+        if min_increment <= 0 || allowed_campaigns.len() > MAX_ALLOWED_CAMPAIGNS {
+            return Err(AuctionError::InvalidConfig);
+        }
 
-        let now = env.ledger().timestamp();
-        let auction = Auction {
-            auction_id,
-            publisher: publisher.clone(),
-            impression_slot,
-            floor_price,
-            reserve_price,
-            start_time: now,
-            end_time: now + duration_secs,
-            status: AuctionStatus::Open,
-            winning_bid: None,
-            winner: None,
-            bid_count: 0,
-        };
+        let mut auction = Self::_new_auction(&env, publisher, impression_slot, floor_price, reserve_price, duration_secs);
+        auction.min_increment = min_increment;
+        auction.anti_snipe_window = anti_snipe_window;
+        let auction_id = Self::_create_auction(&env, auction);
+        for campaign_id in allowed_campaigns.iter() {
+            Self::_allow_campaign(&env, auction_id, campaign_id);
+        }
+        Ok(auction_id)
+    }
 
-        let _ttl_key = DataKey::Auction(auction_id);
-        env.storage().persistent().set(&_ttl_key, &auction);
+    /// Add a campaign to an auction's allowlist (publisher only, before the
+    /// first bid). Allowing a campaign on an open auction restricts it.
+    /// Sealed auctions can't be restricted.
+    pub fn add_allowed_campaign(env: Env, publisher: Address, auction_id: u64, campaign_id: u64) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_allowlist_editable(&env, &publisher, auction_id)?;
+        let size: u32 = env.storage().persistent().get(&DataKey::AllowlistSize(auction_id)).unwrap_or(0);
+        if size >= MAX_ALLOWED_CAMPAIGNS {
+            return Err(AuctionError::InvalidConfig);
+        }
+        Self::_allow_campaign(&env, auction_id, campaign_id);
+        Ok(())
+    }
+
+    /// Remove a campaign from an auction's allowlist (publisher only, before
+    /// the first bid). Removing the last entry reopens the auction.
+    pub fn remove_allowed_campaign(env: Env, publisher: Address, auction_id: u64, campaign_id: u64) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_allowlist_editable(&env, &publisher, auction_id)?;
+        let key = DataKey::AllowedCampaign(auction_id, campaign_id);
+        if !env.storage().persistent().has(&key) {
+            return Err(AuctionError::NotFound);
+        }
+        env.storage().persistent().remove(&key);
+        let size: u32 = env.storage().persistent().get(&DataKey::AllowlistSize(auction_id)).unwrap_or(0);
+        let _ttl_key = DataKey::AllowlistSize(auction_id);
+        env.storage().persistent().set(&_ttl_key, &(size - 1));
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        env.storage().instance().set(&DataKey::AuctionCounter, &auction_id);
+        Ok(())
+    }
 
-        env.events().publish(
-            (symbol_short!("auction"), symbol_short!("created")),
-            (auction_id, publisher),
-        );
+    /// Auction `units` identical impressions at once (at most 50). With
+    /// `pay_as_bid` each winner pays their own bid, otherwise the lowest
+    /// winning bid.
+    pub fn create_batch_auction(
+        env: Env,
+        publisher: Address,
+        impression_slot: String,
+        units: u32,
+        floor_price: i128,
+        reserve_price: i128,
+        duration_secs: u64,
+        pay_as_bid: bool,
+    ) -> Result<u64, AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
+        publisher.require_auth();
 
-        auction_id
+        if units == 0 || units > MAX_BATCH_UNITS {
+            return Err(AuctionError::InvalidConfig);
+        }
+
+        let mut auction = Self::_new_auction(&env, publisher, impression_slot, floor_price, reserve_price, duration_secs);
+        auction.units = units;
+        auction.pay_as_bid = pay_as_bid;
+        Ok(Self::_create_auction(&env, auction))
+    }
+
+    /// Sealed-bid auction: commitments until `duration_secs` elapse, then
+    /// reveals for another `reveal_window` seconds.
+    pub fn create_sealed_auction(
+        env: Env,
+        publisher: Address,
+        impression_slot: String,
+        floor_price: i128,
+        reserve_price: i128,
+        duration_secs: u64,
+        reveal_window: u64,
+    ) -> Result<u64, AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
+        publisher.require_auth();
+
+        if reveal_window == 0 {
+            return Err(AuctionError::InvalidConfig);
+        }
+
+        let mut auction = Self::_new_auction(&env, publisher, impression_slot, floor_price, reserve_price, duration_secs);
+        auction.kind = AuctionKind::Sealed;
+        auction.reveal_deadline = auction.end_time + reveal_window;
+        Ok(Self::_create_auction(&env, auction))
     }
 
-    pub fn place_bid(env: Env, bidder: Address, auction_id: u64, amount: i128, campaign_id: u64) {
+    pub fn place_bid(env: Env, bidder: Address, auction_id: u64, amount: i128, campaign_id: u64) -> Result<(), AuctionError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
         bidder.require_auth();
 
-        let mut auction: Auction = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Auction(auction_id))
-            .expect("auction not found");
+        let mut auction = Self::_load_auction(&env, auction_id)?;
 
         if auction.status != AuctionStatus::Open {
-            panic!("auction not open");
+            return Err(AuctionError::NotOpen);
+        }
+        if auction.kind == AuctionKind::Sealed {
+            return Err(AuctionError::WrongAuctionKind);
         }
 
         let now = env.ledger().timestamp();
         if now > auction.end_time {
-            panic!("auction ended");
+            return Err(AuctionError::Ended);
         }
 
         if amount < auction.floor_price {
-            panic!("bid below floor price");
+            return Err(AuctionError::BelowFloor);
         }
-
-        // Check if higher than current best
-        let current_high: Option<i128> = env.storage().persistent().get(&DataKey::HighestBid(auction_id));
-        if let Some(high) = current_high {
-            if amount <= high {
-                panic!("bid too low");
+        if !Self::_is_campaign_allowed(&env, auction_id, campaign_id) {
+            return Err(AuctionError::CampaignNotAllowed);
+        }
+        let budget_check: bool = env.storage().instance().get(&DataKey::BudgetCheck).unwrap_or(false);
+        let allowlisted = env.storage().persistent().get(&DataKey::AllowlistSize(auction_id)).unwrap_or(0u32) > 0;
+        if allowlisted && !budget_check && !env.storage().instance().has(&DataKey::AdRegistry) {
+            return Err(AuctionError::CampaignNotLinked);
+        }
+        if !Self::_has_approved_content(&env, campaign_id, &bidder) {
+            return Err(AuctionError::NoApprovedContent);
+        }
+        if budget_check {
+            // This bid replaces the bidder's own standing bid, if any
+            let own = match env.storage().persistent().get::<DataKey, (u64, i128)>(&DataKey::Reservation(auction_id, bidder.clone())) {
+                Some((reserved_campaign, reserved)) if reserved_campaign == campaign_id => reserved,
+                _ => 0,
+            };
+            if Self::_campaign_budget(&env, campaign_id, &bidder) + own < amount {
+                return Err(AuctionError::CampaignUnderfunded);
             }
         }
 
+        let outbid = if auction.units > 1 {
+            Self::_place_batch_bid(&env, &mut auction, &bidder, amount)?
+        } else {
+            // Check if higher than current best
+            let current_high: Option<i128> = env.storage().persistent().get(&DataKey::HighestBid(auction_id));
+            if let Some(high) = current_high {
+                if amount < high + auction.min_increment {
+                    return Err(AuctionError::BidTooLow);
+                }
+            }
+            let outbid = auction.winner.clone().zip(auction.winning_bid);
+            auction.winning_bid = Some(amount);
+            auction.winner = Some(bidder.clone());
+            outbid
+        };
+
         let bid = Bid {
             bidder: bidder.clone(),
             amount,
@@ -169,66 +533,418 @@ impl AuctionEngineContract {
         let _ttl_key = DataKey::BidderBid(auction_id, bidder.clone());
         env.storage().persistent().set(&_ttl_key, &amount);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        let _ttl_key = DataKey::HighestBid(auction_id);
-        env.storage().persistent().set(&_ttl_key, &amount);
+        let _ttl_key = DataKey::BidderBids(auction_id, bidder.clone());
+        let mut bid_indexes: Vec<u32> = env.storage().persistent().get(&_ttl_key).unwrap_or(Vec::new(&env));
+        if bid_indexes.is_empty() {
+            Self::_append_id(&env, DataKey::BidderAuctions(bidder.clone()), auction_id);
+        }
+        bid_indexes.push_back(bid_count);
+        env.storage().persistent().set(&_ttl_key, &bid_indexes);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        if let Some(high) = auction.winning_bid {
+            let _ttl_key = DataKey::HighestBid(auction_id);
+            env.storage().persistent().set(&_ttl_key, &high);
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
 
         auction.bid_count += 1;
-        auction.winning_bid = Some(amount);
-        auction.winner = Some(bidder.clone());
+        let extended = auction.anti_snipe_window > 0
+            && auction.end_time - now <= auction.anti_snipe_window
+            && auction.extensions < MAX_EXTENSIONS;
+        if extended {
+            auction.end_time += auction.anti_snipe_window;
+            auction.extensions += 1;
+        }
         let _ttl_key = DataKey::Auction(auction_id);
         env.storage().persistent().set(&_ttl_key, &auction);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 
+        if let Some((prev_bidder, _)) = &outbid {
+            Self::_release_reservation(&env, auction_id, prev_bidder);
+        }
+        if budget_check {
+            Self::_reserve(&env, auction_id, &bidder, campaign_id, amount);
+        }
+
+        // Escrow the new bid, then release the one it replaces
+        let token_client = Self::_token(&env);
+        token_client.transfer(&bidder, &env.current_contract_address(), &amount);
+        if let Some((prev_bidder, prev_amount)) = outbid {
+            token_client.transfer(&env.current_contract_address(), &prev_bidder, &prev_amount);
+            env.events().publish(
+                (symbol_short!("bid"), symbol_short!("refund")),
+                (auction_id, prev_bidder, prev_amount),
+            );
+        }
+
         env.events().publish(
             (symbol_short!("bid"), symbol_short!("placed")),
             (auction_id, bidder, amount),
         );
+        if extended {
+            env.events().publish(
+                (symbol_short!("auction"), symbol_short!("extended")),
+                (auction_id, auction.end_time),
+            );
+        }
+        Ok(())
     }
 
-    pub fn settle_auction(env: Env, caller: Address, auction_id: u64) {
+    /// Settle an auction once it has ended (and its reveal window has closed for
+    /// sealed auctions). Anyone may settle so a high bid is never stuck waiting
+    /// on the publisher; only the admin can close an auction early.
+    pub fn settle_auction(env: Env, caller: Address, auction_id: u64) -> Result<SettlementResult, AuctionError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        let mut auction: Auction = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Auction(auction_id))
-            .expect("auction not found");
+        Self::_require_not_paused(&env)?;
+        caller.require_auth();
+        let mut auction = Self::_load_auction(&env, auction_id)?;
+        let admin = Self::_admin(&env)?;
 
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if caller != auction.publisher && caller != admin {
-            panic!("unauthorized");
+        if auction.status != AuctionStatus::Open {
+            return Err(AuctionError::AlreadySettled);
         }
 
         let now = env.ledger().timestamp();
         if now < auction.end_time && caller != admin {
-            panic!("auction still running");
+            return Err(AuctionError::StillRunning);
+        }
+        if auction.kind == AuctionKind::Sealed && now <= auction.reveal_deadline {
+            return Err(AuctionError::RevealOpen);
         }
 
-        auction.status = if auction.winning_bid.is_some() {
-            let winning = auction.winning_bid.unwrap();
-            if winning >= auction.reserve_price {
-                // Transfer payment from winner to publisher
-                let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
-                let token_client = token::Client::new(&env, &token_addr);
-                if let Some(winner) = auction.winner.clone() {
-                    token_client.transfer(&winner, &auction.publisher, &winning);
+        Self::_release_standing(&env, &auction);
+        let winners = if auction.units > 1 {
+            Self::_settle_batch(&env, &auction)
+        } else {
+            let mut winners = Vec::new(&env);
+            match (auction.winner.clone(), auction.winning_bid) {
+                (Some(winner), Some(winning)) if winning >= auction.reserve_price => {
+                    winners.push_back((winner, winning));
+                }
+                (Some(winner), Some(winning)) => {
+                    // Reserve not met: the escrowed high bid goes back to its bidder
+                    Self::_credit_refund(&env, auction_id, &winner, winning);
                 }
-                AuctionStatus::Settled
-            } else {
-                AuctionStatus::Cancelled
+                _ => {}
             }
-        } else {
+            winners
+        };
+        auction.status = if winners.is_empty() {
             AuctionStatus::Cancelled
+        } else {
+            AuctionStatus::Settled
         };
+        let payment: i128 = winners.iter().map(|(_, paid)| paid).sum();
+        let fee_bps: u32 = env.storage().instance().get(&DataKey::FeeBps).unwrap_or(0);
+        auction.fee_paid = payment * fee_bps as i128 / 10_000;
+        if auction.fee_paid > 0 {
+            let total: i128 = env.storage().instance().get(&DataKey::TotalFeesCollected).unwrap_or(0);
+            env.storage().instance().set(&DataKey::TotalFeesCollected, &(total + auction.fee_paid));
+        }
 
         let _ttl_key = DataKey::Auction(auction_id);
         env.storage().persistent().set(&_ttl_key, &auction);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::Winners(auction_id);
+        env.storage().persistent().set(&_ttl_key, &winners);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        // Pay the fee and the publisher from the escrowed winning bids
+        let token_client = Self::_token(&env);
+        if auction.fee_paid > 0 {
+            let fee_recipient: Address = env.storage().instance().get(&DataKey::FeeRecipient).unwrap();
+            token_client.transfer(&env.current_contract_address(), &fee_recipient, &auction.fee_paid);
+            env.events().publish(
+                (symbol_short!("auction"), symbol_short!("fee")),
+                (auction_id, auction.fee_paid),
+            );
+        }
+        if payment > auction.fee_paid {
+            Self::_pay_publisher(&env, &token_client, &auction.publisher, payment - auction.fee_paid);
+        }
+        if auction.kind == AuctionKind::Sealed {
+            let slashed = Self::_slash_unrevealed(&env, auction_id);
+            if slashed > 0 {
+                Self::_pay_publisher(&env, &token_client, &auction.publisher, slashed);
+            }
+        }
+
+        if winners.is_empty() {
+            env.events().publish(
+                (symbol_short!("auction"), symbol_short!("settle")),
+                (auction_id, None::<Address>, None::<i128>),
+            );
+        }
+        for (winner, paid) in winners.iter() {
+            env.events().publish(
+                (symbol_short!("auction"), symbol_short!("settle")),
+                (auction_id, Some(winner), Some(paid)),
+            );
+        }
+
+        Ok(SettlementResult {
+            status: auction.status,
+            winner: winners.first().map(|(winner, _)| winner),
+            amount_paid: payment,
+            fee: auction.fee_paid,
+            units_sold: winners.len(),
+        })
+    }
+
+    /// Commit to a sealed bid, escrowing `deposit` (at least the floor price).
+    /// `commitment` is sha256(amount || salt).
+    pub fn commit_bid(env: Env, bidder: Address, auction_id: u64, commitment: BytesN<32>, deposit: i128) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
+        bidder.require_auth();
+
+        let mut auction = Self::_load_auction(&env, auction_id)?;
+
+        if auction.status != AuctionStatus::Open {
+            return Err(AuctionError::NotOpen);
+        }
+        if auction.kind != AuctionKind::Sealed {
+            return Err(AuctionError::WrongAuctionKind);
+        }
+        if env.ledger().timestamp() > auction.end_time {
+            return Err(AuctionError::Ended);
+        }
+        if deposit < auction.floor_price {
+            return Err(AuctionError::BelowFloor);
+        }
+        let sealed_key = DataKey::SealedBid(auction_id, bidder.clone());
+        if env.storage().persistent().has(&sealed_key) {
+            return Err(AuctionError::AlreadyCommitted);
+        }
+
+        let _ttl_key = DataKey::Committers(auction_id);
+        let mut committers: Vec<Address> = env.storage().persistent().get(&_ttl_key).unwrap_or(Vec::new(&env));
+        if committers.len() >= MAX_SEALED_BIDDERS {
+            return Err(AuctionError::TooManyBidders);
+        }
+        committers.push_back(bidder.clone());
+        env.storage().persistent().set(&_ttl_key, &committers);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let sealed = SealedBid {
+            commitment,
+            deposit,
+            revealed: false,
+        };
+        env.storage().persistent().set(&sealed_key, &sealed);
+        env.storage().persistent().extend_ttl(&sealed_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        auction.bid_count += 1;
+        let _ttl_key = DataKey::Auction(auction_id);
+        env.storage().persistent().set(&_ttl_key, &auction);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        Self::_token(&env).transfer(&bidder, &env.current_contract_address(), &deposit);
 
         env.events().publish(
-            (symbol_short!("auction"), symbol_short!("settle")),
-            (auction_id, auction.winner, auction.winning_bid),
+            (symbol_short!("bid"), symbol_short!("commit")),
+            (auction_id, bidder, deposit),
         );
+        Ok(())
+    }
+
+    /// Reveal a sealed bid between end_time and reveal_deadline. A bid above
+    /// the deposit collects the difference; a deposit above the bid has its
+    /// surplus made refundable, as is the whole bid once it is beaten.
+    pub fn reveal_bid(env: Env, bidder: Address, auction_id: u64, amount: i128, salt: BytesN<32>) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bidder.require_auth();
+
+        let mut auction = Self::_load_auction(&env, auction_id)?;
+
+        if auction.status != AuctionStatus::Open {
+            return Err(AuctionError::NotOpen);
+        }
+        let now = env.ledger().timestamp();
+        if now <= auction.end_time {
+            return Err(AuctionError::StillRunning);
+        }
+        if now > auction.reveal_deadline {
+            return Err(AuctionError::RevealClosed);
+        }
+
+        let sealed_key = DataKey::SealedBid(auction_id, bidder.clone());
+        let mut sealed: SealedBid = env.storage().persistent().get(&sealed_key).ok_or(AuctionError::NoCommitment)?;
+        if sealed.revealed {
+            return Err(AuctionError::AlreadyRevealed);
+        }
+        let mut preimage = Bytes::from_array(&env, &amount.to_be_bytes());
+        preimage.append(&Bytes::from_array(&env, &salt.to_array()));
+        if BytesN::from(env.crypto().sha256(&preimage)) != sealed.commitment {
+            return Err(AuctionError::CommitmentMismatch);
+        }
+        if amount < auction.floor_price {
+            return Err(AuctionError::BelowFloor);
+        }
+
+        sealed.revealed = true;
+        env.storage().persistent().set(&sealed_key, &sealed);
+        env.storage().persistent().extend_ttl(&sealed_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let bid = Bid {
+            bidder: bidder.clone(),
+            amount,
+            campaign_id: 0,
+            timestamp: now,
+        };
+        let bid_count: u32 = env.storage().persistent().get(&DataKey::BidCount(auction_id)).unwrap_or(0);
+        let _ttl_key = DataKey::Bid(auction_id, bid_count);
+        env.storage().persistent().set(&_ttl_key, &bid);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::BidCount(auction_id);
+        env.storage().persistent().set(&_ttl_key, &(bid_count + 1));
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        // Ties go to the earlier reveal
+        let wins = auction.winning_bid.is_none_or(|high| amount > high);
+        if wins {
+            if let (Some(prev_winner), Some(prev_amount)) = (auction.winner.clone(), auction.winning_bid) {
+                Self::_credit_refund(&env, auction_id, &prev_winner, prev_amount);
+            }
+            auction.winner = Some(bidder.clone());
+            auction.winning_bid = Some(amount);
+            let _ttl_key = DataKey::HighestBid(auction_id);
+            env.storage().persistent().set(&_ttl_key, &amount);
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            let _ttl_key = DataKey::Auction(auction_id);
+            env.storage().persistent().set(&_ttl_key, &auction);
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        } else {
+            Self::_credit_refund(&env, auction_id, &bidder, amount);
+        }
+        if sealed.deposit > amount {
+            Self::_credit_refund(&env, auction_id, &bidder, sealed.deposit - amount);
+        } else if amount > sealed.deposit {
+            Self::_token(&env).transfer(&bidder, &env.current_contract_address(), &(amount - sealed.deposit));
+        }
+
+        env.events().publish(
+            (symbol_short!("bid"), symbol_short!("reveal")),
+            (auction_id, bidder, amount),
+        );
+        Ok(())
+    }
+
+    pub fn get_sealed_bid(env: Env, auction_id: u64, bidder: Address) -> Option<SealedBid> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::SealedBid(auction_id, bidder))
+    }
+
+    /// Cancel an open auction. The publisher may cancel until the first bid;
+    /// the admin may cancel any time before settlement, making the escrowed
+    /// bids refundable.
+    pub fn cancel_auction(env: Env, caller: Address, auction_id: u64) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        caller.require_auth();
+
+        let mut auction = Self::_load_auction(&env, auction_id)?;
+
+        let admin = Self::_admin(&env)?;
+        if caller != auction.publisher && caller != admin {
+            return Err(AuctionError::Unauthorized);
+        }
+        if auction.status != AuctionStatus::Open {
+            return Err(AuctionError::NotOpen);
+        }
+        if auction.bid_count > 0 && caller != admin {
+            return Err(AuctionError::HasBids);
+        }
+
+        Self::_release_standing(&env, &auction);
+        if auction.units > 1 {
+            let leaders: Vec<(Address, i128)> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Leaders(auction_id))
+                .unwrap_or(Vec::new(&env));
+            for (bidder, amount) in leaders.iter() {
+                Self::_credit_refund(&env, auction_id, &bidder, amount);
+            }
+        } else if let (Some(winner), Some(winning)) = (auction.winner.clone(), auction.winning_bid) {
+            Self::_credit_refund(&env, auction_id, &winner, winning);
+        }
+        if auction.kind == AuctionKind::Sealed {
+            let committers: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Committers(auction_id))
+                .unwrap_or(Vec::new(&env));
+            for committer in committers.iter() {
+                let sealed: SealedBid = env.storage().persistent().get(&DataKey::SealedBid(auction_id, committer.clone())).unwrap();
+                if !sealed.revealed {
+                    Self::_credit_refund(&env, auction_id, &committer, sealed.deposit);
+                }
+            }
+        }
+
+        auction.status = AuctionStatus::Cancelled;
+        let _ttl_key = DataKey::Auction(auction_id);
+        env.storage().persistent().set(&_ttl_key, &auction);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("auction"), symbol_short!("cancel")),
+            (auction_id, caller),
+        );
+        Ok(())
+    }
+
+    /// Close an open auction that ended without a single bid. Anyone may call.
+    pub fn close_expired(env: Env, auction_id: u64) -> Result<(), AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut auction = Self::_load_auction(&env, auction_id)?;
+
+        if auction.status != AuctionStatus::Open {
+            return Err(AuctionError::NotOpen);
+        }
+        if env.ledger().timestamp() <= auction.end_time {
+            return Err(AuctionError::StillRunning);
+        }
+        if auction.bid_count > 0 {
+            return Err(AuctionError::HasBids);
+        }
+
+        auction.status = AuctionStatus::Cancelled;
+        let _ttl_key = DataKey::Auction(auction_id);
+        env.storage().persistent().set(&_ttl_key, &auction);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("auction"), symbol_short!("closed")),
+            auction_id,
+        );
+        Ok(())
+    }
+
+    /// Withdraw an escrowed bid that will not be paid out, e.g. the high bid of
+    /// an auction that was cancelled.
+    pub fn claim_refund(env: Env, bidder: Address, auction_id: u64) -> Result<i128, AuctionError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bidder.require_auth();
+
+        let refund_key = DataKey::Refund(auction_id, bidder.clone());
+        let amount: i128 = env.storage().persistent().get(&refund_key).unwrap_or(0);
+        if amount <= 0 {
+            return Err(AuctionError::NothingToRefund);
+        }
+        env.storage().persistent().remove(&refund_key);
+
+        Self::_token(&env).transfer(&env.current_contract_address(), &bidder, &amount);
+        env.events().publish(
+            (symbol_short!("bid"), symbol_short!("refund")),
+            (auction_id, bidder, amount),
+        );
+        Ok(amount)
+    }
+
+    pub fn get_refundable(env: Env, auction_id: u64, bidder: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Refund(auction_id, bidder)).unwrap_or(0)
     }
 
     pub fn get_auction(env: Env, auction_id: u64) -> Option<Auction> {
@@ -236,6 +952,139 @@ impl AuctionEngineContract {
         env.storage().persistent().get(&DataKey::Auction(auction_id))
     }
 
+    /// Winners and the price each paid; empty until the auction is settled
+    pub fn get_winners(env: Env, auction_id: u64) -> Vec<(Address, i128)> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::Winners(auction_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// A publisher's auction ids, oldest first; `limit` is capped at 100
+    pub fn list_auctions_by_publisher(env: Env, publisher: Address, offset: u32, limit: u32) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_page(&env, DataKey::PublisherAuctions(publisher), offset, limit)
+    }
+
+    /// Auctions a bidder has bid in, in order of their first bid; `limit` is
+    /// capped at 100
+    pub fn list_auctions_by_bidder(env: Env, bidder: Address, offset: u32, limit: u32) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_page(&env, DataKey::BidderAuctions(bidder), offset, limit)
+    }
+
+    /// Every bid a bidder placed in an auction, oldest first
+    pub fn get_bidder_bids(env: Env, auction_id: u64, bidder: Address) -> Vec<Bid> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let bid_indexes: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BidderBids(auction_id, bidder))
+            .unwrap_or(Vec::new(&env));
+        let mut bids = Vec::new(&env);
+        for index in bid_indexes.iter() {
+            if let Some(bid) = env.storage().persistent().get(&DataKey::Bid(auction_id, index)) {
+                bids.push_back(bid);
+            }
+        }
+        bids
+    }
+
+    /// Auction, top bid and time left in one read. `campaign_leads` is true
+    /// when `campaign_id` placed the current top bid.
+    pub fn get_auction_summary(env: Env, auction_id: u64, campaign_id: Option<u64>) -> Option<AuctionSummary> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let auction: Auction = env.storage().persistent().get(&DataKey::Auction(auction_id))?;
+        let top_campaign_id = auction.winner.clone().and_then(|winner| {
+            let bid_indexes: Vec<u32> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::BidderBids(auction_id, winner))
+                .unwrap_or(Vec::new(&env));
+            let index = bid_indexes.last()?;
+            env.storage()
+                .persistent()
+                .get::<DataKey, Bid>(&DataKey::Bid(auction_id, index))
+                .map(|bid| bid.campaign_id)
+        });
+        let seconds_remaining = if auction.status == AuctionStatus::Open {
+            auction.end_time.saturating_sub(env.ledger().timestamp())
+        } else {
+            0
+        };
+        Some(AuctionSummary {
+            bid_count: env.storage().persistent().get(&DataKey::BidCount(auction_id)).unwrap_or(0),
+            top_bid: auction.winning_bid,
+            top_bidder: auction.winner.clone(),
+            top_campaign_id,
+            seconds_remaining,
+            campaign_leads: campaign_id.is_some() && top_campaign_id == campaign_id,
+            auction,
+        })
+    }
+
+    /// The latest bids in an auction, newest first; `limit` is capped at 50
+    pub fn get_recent_bids(env: Env, auction_id: u64, limit: u32) -> Vec<Bid> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let bid_count: u32 = env.storage().persistent().get(&DataKey::BidCount(auction_id)).unwrap_or(0);
+        let oldest = bid_count.saturating_sub(limit.min(MAX_RECENT_BIDS));
+        let mut bids = Vec::new(&env);
+        for index in (oldest..bid_count).rev() {
+            if let Some(bid) = env.storage().persistent().get(&DataKey::Bid(auction_id, index)) {
+                bids.push_back(bid);
+            }
+        }
+        bids
+    }
+
+    pub fn get_escrow_vault(env: Env) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::EscrowVault)
+    }
+
+    pub fn get_ad_registry(env: Env) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::AdRegistry)
+    }
+
+    /// Whether a campaign may bid on an auction; true for every campaign
+    /// while the allowlist is empty
+    pub fn is_campaign_allowed(env: Env, auction_id: u64, campaign_id: u64) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_is_campaign_allowed(&env, auction_id, campaign_id)
+    }
+
+    pub fn get_payout_contract(env: Env) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::PayoutContract)
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+    }
+
+    pub fn get_fee_bps(env: Env) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::FeeBps).unwrap_or(0)
+    }
+
+    pub fn is_budget_check_enabled(env: Env) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::BudgetCheck).unwrap_or(false)
+    }
+
+    pub fn get_total_fees(env: Env) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::TotalFeesCollected).unwrap_or(0)
+    }
+
     pub fn get_bid(env: Env, auction_id: u64, index: u32) -> Option<Bid> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::Bid(auction_id, index))
@@ -250,6 +1099,355 @@ impl AuctionEngineContract {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::HighestBid(auction_id))
     }
+
+    // ============================================================
+    // Internal Helpers
+    // ============================================================
+
+    /// A single-unit standard auction starting now; callers adjust the
+    /// kind-specific fields before storing it with _create_auction
+    fn _new_auction(
+        env: &Env,
+        publisher: Address,
+        impression_slot: String,
+        floor_price: i128,
+        reserve_price: i128,
+        duration_secs: u64,
+    ) -> Auction {
+        let now = env.ledger().timestamp();
+        Auction {
+            auction_id: 0,
+            publisher,
+            impression_slot,
+            floor_price,
+            reserve_price,
+            start_time: now,
+            end_time: now + duration_secs,
+            status: AuctionStatus::Open,
+            winning_bid: None,
+            winner: None,
+            bid_count: 0,
+            min_increment: 1,
+            anti_snipe_window: 0,
+            extensions: 0,
+            units: 1,
+            pay_as_bid: false,
+            fee_paid: 0,
+            kind: AuctionKind::Standard,
+            reveal_deadline: 0,
+        }
+    }
+
+    fn _create_auction(env: &Env, mut auction: Auction) -> u64 {
+        let counter: u64 = env.storage().instance().get(&DataKey::AuctionCounter).unwrap_or(0);
+        let auction_id = counter + 1;
+        auction.auction_id = auction_id;
+        let publisher = auction.publisher.clone();
+
+        let _ttl_key = DataKey::Auction(auction_id);
+        env.storage().persistent().set(&_ttl_key, &auction);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage().instance().set(&DataKey::AuctionCounter, &auction_id);
+        Self::_append_id(env, DataKey::PublisherAuctions(publisher.clone()), auction_id);
+
+        env.events().publish(
+            (symbol_short!("auction"), symbol_short!("created")),
+            (auction_id, publisher),
+        );
+
+        auction_id
+    }
+
+    /// Insert a bid into the standing batch bids and return the bid it
+    /// displaces: the bidder's own earlier bid, or the lowest bid once all
+    /// units are taken.
+    fn _place_batch_bid(env: &Env, auction: &mut Auction, bidder: &Address, amount: i128) -> Result<Option<(Address, i128)>, AuctionError> {
+        let _ttl_key = DataKey::Leaders(auction.auction_id);
+        let mut leaders: Vec<(Address, i128)> = env.storage().persistent().get(&_ttl_key).unwrap_or(Vec::new(env));
+
+        let mut displaced = None;
+        if let Some(pos) = leaders.iter().position(|(leader, _)| leader == *bidder) {
+            let (_, previous) = leaders.get_unchecked(pos as u32);
+            if amount < previous + auction.min_increment {
+                return Err(AuctionError::BidTooLow);
+            }
+            leaders.remove(pos as u32);
+            displaced = Some((bidder.clone(), previous));
+        } else if leaders.len() >= auction.units {
+            let (_, lowest) = leaders.last_unchecked();
+            if amount < lowest + auction.min_increment {
+                return Err(AuctionError::BidTooLow);
+            }
+            displaced = leaders.pop_back();
+        }
+
+        // Highest first; equal bids keep their arrival order
+        let pos = leaders.iter().position(|(_, standing)| standing < amount).unwrap_or(leaders.len() as usize);
+        leaders.insert(pos as u32, (bidder.clone(), amount));
+        env.storage().persistent().set(&_ttl_key, &leaders);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let (top_bidder, top_amount) = leaders.get_unchecked(0);
+        auction.winner = Some(top_bidder);
+        auction.winning_bid = Some(top_amount);
+        Ok(displaced)
+    }
+
+    /// Price the standing batch bids: bids meeting the reserve win, anything
+    /// not charged is credited back to its bidder.
+    fn _settle_batch(env: &Env, auction: &Auction) -> Vec<(Address, i128)> {
+        let leaders: Vec<(Address, i128)> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Leaders(auction.auction_id))
+            .unwrap_or(Vec::new(env));
+        let clearing_price = leaders
+            .iter()
+            .map(|(_, amount)| amount)
+            .filter(|amount| *amount >= auction.reserve_price)
+            .min();
+
+        let mut winners = Vec::new(env);
+        for (bidder, amount) in leaders.iter() {
+            let paid = match clearing_price {
+                Some(price) if amount >= auction.reserve_price => {
+                    if auction.pay_as_bid { amount } else { price }
+                }
+                _ => 0,
+            };
+            if amount > paid {
+                Self::_credit_refund(env, auction.auction_id, &bidder, amount - paid);
+            }
+            if paid > 0 {
+                winners.push_back((bidder, paid));
+            }
+        }
+        winners
+    }
+
+    /// Total deposit forfeited by bidders who committed but never revealed
+    fn _slash_unrevealed(env: &Env, auction_id: u64) -> i128 {
+        let committers: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Committers(auction_id))
+            .unwrap_or(Vec::new(env));
+        let mut slashed = 0;
+        for committer in committers.iter() {
+            let sealed: SealedBid = env.storage().persistent().get(&DataKey::SealedBid(auction_id, committer.clone())).unwrap();
+            if !sealed.revealed {
+                slashed += sealed.deposit;
+                env.events().publish(
+                    (symbol_short!("bid"), symbol_short!("slashed")),
+                    (auction_id, committer, sealed.deposit),
+                );
+            }
+        }
+        slashed
+    }
+
+    /// Funds the bidder has locked in the escrow vault for a campaign in the
+    /// auction token, less what their standing bids already reserve
+    fn _campaign_budget(env: &Env, campaign_id: u64, bidder: &Address) -> i128 {
+        let escrow_vault: Address = env.storage().instance().get(&DataKey::EscrowVault).unwrap();
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let locked: i128 = env.invoke_contract(
+            &escrow_vault,
+            &Symbol::new(env, "get_campaign_locked_by"),
+            vec![env, campaign_id.into_val(env), bidder.into_val(env), token_addr.into_val(env)],
+        );
+        let reserved: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CampaignReserved(campaign_id, bidder.clone()))
+            .unwrap_or(0);
+        locked - reserved
+    }
+
+    fn _reserve(env: &Env, auction_id: u64, bidder: &Address, campaign_id: u64, amount: i128) {
+        Self::_release_reservation(env, auction_id, bidder);
+        let _ttl_key = DataKey::Reservation(auction_id, bidder.clone());
+        env.storage().persistent().set(&_ttl_key, &(campaign_id, amount));
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::CampaignReserved(campaign_id, bidder.clone());
+        let reserved: i128 = env.storage().persistent().get(&_ttl_key).unwrap_or(0);
+        env.storage().persistent().set(&_ttl_key, &(reserved + amount));
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Drop the reservation behind a bidder's standing bid, if it has one
+    fn _release_reservation(env: &Env, auction_id: u64, bidder: &Address) {
+        let key = DataKey::Reservation(auction_id, bidder.clone());
+        let (campaign_id, amount): (u64, i128) = match env.storage().persistent().get(&key) {
+            Some(reservation) => reservation,
+            None => return,
+        };
+        env.storage().persistent().remove(&key);
+        let _ttl_key = DataKey::CampaignReserved(campaign_id, bidder.clone());
+        let reserved: i128 = env.storage().persistent().get(&_ttl_key).unwrap_or(0);
+        if reserved <= amount {
+            env.storage().persistent().remove(&_ttl_key);
+        } else {
+            env.storage().persistent().set(&_ttl_key, &(reserved - amount));
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+    }
+
+    /// Release every standing bid's reservation once the auction stops taking bids
+    fn _release_standing(env: &Env, auction: &Auction) {
+        if auction.units > 1 {
+            let leaders: Vec<(Address, i128)> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Leaders(auction.auction_id))
+                .unwrap_or(Vec::new(env));
+            for (bidder, _) in leaders.iter() {
+                Self::_release_reservation(env, auction.auction_id, &bidder);
+            }
+        } else if let Some(winner) = auction.winner.clone() {
+            Self::_release_reservation(env, auction.auction_id, &winner);
+        }
+    }
+
+    fn _require_allowlist_editable(env: &Env, publisher: &Address, auction_id: u64) -> Result<(), AuctionError> {
+        publisher.require_auth();
+        let auction = Self::_load_auction(env, auction_id)?;
+        if auction.publisher != *publisher {
+            return Err(AuctionError::Unauthorized);
+        }
+        if auction.kind == AuctionKind::Sealed {
+            return Err(AuctionError::WrongAuctionKind);
+        }
+        if auction.status != AuctionStatus::Open {
+            return Err(AuctionError::NotOpen);
+        }
+        if auction.bid_count > 0 {
+            return Err(AuctionError::HasBids);
+        }
+        Ok(())
+    }
+
+    fn _allow_campaign(env: &Env, auction_id: u64, campaign_id: u64) {
+        let key = DataKey::AllowedCampaign(auction_id, campaign_id);
+        if env.storage().persistent().has(&key) {
+            return;
+        }
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let size: u32 = env.storage().persistent().get(&DataKey::AllowlistSize(auction_id)).unwrap_or(0);
+        let _ttl_key = DataKey::AllowlistSize(auction_id);
+        env.storage().persistent().set(&_ttl_key, &(size + 1));
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    fn _is_campaign_allowed(env: &Env, auction_id: u64, campaign_id: u64) -> bool {
+        let size: u32 = env.storage().persistent().get(&DataKey::AllowlistSize(auction_id)).unwrap_or(0);
+        size == 0 || env.storage().persistent().has(&DataKey::AllowedCampaign(auction_id, campaign_id))
+    }
+
+    /// True when no ad registry is configured, otherwise whether the bidder
+    /// owns any currently approved content for the campaign
+    fn _has_approved_content(env: &Env, campaign_id: u64, bidder: &Address) -> bool {
+        let registry: Address = match env.storage().instance().get(&DataKey::AdRegistry) {
+            Some(addr) => addr,
+            None => return true,
+        };
+        let approved: u32 = env.invoke_contract(
+            &registry,
+            &Symbol::new(env, "get_approved_count"),
+            vec![env, campaign_id.into_val(env), bidder.into_val(env)],
+        );
+        approved > 0
+    }
+
+    fn _append_id(env: &Env, key: DataKey, id: u64) {
+        let mut ids: Vec<u64> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+        ids.push_back(id);
+        env.storage().persistent().set(&key, &ids);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    fn _page(env: &Env, key: DataKey, offset: u32, limit: u32) -> Vec<u64> {
+        let ids: Vec<u64> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(ids.len());
+        if offset >= end {
+            return Vec::new(env);
+        }
+        ids.slice(offset..end)
+    }
+
+    fn _require_not_paused(env: &Env) -> Result<(), AuctionError> {
+        if env.storage().instance().get(&DataKey::Paused).unwrap_or(false) {
+            return Err(AuctionError::Paused);
+        }
+        Ok(())
+    }
+
+    fn _require_admin(env: &Env, caller: &Address) -> Result<(), AuctionError> {
+        caller.require_auth();
+        let admin = Self::_admin(env)?;
+        if *caller != admin {
+            return Err(AuctionError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn _admin(env: &Env) -> Result<Address, AuctionError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(AuctionError::NotInitialized)
+    }
+
+    fn _load_auction(env: &Env, auction_id: u64) -> Result<Auction, AuctionError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Auction(auction_id))
+            .ok_or(AuctionError::NotFound)
+    }
+
+    fn _token(env: &Env) -> token::Client<'_> {
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        token::Client::new(env, &token_addr)
+    }
+
+    /// Pay publisher proceeds directly, or into payout-automation as pending
+    /// earnings when a payout contract is configured. Publishers that
+    /// payout-automation would refuse (unregistered or suspended) are paid
+    /// directly so settlement never fails on their status.
+    fn _pay_publisher(env: &Env, token_client: &token::Client, publisher: &Address, amount: i128) {
+        match env.storage().instance().get::<DataKey, Address>(&DataKey::PayoutContract) {
+            Some(payout_contract) if Self::_payout_accepts(env, &payout_contract, publisher) => {
+                token_client.transfer(&env.current_contract_address(), &payout_contract, &amount);
+                env.invoke_contract::<()>(
+                    &payout_contract,
+                    &Symbol::new(env, "credit_earnings"),
+                    vec![
+                        env,
+                        env.current_contract_address().into_val(env),
+                        publisher.into_val(env),
+                        amount.into_val(env),
+                    ],
+                );
+            }
+            _ => token_client.transfer(&env.current_contract_address(), publisher, &amount),
+        }
+    }
+
+    fn _payout_accepts(env: &Env, payout_contract: &Address, publisher: &Address) -> bool {
+        env.invoke_contract::<bool>(
+            payout_contract,
+            &Symbol::new(env, "is_active_publisher"),
+            vec![env, publisher.into_val(env)],
+        )
+    }
+
+    fn _credit_refund(env: &Env, auction_id: u64, bidder: &Address, amount: i128) {
+        let _ttl_key = DataKey::Refund(auction_id, bidder.clone());
+        let owed: i128 = env.storage().persistent().get(&_ttl_key).unwrap_or(0);
+        env.storage().persistent().set(&_ttl_key, &(owed + amount));
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
 }
 
 mod test;
@@ -1,6 +1,21 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, IntoVal};
+use soroban_sdk::{contract, contractimpl, testutils::{Address as _, Events, Ledger}, token, vec, Address, Bytes, BytesN, Env, IntoVal, String};
+
+/// Stand-in for the escrow vault's per-depositor locked-funds view
+#[contract]
+pub struct MockEscrowVault;
+
+#[contractimpl]
+impl MockEscrowVault {
+    pub fn fund(env: Env, campaign_id: u64, depositor: Address, amount: i128) {
+        env.storage().instance().set(&(campaign_id, depositor), &amount);
+    }
+
+    pub fn get_campaign_locked_by(env: Env, campaign_id: u64, depositor: Address, _token: Address) -> i128 {
+        env.storage().instance().get(&(campaign_id, depositor)).unwrap_or(0)
+    }
+}
 
 #[test]
 fn test_initialize() {
@@ -13,11 +28,10 @@ fn test_initialize() {
     let admin = Address::generate(&env);
     let token = Address::generate(&env);
 
-    client.initialize(&admin, &token);
+    client.initialize(&admin, &token, &0, &admin);
 }
 
 #[test]
-#[should_panic(expected = "already initialized")]
 fn test_initialize_twice() {
     let env = Env::default();
     env.mock_all_auths();
@@ -28,8 +42,8 @@ fn test_initialize_twice() {
     let admin = Address::generate(&env);
     let token = Address::generate(&env);
 
-    client.initialize(&admin, &token);
-    client.initialize(&admin, &token);
+    client.initialize(&admin, &token, &0, &admin);
+    assert_eq!(client.try_initialize(&admin, &token, &0, &admin), Err(Ok(AuctionError::AlreadyInitialized)));
 }
 
 #[test]
@@ -44,5 +58,908 @@ fn test_initialize_non_admin_fails() {
     let token = Address::generate(&env);
 
     // This should panic because admin didn't authorize it and we haven't mocked it
-    client.initialize(&admin, &token);
+    client.initialize(&admin, &token, &0, &admin);
+}
+
+fn setup(env: &Env) -> (AuctionEngineContractClient<'_>, Address, Address) {
+    env.mock_all_auths();
+
+    let contract_id = env.register(AuctionEngineContract, ());
+    let client = AuctionEngineContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+
+    client.initialize(&admin, &token, &0, &admin);
+    (client, admin, token)
+}
+
+fn bidder(env: &Env, token: &Address, amount: i128) -> Address {
+    let account = Address::generate(env);
+    token::StellarAssetClient::new(env, token).mint(&account, &amount);
+    account
+}
+
+fn open_auction(env: &Env, client: &AuctionEngineContractClient, publisher: &Address) -> u64 {
+    client.create_auction(publisher, &String::from_str(env, "banner-top"), &100, &500, &3_600, &10, &0, &vec![env])
+}
+
+#[test]
+fn test_bids_are_escrowed_and_outbid_refunded() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+
+    let alice = bidder(&env, &token, 1_000);
+    let bob = bidder(&env, &token, 1_000);
+    client.place_bid(&alice, &auction_id, &400, &1);
+    assert_eq!(balance.balance(&alice), 600);
+    assert_eq!(balance.balance(&client.address), 400);
+
+    client.place_bid(&bob, &auction_id, &700, &2);
+    assert_eq!(balance.balance(&alice), 1_000);
+    assert_eq!(balance.balance(&bob), 300);
+    assert_eq!(balance.balance(&client.address), 700);
+
+    // Emptying the wallet after winning no longer dodges payment
+    balance.transfer(&bob, &Address::generate(&env), &300);
+    env.ledger().set_timestamp(3_601);
+    client.settle_auction(&publisher, &auction_id);
+    assert_eq!(balance.balance(&publisher), 700);
+    assert_eq!(balance.balance(&client.address), 0);
+    assert!(client.get_auction(&auction_id).unwrap().status == AuctionStatus::Settled);
+}
+
+#[test]
+fn test_cancelled_auction_refunds_high_bidder() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+
+    // Below the 500 reserve
+    let alice = bidder(&env, &token, 1_000);
+    client.place_bid(&alice, &auction_id, &300, &1);
+    env.ledger().set_timestamp(3_601);
+    let result = client.settle_auction(&publisher, &auction_id);
+    assert_eq!(result.status, AuctionStatus::Cancelled);
+    assert_eq!(result.winner, None);
+    assert_eq!(result.amount_paid, 0);
+    assert_eq!(balance.balance(&publisher), 0);
+    assert_eq!(client.get_refundable(&auction_id, &alice), 300);
+
+    assert_eq!(client.claim_refund(&alice, &auction_id), 300);
+    assert_eq!(balance.balance(&alice), 1_000);
+    assert_eq!(client.get_refundable(&auction_id, &alice), 0);
+    assert_eq!(client.try_claim_refund(&alice, &auction_id), Err(Ok(AuctionError::NothingToRefund)));
+}
+
+#[test]
+fn test_min_increment() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let publisher = Address::generate(&env);
+    let slot = String::from_str(&env, "banner-top");
+    assert_eq!(client.try_create_auction(&publisher, &slot, &100, &500, &3_600, &0, &0, &vec![&env]), Err(Ok(AuctionError::InvalidConfig)));
+    let auction_id = open_auction(&env, &client, &publisher);
+
+    client.place_bid(&bidder(&env, &token, 1_000), &auction_id, &400, &1);
+    let rival = bidder(&env, &token, 1_000);
+    assert_eq!(client.try_place_bid(&rival, &auction_id, &401, &2), Err(Ok(AuctionError::BidTooLow)));
+    assert_eq!(client.try_place_bid(&rival, &auction_id, &409, &2), Err(Ok(AuctionError::BidTooLow)));
+    client.place_bid(&rival, &auction_id, &410, &2);
+    assert_eq!(client.get_highest_bid(&auction_id), Some(410));
+}
+
+#[test]
+fn test_anti_snipe_extension_and_cap() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let publisher = Address::generate(&env);
+    let auction_id = client.create_auction(&publisher, &String::from_str(&env, "banner-top"), &100, &500, &3_600, &10, &300, &vec![&env]);
+    let sniper = bidder(&env, &token, 100_000);
+
+    // Outside the window the deadline holds
+    env.ledger().set_timestamp(3_000);
+    client.place_bid(&sniper, &auction_id, &100, &1);
+    assert_eq!(client.get_auction(&auction_id).unwrap().end_time, 3_600);
+
+    // A late bid pushes the deadline back by the window
+    env.ledger().set_timestamp(3_590);
+    client.place_bid(&sniper, &auction_id, &110, &1);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("auction"), symbol_short!("extended")).into_val(&env),
+                (auction_id, 3_900u64).into_val(&env),
+            ),
+        ]
+    );
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.end_time, 3_900);
+    assert_eq!(auction.extensions, 1);
+
+    // Extensions stop after MAX_EXTENSIONS
+    let mut amount = 110;
+    for _ in 1..MAX_EXTENSIONS {
+        let end_time = client.get_auction(&auction_id).unwrap().end_time;
+        env.ledger().set_timestamp(end_time - 1);
+        amount += 10;
+        client.place_bid(&sniper, &auction_id, &amount, &1);
+    }
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.extensions, MAX_EXTENSIONS);
+    env.ledger().set_timestamp(auction.end_time - 1);
+    client.place_bid(&sniper, &auction_id, &(amount + 10), &1);
+    assert_eq!(client.get_auction(&auction_id).unwrap().end_time, auction.end_time);
+}
+
+fn batch_auction(env: &Env, client: &AuctionEngineContractClient, publisher: &Address, pay_as_bid: bool) -> u64 {
+    client.create_batch_auction(publisher, &String::from_str(env, "sidebar"), &3, &100, &200, &3_600, &pay_as_bid)
+}
+
+#[test]
+fn test_batch_auction_uniform_price() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    assert_eq!(client.try_create_batch_auction(&publisher, &String::from_str(&env, "sidebar"), &51, &100, &200, &3_600, &false), Err(Ok(AuctionError::InvalidConfig)));
+    let auction_id = batch_auction(&env, &client, &publisher, false);
+
+    let bidders = [
+        bidder(&env, &token, 1_000),
+        bidder(&env, &token, 1_000),
+        bidder(&env, &token, 1_000),
+        bidder(&env, &token, 1_000),
+    ];
+    client.place_bid(&bidders[0], &auction_id, &300, &1);
+    client.place_bid(&bidders[1], &auction_id, &500, &2);
+    client.place_bid(&bidders[2], &auction_id, &400, &3);
+
+    // All units taken: a new bid must beat the lowest, which is refunded
+    assert_eq!(client.try_place_bid(&bidders[3], &auction_id, &300, &4), Err(Ok(AuctionError::BidTooLow)));
+    client.place_bid(&bidders[3], &auction_id, &350, &4);
+    assert_eq!(balance.balance(&bidders[0]), 1_000);
+    assert_eq!(client.get_highest_bid(&auction_id), Some(500));
+
+    // Raising a standing bid replaces it
+    client.place_bid(&bidders[3], &auction_id, &450, &4);
+    assert_eq!(balance.balance(&bidders[3]), 550);
+    assert_eq!(balance.balance(&client.address), 1_350);
+
+    env.ledger().set_timestamp(3_601);
+    let result = client.settle_auction(&publisher, &auction_id);
+    assert_eq!(result.winner, Some(bidders[1].clone()));
+    assert_eq!(result.amount_paid, 1_200);
+    assert_eq!(result.units_sold, 3);
+    assert_eq!(
+        client.get_winners(&auction_id),
+        vec![&env, (bidders[1].clone(), 400i128), (bidders[3].clone(), 400i128), (bidders[2].clone(), 400i128)]
+    );
+    assert_eq!(balance.balance(&publisher), 1_200);
+    assert_eq!(client.get_refundable(&auction_id, &bidders[1]), 100);
+    assert_eq!(client.get_refundable(&auction_id, &bidders[3]), 50);
+    assert_eq!(client.get_refundable(&auction_id, &bidders[2]), 0);
+}
+
+#[test]
+fn test_batch_auction_pay_as_bid_and_reserve() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    let auction_id = batch_auction(&env, &client, &publisher, true);
+
+    let high = bidder(&env, &token, 1_000);
+    let mid = bidder(&env, &token, 1_000);
+    let low = bidder(&env, &token, 1_000);
+    client.place_bid(&high, &auction_id, &600, &1);
+    client.place_bid(&mid, &auction_id, &250, &2);
+    // Below the 200 reserve: never wins, fully refundable
+    client.place_bid(&low, &auction_id, &150, &3);
+
+    env.ledger().set_timestamp(3_601);
+    client.settle_auction(&publisher, &auction_id);
+    let events = env.events().all();
+    let settle_events = events
+        .iter()
+        .filter(|(contract, topics, _)| {
+            *contract == client.address && *topics == (symbol_short!("auction"), symbol_short!("settle")).into_val(&env)
+        })
+        .count();
+    assert_eq!(settle_events, 2);
+    assert_eq!(client.get_winners(&auction_id), vec![&env, (high.clone(), 600i128), (mid.clone(), 250i128)]);
+    assert_eq!(balance.balance(&publisher), 850);
+    assert_eq!(client.get_refundable(&auction_id, &low), 150);
+    assert!(client.get_auction(&auction_id).unwrap().status == AuctionStatus::Settled);
+}
+
+#[test]
+fn test_cancel_auction_without_bids() {
+    let env = Env::default();
+    let (client, _admin, _token) = setup(&env);
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+
+    assert_eq!(client.try_cancel_auction(&Address::generate(&env), &auction_id), Err(Ok(AuctionError::Unauthorized)));
+    client.cancel_auction(&publisher, &auction_id);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("auction"), symbol_short!("cancel")).into_val(&env),
+                (auction_id, publisher.clone()).into_val(&env),
+            ),
+        ]
+    );
+    assert!(client.get_auction(&auction_id).unwrap().status == AuctionStatus::Cancelled);
+    assert_eq!(client.try_cancel_auction(&publisher, &auction_id), Err(Ok(AuctionError::NotOpen)));
+}
+
+#[test]
+fn test_cancel_auction_with_bids_requires_admin() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+    let alice = bidder(&env, &token, 1_000);
+    client.place_bid(&alice, &auction_id, &600, &1);
+
+    assert_eq!(client.try_cancel_auction(&publisher, &auction_id), Err(Ok(AuctionError::HasBids)));
+    client.cancel_auction(&admin, &auction_id);
+    assert!(client.get_auction(&auction_id).unwrap().status == AuctionStatus::Cancelled);
+    assert_eq!(client.claim_refund(&alice, &auction_id), 600);
+    assert_eq!(token::Client::new(&env, &token).balance(&alice), 1_000);
+
+    // A cancelled auction can't be settled or bid on
+    env.ledger().set_timestamp(3_601);
+    assert_eq!(client.try_settle_auction(&admin, &auction_id), Err(Ok(AuctionError::AlreadySettled)));
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &700, &1), Err(Ok(AuctionError::NotOpen)));
+}
+
+#[test]
+fn test_pause_blocks_bidding_but_not_refunds() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+    let alice = bidder(&env, &token, 1_000);
+    client.place_bid(&alice, &auction_id, &600, &1);
+
+    assert_eq!(client.try_pause(&publisher), Err(Ok(AuctionError::Unauthorized)));
+    client.pause(&admin);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![&env, (client.address.clone(), (symbol_short!("admin"), symbol_short!("paused")).into_val(&env), admin.into_val(&env))]
+    );
+    assert!(client.is_paused());
+
+    assert_eq!(
+        client.try_create_auction(&publisher, &String::from_str(&env, "banner-top"), &100, &500, &3_600, &10, &0, &vec![&env]),
+        Err(Ok(AuctionError::Paused))
+    );
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &700, &1), Err(Ok(AuctionError::Paused)));
+    env.ledger().set_timestamp(3_601);
+    assert_eq!(client.try_settle_auction(&publisher, &auction_id), Err(Ok(AuctionError::Paused)));
+
+    // Escrowed bids can still leave the contract
+    client.cancel_auction(&admin, &auction_id);
+    assert_eq!(client.claim_refund(&alice, &auction_id), 600);
+    assert_eq!(balance.balance(&alice), 1_000);
+
+    client.unpause(&admin);
+    assert!(!client.is_paused());
+    open_auction(&env, &client, &publisher);
+}
+
+#[test]
+fn test_close_expired() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let publisher = Address::generate(&env);
+    let idle = open_auction(&env, &client, &publisher);
+    let active = open_auction(&env, &client, &publisher);
+    client.place_bid(&bidder(&env, &token, 1_000), &active, &600, &1);
+
+    assert_eq!(client.try_close_expired(&idle), Err(Ok(AuctionError::StillRunning)));
+    env.ledger().set_timestamp(3_601);
+    client.close_expired(&idle);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("auction"), symbol_short!("closed")).into_val(&env),
+                idle.into_val(&env),
+            ),
+        ]
+    );
+    assert!(client.get_auction(&idle).unwrap().status == AuctionStatus::Cancelled);
+    assert_eq!(client.try_close_expired(&idle), Err(Ok(AuctionError::NotOpen)));
+
+    // Auctions with bids go through settlement instead
+    assert_eq!(client.try_close_expired(&active), Err(Ok(AuctionError::HasBids)));
+}
+
+#[test]
+fn test_settle_twice_fails() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+    client.place_bid(&bidder(&env, &token, 1_000), &auction_id, &600, &1);
+
+    env.ledger().set_timestamp(3_601);
+    client.settle_auction(&publisher, &auction_id);
+    assert_eq!(client.try_settle_auction(&publisher, &auction_id), Err(Ok(AuctionError::AlreadySettled)));
+    assert_eq!(balance.balance(&publisher), 600);
+}
+
+#[test]
+fn test_anyone_settles_after_end() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    let alice = bidder(&env, &token, 1_000);
+    let auction_id = open_auction(&env, &client, &publisher);
+    client.place_bid(&alice, &auction_id, &600, &1);
+
+    // The publisher never settles, so the high bidder does it once bidding ends
+    assert_eq!(client.try_settle_auction(&alice, &auction_id), Err(Ok(AuctionError::StillRunning)));
+    env.ledger().set_timestamp(3_601);
+    client.settle_auction(&alice, &auction_id);
+    assert!(client.get_auction(&auction_id).unwrap().status == AuctionStatus::Settled);
+    assert_eq!(balance.balance(&publisher), 600);
+
+    // Sealed auctions still wait for the reveal window
+    let sealed_id = sealed_auction(&env, &client, &publisher);
+    let keeper = Address::generate(&env);
+    env.ledger().set_timestamp(7_201);
+    assert_eq!(client.try_settle_auction(&keeper, &sealed_id), Err(Ok(AuctionError::RevealOpen)));
+    assert_eq!(client.try_settle_auction(&admin, &sealed_id), Err(Ok(AuctionError::RevealOpen)));
+    env.ledger().set_timestamp(7_802);
+    client.settle_auction(&keeper, &sealed_id);
+    assert!(client.get_auction(&sealed_id).unwrap().status == AuctionStatus::Cancelled);
+}
+
+#[test]
+fn test_set_admin_hands_over_role() {
+    let env = Env::default();
+    let (client, admin, _) = setup(&env);
+    let dao = Address::generate(&env);
+
+    client.set_admin(&admin, &dao);
+    assert_eq!(client.get_admin(), dao);
+    assert_eq!(client.try_set_fee_bps(&admin, &250), Err(Ok(AuctionError::Unauthorized)));
+    client.set_fee_bps(&dao, &250);
+    assert_eq!(client.get_fee_bps(), 250);
+}
+
+#[test]
+fn test_protocol_fee() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    assert_eq!(client.try_set_fee_bps(&admin, &10_001), Err(Ok(AuctionError::InvalidFee)));
+    assert_eq!(client.try_set_fee_bps(&publisher, &250), Err(Ok(AuctionError::Unauthorized)));
+    client.set_fee_bps(&admin, &250);
+    client.set_fee_recipient(&admin, &treasury);
+
+    // 2.5% of 999 is 24.975: the fee rounds down
+    let auction_id = open_auction(&env, &client, &publisher);
+    client.place_bid(&bidder(&env, &token, 1_000), &auction_id, &999, &1);
+    env.ledger().set_timestamp(3_601);
+    let result = client.settle_auction(&publisher, &auction_id);
+    let event = env.events().all().iter().find(|(_, topics, _)| {
+        *topics == (symbol_short!("auction"), symbol_short!("fee")).into_val(&env)
+    });
+    assert_eq!(
+        vec![&env, event.unwrap()],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("auction"), symbol_short!("fee")).into_val(&env),
+                (auction_id, 24i128).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(balance.balance(&treasury), 24);
+    assert_eq!(balance.balance(&publisher), 975);
+    assert_eq!(client.get_auction(&auction_id).unwrap().fee_paid, 24);
+    assert_eq!(
+        result,
+        SettlementResult {
+            status: AuctionStatus::Settled,
+            winner: client.get_auction(&auction_id).unwrap().winner,
+            amount_paid: 999,
+            fee: 24,
+            units_sold: 1,
+        }
+    );
+
+    let second = open_auction(&env, &client, &publisher);
+    client.place_bid(&bidder(&env, &token, 1_000), &second, &800, &1);
+    env.ledger().set_timestamp(7_202);
+    client.settle_auction(&publisher, &second);
+    assert_eq!(balance.balance(&treasury), 44);
+    assert_eq!(client.get_total_fees(), 44);
+}
+
+#[test]
+fn test_zero_fee() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+    client.place_bid(&bidder(&env, &token, 1_000), &auction_id, &999, &1);
+    env.ledger().set_timestamp(3_601);
+    client.settle_auction(&publisher, &auction_id);
+
+    assert_eq!(balance.balance(&publisher), 999);
+    assert_eq!(client.get_auction(&auction_id).unwrap().fee_paid, 0);
+    assert_eq!(client.get_total_fees(), 0);
+}
+
+#[test]
+fn test_bidder_history_and_listings() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let publisher = Address::generate(&env);
+    let first = open_auction(&env, &client, &publisher);
+    open_auction(&env, &client, &Address::generate(&env));
+    let second = open_auction(&env, &client, &publisher);
+
+    let alice = bidder(&env, &token, 10_000);
+    let bob = bidder(&env, &token, 10_000);
+    client.place_bid(&alice, &second, &200, &1);
+    client.place_bid(&alice, &first, &150, &1);
+    client.place_bid(&bob, &first, &300, &2);
+    client.place_bid(&alice, &first, &400, &1);
+    client.place_bid(&alice, &second, &250, &1);
+
+    assert_eq!(client.list_auctions_by_publisher(&publisher, &0, &10), vec![&env, first, second]);
+    assert_eq!(client.list_auctions_by_publisher(&publisher, &1, &10), vec![&env, second]);
+    assert_eq!(client.list_auctions_by_publisher(&publisher, &5, &10).len(), 0);
+    assert_eq!(client.list_auctions_by_bidder(&alice, &0, &10), vec![&env, second, first]);
+    assert_eq!(client.list_auctions_by_bidder(&alice, &0, &1), vec![&env, second]);
+    assert_eq!(client.list_auctions_by_bidder(&bob, &0, &10), vec![&env, first]);
+
+    let history = client.get_bidder_bids(&first, &alice);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().amount, 150);
+    assert_eq!(history.get(1).unwrap().amount, 400);
+    assert_eq!(client.get_bidder_bids(&second, &alice).len(), 2);
+    assert_eq!(client.get_bidder_bids(&second, &bob).len(), 0);
+}
+
+#[test]
+fn test_auction_summary_without_bids() {
+    let env = Env::default();
+    let (client, _admin, _token) = setup(&env);
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+    assert!(client.get_auction_summary(&99, &None).is_none());
+
+    env.ledger().set_timestamp(600);
+    let summary = client.get_auction_summary(&auction_id, &Some(1)).unwrap();
+    assert_eq!(summary.auction.auction_id, auction_id);
+    assert_eq!(summary.bid_count, 0);
+    assert_eq!(summary.top_bid, None);
+    assert_eq!(summary.top_bidder, None);
+    assert_eq!(summary.top_campaign_id, None);
+    assert_eq!(summary.seconds_remaining, 3_000);
+    assert!(!summary.campaign_leads);
+    assert_eq!(client.get_recent_bids(&auction_id, &10).len(), 0);
+
+    client.cancel_auction(&publisher, &auction_id);
+    assert_eq!(client.get_auction_summary(&auction_id, &None).unwrap().seconds_remaining, 0);
+}
+
+#[test]
+fn test_auction_summary_and_recent_bids() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+
+    let alice = bidder(&env, &token, 100_000);
+    let bob = bidder(&env, &token, 100_000);
+    for i in 0..60i128 {
+        let (who, campaign_id) = if i % 2 == 0 { (&alice, 1) } else { (&bob, 2) };
+        client.place_bid(who, &auction_id, &(100 + i * 10), &campaign_id);
+    }
+
+    let summary = client.get_auction_summary(&auction_id, &Some(2)).unwrap();
+    assert_eq!(summary.bid_count, 60);
+    assert_eq!(summary.top_bid, Some(690));
+    assert_eq!(summary.top_bidder, Some(bob.clone()));
+    assert_eq!(summary.top_campaign_id, Some(2));
+    assert!(summary.campaign_leads);
+    assert!(!client.get_auction_summary(&auction_id, &Some(1)).unwrap().campaign_leads);
+    assert!(!client.get_auction_summary(&auction_id, &None).unwrap().campaign_leads);
+
+    let recent = client.get_recent_bids(&auction_id, &3);
+    assert_eq!(recent.len(), 3);
+    assert_eq!(recent.get(0).unwrap().amount, 690);
+    assert_eq!(recent.get(1).unwrap().amount, 680);
+    assert_eq!(recent.get(2).unwrap().bidder, bob);
+
+    let capped = client.get_recent_bids(&auction_id, &u32::MAX);
+    assert_eq!(capped.len(), 50);
+    assert_eq!(capped.get(49).unwrap().amount, 200);
+}
+
+fn commitment(env: &Env, amount: i128, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &amount.to_be_bytes());
+    preimage.append(&Bytes::from_array(env, &salt.to_array()));
+    env.crypto().sha256(&preimage).into()
+}
+
+fn sealed_auction(env: &Env, client: &AuctionEngineContractClient, publisher: &Address) -> u64 {
+    client.create_sealed_auction(publisher, &String::from_str(env, "premium"), &100, &500, &3_600, &600)
+}
+
+#[test]
+fn test_sealed_auction_reveal_and_settle() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    let auction_id = sealed_auction(&env, &client, &publisher);
+
+    let alice = bidder(&env, &token, 1_000);
+    let bob = bidder(&env, &token, 1_000);
+    let alice_salt = BytesN::from_array(&env, &[1; 32]);
+    let bob_salt = BytesN::from_array(&env, &[2; 32]);
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &700, &1), Err(Ok(AuctionError::WrongAuctionKind)));
+    assert_eq!(client.try_commit_bid(&alice, &auction_id, &commitment(&env, 700, &alice_salt), &99), Err(Ok(AuctionError::BelowFloor)));
+    client.commit_bid(&alice, &auction_id, &commitment(&env, 700, &alice_salt), &100);
+    client.commit_bid(&bob, &auction_id, &commitment(&env, 600, &bob_salt), &800);
+    assert_eq!(client.try_commit_bid(&bob, &auction_id, &commitment(&env, 650, &bob_salt), &100), Err(Ok(AuctionError::AlreadyCommitted)));
+    assert_eq!(client.get_highest_bid(&auction_id), None);
+
+    // No reveals while bidding is open
+    assert_eq!(client.try_reveal_bid(&alice, &auction_id, &700, &alice_salt), Err(Ok(AuctionError::StillRunning)));
+    env.ledger().set_timestamp(3_601);
+    client.reveal_bid(&alice, &auction_id, &700, &alice_salt);
+    assert_eq!(balance.balance(&alice), 300);
+    client.reveal_bid(&bob, &auction_id, &600, &bob_salt);
+    assert_eq!(client.try_reveal_bid(&bob, &auction_id, &600, &bob_salt), Err(Ok(AuctionError::AlreadyRevealed)));
+    assert!(client.get_sealed_bid(&auction_id, &bob).unwrap().revealed);
+
+    // Settlement waits for the reveal window
+    assert_eq!(client.try_settle_auction(&publisher, &auction_id), Err(Ok(AuctionError::RevealOpen)));
+    env.ledger().set_timestamp(4_201);
+    assert_eq!(client.try_reveal_bid(&alice, &auction_id, &700, &alice_salt), Err(Ok(AuctionError::RevealClosed)));
+    client.settle_auction(&publisher, &auction_id);
+    assert_eq!(client.get_winners(&auction_id), vec![&env, (alice.clone(), 700i128)]);
+    assert_eq!(balance.balance(&publisher), 700);
+    assert_eq!(client.claim_refund(&bob, &auction_id), 800);
+    assert_eq!(balance.balance(&bob), 1_000);
+}
+
+#[test]
+fn test_sealed_reveal_wrong_salt_fails() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let publisher = Address::generate(&env);
+    let auction_id = sealed_auction(&env, &client, &publisher);
+    let alice = bidder(&env, &token, 1_000);
+    let salt = BytesN::from_array(&env, &[1; 32]);
+    client.commit_bid(&alice, &auction_id, &commitment(&env, 700, &salt), &700);
+
+    env.ledger().set_timestamp(3_601);
+    assert_eq!(client.try_reveal_bid(&alice, &auction_id, &700, &BytesN::from_array(&env, &[9; 32])), Err(Ok(AuctionError::CommitmentMismatch)));
+    assert_eq!(client.try_reveal_bid(&alice, &auction_id, &701, &salt), Err(Ok(AuctionError::CommitmentMismatch)));
+    assert!(!client.get_sealed_bid(&auction_id, &alice).unwrap().revealed);
+    client.reveal_bid(&alice, &auction_id, &700, &salt);
+    assert_eq!(client.get_highest_bid(&auction_id), Some(700));
+}
+
+#[test]
+fn test_sealed_non_reveal_is_slashed() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+    let auction_id = sealed_auction(&env, &client, &publisher);
+
+    let honest = bidder(&env, &token, 1_000);
+    let silent = bidder(&env, &token, 1_000);
+    let salt = BytesN::from_array(&env, &[3; 32]);
+    client.commit_bid(&honest, &auction_id, &commitment(&env, 550, &salt), &550);
+    client.commit_bid(&silent, &auction_id, &commitment(&env, 900, &salt), &300);
+
+    env.ledger().set_timestamp(3_601);
+    client.reveal_bid(&honest, &auction_id, &550, &salt);
+    env.ledger().set_timestamp(4_201);
+    client.settle_auction(&publisher, &auction_id);
+    let slashed = env.events().all().iter().find(|(_, topics, _)| {
+        *topics == (symbol_short!("bid"), symbol_short!("slashed")).into_val(&env)
+    });
+    assert_eq!(
+        vec![&env, slashed.unwrap()],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("bid"), symbol_short!("slashed")).into_val(&env),
+                (auction_id, silent.clone(), 300i128).into_val(&env),
+            ),
+        ]
+    );
+
+    assert_eq!(balance.balance(&publisher), 850);
+    assert_eq!(client.get_refundable(&auction_id, &silent), 0);
+    assert_eq!(balance.balance(&silent), 700);
+    assert_eq!(balance.balance(&client.address), 0);
+}
+
+#[test]
+fn test_bids_checked_against_campaign_escrow() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let vault = MockEscrowVaultClient::new(&env, &env.register(MockEscrowVault, ()));
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+    let alice = bidder(&env, &token, 2_000);
+    let mallory = bidder(&env, &token, 2_000);
+    vault.fund(&1, &alice, &500);
+
+    assert_eq!(client.try_set_budget_check(&admin, &true), Err(Ok(AuctionError::EscrowVaultNotSet)));
+    client.set_escrow_vault(&admin, &vault.address);
+    client.set_budget_check(&admin, &true);
+    assert!(client.is_budget_check_enabled());
+
+    // Alice has 500 locked for campaign 1 and nothing for campaign 2
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &600, &1), Err(Ok(AuctionError::CampaignUnderfunded)));
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &200, &2), Err(Ok(AuctionError::CampaignUnderfunded)));
+    // Naming alice's campaign doesn't let mallory spend her deposit
+    assert_eq!(client.try_place_bid(&mallory, &auction_id, &200, &1), Err(Ok(AuctionError::CampaignUnderfunded)));
+    client.place_bid(&alice, &auction_id, &500, &1);
+    assert_eq!(client.get_highest_bid(&auction_id), Some(500));
+
+    // Standalone mode skips the check
+    client.set_budget_check(&admin, &false);
+    client.place_bid(&alice, &auction_id, &600, &2);
+    assert_eq!(client.get_highest_bid(&auction_id), Some(600));
+}
+
+#[test]
+fn test_standing_bids_reserve_campaign_budget() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let vault = MockEscrowVaultClient::new(&env, &env.register(MockEscrowVault, ()));
+    client.set_escrow_vault(&admin, &vault.address);
+    client.set_budget_check(&admin, &true);
+    let publisher = Address::generate(&env);
+    let first = open_auction(&env, &client, &publisher);
+    let second = open_auction(&env, &client, &publisher);
+    let alice = bidder(&env, &token, 10_000);
+    let bob = bidder(&env, &token, 10_000);
+    vault.fund(&1, &alice, &500);
+    vault.fund(&2, &bob, &1_000);
+
+    client.place_bid(&alice, &first, &300, &1);
+    assert_eq!(client.try_place_bid(&alice, &second, &300, &1), Err(Ok(AuctionError::CampaignUnderfunded)));
+    client.place_bid(&alice, &second, &200, &1);
+
+    // Raising her own bid only needs the difference
+    assert_eq!(client.try_place_bid(&alice, &first, &310, &1), Err(Ok(AuctionError::CampaignUnderfunded)));
+    client.cancel_auction(&admin, &second);
+    client.place_bid(&alice, &first, &500, &1);
+
+    // Being outbid frees the reservation for another auction
+    let third = open_auction(&env, &client, &publisher);
+    assert_eq!(client.try_place_bid(&alice, &third, &100, &1), Err(Ok(AuctionError::CampaignUnderfunded)));
+    client.place_bid(&bob, &first, &600, &2);
+    client.place_bid(&alice, &third, &500, &1);
+
+    // Settlement releases the winning bid's reservation too
+    env.ledger().set_timestamp(3_601);
+    client.settle_auction(&publisher, &first);
+    let fourth = open_auction(&env, &client, &publisher);
+    client.place_bid(&bob, &fourth, &1_000, &2);
+}
+
+#[test]
+fn test_campaign_allowlist() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let publisher = Address::generate(&env);
+    let slot = String::from_str(&env, "banner-top");
+    let auction_id = client.create_auction(&publisher, &slot, &100, &500, &3_600, &10, &0, &vec![&env, 1, 2]);
+    assert!(client.is_campaign_allowed(&auction_id, &1));
+    assert!(!client.is_campaign_allowed(&auction_id, &3));
+
+    let alice = bidder(&env, &token, 10_000);
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &200, &3), Err(Ok(AuctionError::CampaignNotAllowed)));
+
+    // Without the registry or the budget check nothing ties alice to campaign 1
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &200, &1), Err(Ok(AuctionError::CampaignNotLinked)));
+    let vault = MockEscrowVaultClient::new(&env, &env.register(MockEscrowVault, ()));
+    for campaign_id in 1..10u64 {
+        vault.fund(&campaign_id, &alice, &10_000);
+    }
+    client.set_escrow_vault(&admin, &vault.address);
+    client.set_budget_check(&admin, &true);
+
+    assert_eq!(
+        client.try_add_allowed_campaign(&Address::generate(&env), &auction_id, &3),
+        Err(Ok(AuctionError::Unauthorized))
+    );
+    client.add_allowed_campaign(&publisher, &auction_id, &3);
+    client.remove_allowed_campaign(&publisher, &auction_id, &1);
+    assert_eq!(client.try_remove_allowed_campaign(&publisher, &auction_id, &1), Err(Ok(AuctionError::NotFound)));
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &200, &1), Err(Ok(AuctionError::CampaignNotAllowed)));
+    client.place_bid(&alice, &auction_id, &200, &3);
+
+    // The allowlist is frozen once bidding starts
+    assert_eq!(client.try_add_allowed_campaign(&publisher, &auction_id, &4), Err(Ok(AuctionError::HasBids)));
+
+    // Removing every entry reopens an auction
+    let open_id = client.create_auction(&publisher, &slot, &100, &500, &3_600, &10, &0, &vec![&env, 5]);
+    client.remove_allowed_campaign(&publisher, &open_id, &5);
+    assert!(client.is_campaign_allowed(&open_id, &9));
+    client.place_bid(&alice, &open_id, &200, &9);
+
+    // Commitments name no campaign, so sealed auctions can't be restricted
+    let sealed_id = sealed_auction(&env, &client, &publisher);
+    assert_eq!(client.try_add_allowed_campaign(&publisher, &sealed_id, &1), Err(Ok(AuctionError::WrongAuctionKind)));
+}
+
+#[test]
+fn test_bids_require_approved_registry_content() {
+    use pulsar_ad_registry::{AdRegistryContract, AdRegistryContractClient, ContentFormat, ContentStatus};
+
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let registry = AdRegistryContractClient::new(&env, &env.register(AdRegistryContract, ()));
+    registry.initialize(&admin);
+    let alice = bidder(&env, &token, 10_000);
+    let mallory = bidder(&env, &token, 10_000);
+    let register = |owner: &Address, campaign_id: u64| {
+        registry.register_content(
+            owner,
+            &campaign_id,
+            &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+            &ContentFormat::Image,
+            &50_000,
+            &String::from_str(&env, "Summer sale"),
+            &String::from_str(&env, "Everything must go"),
+            &String::from_str(&env, "Shop now"),
+            &String::from_str(&env, "https://example.com"),
+            &0,
+        )
+    };
+    // Plenty of rejected creatives don't weigh on the check
+    for _ in 0..10 {
+        let rejected = register(&alice, 1);
+        registry.update_status(&admin, &rejected, &ContentStatus::Rejected);
+    }
+    let approved = register(&alice, 1);
+    registry.update_status(&admin, &approved, &ContentStatus::Approved);
+    register(&alice, 2);
+
+    assert_eq!(client.try_set_ad_registry(&Address::generate(&env), &Some(registry.address.clone())), Err(Ok(AuctionError::Unauthorized)));
+    client.set_ad_registry(&admin, &Some(registry.address.clone()));
+    assert_eq!(client.get_ad_registry(), Some(registry.address.clone()));
+
+    let publisher = Address::generate(&env);
+    let auction_id = open_auction(&env, &client, &publisher);
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &200, &2), Err(Ok(AuctionError::NoApprovedContent)));
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &200, &3), Err(Ok(AuctionError::NoApprovedContent)));
+    // Campaign 1's approved content belongs to alice, not to mallory
+    assert_eq!(client.try_place_bid(&mallory, &auction_id, &200, &1), Err(Ok(AuctionError::NoApprovedContent)));
+    client.place_bid(&alice, &auction_id, &200, &1);
+
+    // Losing approval blocks further bids
+    registry.update_status(&admin, &approved, &ContentStatus::Suspended);
+    assert_eq!(client.try_place_bid(&alice, &auction_id, &300, &1), Err(Ok(AuctionError::NoApprovedContent)));
+
+    client.set_ad_registry(&admin, &None);
+    client.place_bid(&alice, &auction_id, &300, &2);
+}
+
+#[test]
+fn test_settlement_credits_payout_earnings() {
+    use pulsar_payout_automation::{PayoutAutomationContract, PayoutAutomationContractClient};
+
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+
+    let payout_id = env.register(PayoutAutomationContract, ());
+    let payout = PayoutAutomationContractClient::new(&env, &payout_id);
+    payout.initialize(&admin, &token);
+    payout.register_publisher(&admin, &publisher, &BytesN::from_array(&env, &[1; 32]));
+    payout.add_crediting_contract(&admin, &client.address);
+    client.set_payout_contract(&admin, &Some(payout_id.clone()));
+    assert_eq!(client.get_payout_contract(), Some(payout_id.clone()));
+    client.set_fee_bps(&admin, &1_000);
+
+    let auction_id = open_auction(&env, &client, &publisher);
+    let alice = bidder(&env, &token, 5_000_000);
+    client.place_bid(&alice, &auction_id, &2_000_000, &1);
+    env.ledger().set_timestamp(3_601);
+    client.settle_auction(&publisher, &auction_id);
+
+    // The publisher's share accrues in payout-automation instead of the wallet
+    assert_eq!(balance.balance(&publisher), 0);
+    assert_eq!(balance.balance(&admin), 200_000);
+    assert_eq!(balance.balance(&payout_id), 1_800_000);
+    assert_eq!(payout.get_publisher_earnings(&publisher).unwrap().pending_amount, 1_800_000);
+
+    assert_eq!(payout.claim_earnings(&publisher, &None), 1_800_000);
+    assert_eq!(balance.balance(&publisher), 1_800_000);
+    assert_eq!(payout.get_publisher_earnings(&publisher).unwrap().pending_amount, 0);
+
+    // Unregistered, the auction can no longer settle into payout-automation
+    payout.remove_crediting_contract(&admin, &client.address);
+    let auction_id = open_auction(&env, &client, &publisher);
+    client.place_bid(&alice, &auction_id, &2_000_000, &1);
+    env.ledger().set_timestamp(7_202);
+    assert!(client.try_settle_auction(&publisher, &auction_id).is_err());
+
+    client.set_payout_contract(&admin, &None);
+    client.settle_auction(&publisher, &auction_id);
+    assert_eq!(balance.balance(&publisher), 3_600_000);
+}
+
+#[test]
+fn test_settlement_pays_inactive_publisher_directly() {
+    use pulsar_payout_automation::{PayoutAutomationContract, PayoutAutomationContractClient};
+
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let balance = token::Client::new(&env, &token);
+    let publisher = Address::generate(&env);
+
+    let payout_id = env.register(PayoutAutomationContract, ());
+    let payout = PayoutAutomationContractClient::new(&env, &payout_id);
+    payout.initialize(&admin, &token);
+    payout.add_crediting_contract(&admin, &client.address);
+    client.set_payout_contract(&admin, &Some(payout_id.clone()));
+
+    // Never registered with payout-automation: settlement still pays out
+    let auction_id = open_auction(&env, &client, &publisher);
+    let alice = bidder(&env, &token, 5_000_000);
+    client.place_bid(&alice, &auction_id, &2_000_000, &1);
+    env.ledger().set_timestamp(3_601);
+    client.settle_auction(&publisher, &auction_id);
+    assert_eq!(balance.balance(&publisher), 2_000_000);
+    assert_eq!(balance.balance(&payout_id), 0);
+    assert!(payout.get_publisher_earnings(&publisher).is_none());
+
+    // Suspended publishers are paid directly too
+    payout.register_publisher(&admin, &publisher, &BytesN::from_array(&env, &[1; 32]));
+    payout.suspend_publisher(&admin, &publisher, &String::from_str(&env, "review"));
+    let auction_id = open_auction(&env, &client, &publisher);
+    client.place_bid(&alice, &auction_id, &2_000_000, &1);
+    env.ledger().set_timestamp(7_202);
+    client.settle_auction(&publisher, &auction_id);
+    assert_eq!(balance.balance(&publisher), 4_000_000);
+    assert_eq!(balance.balance(&payout_id), 0);
 }
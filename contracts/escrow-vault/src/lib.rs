@@ -3,17 +3,78 @@
 //!
 //! Events:
 //! - ("escrow", "created"): [escrow_id: u64, campaign_id: u64, amount: i128]
-//! - ("escrow", "release"): [escrow_id: u64, amount: i128]
+//! - ("escrow", "release"): [escrow_id: u64, amount: i128] (also from settle_split)
+//! - ("escrow", "auto"): [escrow_id: u64, amount: i128] (after "release" when update_performance or sync_performance auto-releases)
 //! - ("escrow", "release_partial"): [escrow_id: u64, amount: i128]
-//! - ("escrow", "refund"): [escrow_id: u64, amount: i128]
+//! - ("escrow", "refund"): [escrow_id: u64, amount: i128] (also from settle_split)
+//! - ("escrow", "cancelled"): [escrow_id: u64, refunded: i128]
+//! - ("escrow", "milestone"): [escrow_id: u64, index: u32, amount: i128]
+//! - ("escrow", "approved"): [escrow_id: u64, approver: Address, new_count: u32]
+//! - ("escrow", "perf"): [escrow_id: u64, performance: u32, views: u64, clicks: u64] (also from sync_performance)
+//! - ("oracle", "added"): [oracle: Address]
+//! - ("oracle", "removed"): [oracle: Address]
+//! - ("arbiter", "added"): [arbiter: Address]
+//! - ("arbiter", "removed"): [arbiter: Address]
+//! - ("admin", "upgraded"): [new_wasm_hash: BytesN<32>]
+//! - ("admin", "proposed"): [admin: Address, new_admin: Address]
+//! - ("admin", "accepted"): [new_admin: Address]
+//! - ("admin", "paused"): [admin: Address]
+//! - ("admin", "unpaused"): [admin: Address]
+//! - ("token", "added"): [token: Address]
+//! - ("token", "removed"): [token: Address]
+//! - ("escrow", "dispute"): [escrow_id: u64, raised_by: Address, reason: String]
+//! - ("escrow", "resolved"): [escrow_id: u64, beneficiary_amount: i128, depositor_amount: i128]
 
 
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short,
+    token, vec, Address, BytesN, Env, IntoVal, String, Symbol, Vec,
 };
 
+// ============================================================
+// Errors
+// ============================================================
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum EscrowError {
+    AlreadyInitialized = 1,
+    Unauthorized = 2,
+    InvalidAmount = 3,
+    InvalidPerformance = 4,
+    NotFound = 5,
+    NotApprover = 6,
+    AlreadyReleased = 7,
+    Disputed = 8,
+    TimeLockActive = 9,
+    ApprovalRequired = 10,
+    PerformanceNotMet = 11,
+    NothingToRelease = 12,
+    NotExpired = 13,
+    NothingToRefund = 14,
+    FraudContractNotSet = 15,
+    AlreadyDisputed = 16,
+    NotDisputed = 17,
+    InvalidSplit = 18,
+    InvalidMilestones = 19,
+    MilestoneNotFound = 20,
+    MilestoneReleased = 21,
+    GracePeriodActive = 22,
+    Overflow = 23,
+    InvariantViolated = 24,
+    TokenNotSupported = 25,
+    InvalidBatch = 26,
+    NoPendingAdmin = 27,
+    AdRegistryNotSet = 28,
+    ContentNotLinked = 29,
+    Paused = 30,
+    MilestoneEscrow = 31,
+    AlreadyApproved = 32,
+}
+
 // ============================================================
 // Data Types
 // ============================================================
@@ -27,6 +88,10 @@ pub enum EscrowState {
     Refunded,
     PartiallyReleased,
     Disputed,
+    Resolved,
+    Cancelled,
+    /// Locked funds split between beneficiary and depositor by settle_split
+    SettledSplit,
 }
 
 #[contracttype]
@@ -55,6 +120,42 @@ pub struct EscrowApproval {
     pub timestamp: u64,
 }
 
+/// One beneficiary's terms in a batch created with `create_escrows_batch`
+#[contracttype]
+#[derive(Clone)]
+pub struct EscrowEntry {
+    pub beneficiary: Address,
+    pub amount: i128,
+    pub time_lock_duration: u64,
+    pub performance_threshold: u32,
+    pub expires_in: u64,
+}
+
+/// A release step: once performance reaches `performance_pct`, `release_bps`
+/// of the escrow amount can be released.
+#[contracttype]
+#[derive(Clone)]
+pub struct Milestone {
+    pub performance_pct: u32,
+    pub release_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct EscrowDispute {
+    pub raised_by: Address,
+    pub reason: String,
+    pub raised_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct DisputeResolution {
+    pub beneficiary_amount: i128,
+    pub depositor_amount: i128,
+    pub resolved_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct PerformanceMetrics {
@@ -62,6 +163,59 @@ pub struct PerformanceMetrics {
     pub views_delivered: u64,
     pub clicks_delivered: u64,
     pub last_updated: u64,
+    pub last_oracle: Address,
+    /// Number of this update for the escrow, starting at 1
+    pub sequence: u64,
+}
+
+/// Dashboard view of an escrow returned by `get_escrow_summary`.
+/// The latest performance update is flattened into its own fields;
+/// `performance_updated_at` is `None` until the oracle first reports.
+/// Both countdowns are zero once their timestamp has passed.
+#[contracttype]
+#[derive(Clone)]
+pub struct EscrowSummary {
+    pub escrow_id: u64,
+    pub escrow: Escrow,
+    pub current_performance: u32,
+    pub views_delivered: u64,
+    pub clicks_delivered: u64,
+    pub performance_updated_at: Option<u64>,
+    pub approval_count: u32,
+    pub pending_approvers: Vec<Address>,
+    pub can_release: bool,
+    pub seconds_until_unlock: u64,
+    pub seconds_until_expiry: u64,
+}
+
+/// Ad-registry content whose tracked views measure an escrow's performance.
+/// Performance is `total_views * 100 / views_target`, capped at 100.
+#[contracttype]
+#[derive(Clone)]
+pub struct ContentLink {
+    pub content_id: u64,
+    pub views_target: u64,
+}
+
+/// Mirror of the ad registry's `ContentPerformance`, as returned by its
+/// `get_performance` view
+#[contracttype]
+#[derive(Clone)]
+pub struct ContentPerformance {
+    pub total_views: u64,
+    pub total_clicks: u64,
+    pub unique_viewers: u64,
+    pub click_through_rate: u64,
+    pub last_shown: u64,
+}
+
+/// Ring buffer position for an escrow's performance history. The capacity is
+/// fixed at the first update so later size changes don't scramble slots.
+#[contracttype]
+#[derive(Clone)]
+pub struct PerfHistoryHead {
+    pub count: u64,
+    pub capacity: u32,
 }
 
 // ============================================================
@@ -81,6 +235,30 @@ pub enum DataKey {
     ApprovalCount(u64),
     RequiredApprover(u64, Address),
     Performance(u64),
+    PayoutContract,
+    Dispute(u64),
+    Resolution(u64),
+    Milestones(u64),
+    Milestone(u64, u32),
+    CampaignEscrows(u64),
+    DepositorEscrows(Address),
+    RefundGrace,
+    Oracle(Address),
+    PerfHistorySize,
+    PerfHistoryHead(u64),
+    PerfHistory(u64, u32),
+    Approvers(u64),
+    SupportedToken(Address),
+    EscrowToken(u64),
+    AutoRelease(u64),
+    PendingAdmin,
+    EscrowTtlThreshold,
+    EscrowTtlBump,
+    AdRegistry,
+    ContentLink(u64),
+    Paused,
+    Arbiter(Address),
+    Arbiters,
 }
 
 // ============================================================
@@ -91,17 +269,35 @@ const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
+const LEDGER_SECONDS: u64 = 5;
+const BPS_DENOMINATOR: u32 = 10_000;
+const MAX_LIST_LIMIT: u32 = 100;
+const DEFAULT_REFUND_GRACE: u64 = 7 * 86_400;
+// Each escrow writes several ledger entries; keep a batch well inside the per-tx write limits
+const MAX_BATCH_SIZE: u32 = 20;
+const DEFAULT_PERF_HISTORY_SIZE: u32 = 10;
+const MAX_PERF_HISTORY_SIZE: u32 = 100;
 
 #[contract]
 pub struct EscrowVaultContract;
 
 #[contractimpl]
 impl EscrowVaultContract {
-    /// Initialize the contract
-    pub fn initialize(env: Env, admin: Address, token_address: Address, oracle: Address) {
+    /// Initialize the contract. `ttl_threshold` / `ttl_bump` (in ledgers) tune how
+    /// far escrow entries are kept alive past their expiry; None uses the defaults.
+    /// `ad_registry` enables `sync_performance` for escrows linked to ad content.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        token_address: Address,
+        oracle: Address,
+        ttl_threshold: Option<u32>,
+        ttl_bump: Option<u32>,
+        ad_registry: Option<Address>,
+    ) -> Result<(), EscrowError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("already initialized");
+            return Err(EscrowError::AlreadyInitialized);
         }
         admin.require_auth();
         env.storage().instance().set(&DataKey::Admin, &admin);
@@ -117,133 +313,496 @@ impl EscrowVaultContract {
         env.storage()
             .instance()
             .set(&DataKey::EscrowNonce, &0u64);
+        let ttl_threshold = ttl_threshold.unwrap_or(PERSISTENT_LIFETIME_THRESHOLD);
+        let ttl_bump = ttl_bump.unwrap_or(PERSISTENT_BUMP_AMOUNT);
+        if ttl_threshold > ttl_bump {
+            return Err(EscrowError::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::EscrowTtlThreshold, &ttl_threshold);
+        env.storage()
+            .instance()
+            .set(&DataKey::EscrowTtlBump, &ttl_bump);
+        if let Some(registry) = ad_registry {
+            env.storage().instance().set(&DataKey::AdRegistry, &registry);
+        }
+        Ok(())
     }
 
-    pub fn set_fraud_contract(env: Env, admin: Address, fraud_contract: Address) {
+    /// Replace the contract code (admin only)
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), EscrowError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        admin.require_auth();
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if admin != stored_admin {
-            panic!("unauthorized");
+        Self::_require_admin(&env, &admin)?;
+
+        env.events().publish(
+            (symbol_short!("admin"), symbol_short!("upgraded")),
+            new_wasm_hash.clone(),
+        );
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    /// First step of an admin handover; the new admin must accept before it takes effect
+    pub fn propose_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+        env.events().publish(
+            (symbol_short!("admin"), symbol_short!("proposed")),
+            (admin, new_admin),
+        );
+        Ok(())
+    }
+
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        new_admin.require_auth();
+        let pending: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(EscrowError::NoPendingAdmin)?;
+        if new_admin != pending {
+            return Err(EscrowError::Unauthorized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        env.events().publish(
+            (symbol_short!("admin"), symbol_short!("accepted")),
+            new_admin,
+        );
+        Ok(())
+    }
+
+    /// Emergency stop: block new escrows, approvals, releases and performance
+    /// updates. Refunds of expired escrows and mutual cancellations stay open
+    /// so depositors can always exit.
+    pub fn pause(env: Env, admin: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::Paused, &true);
+        env.events().publish((symbol_short!("admin"), symbol_short!("paused")), admin);
+        Ok(())
+    }
+
+    pub fn unpause(env: Env, admin: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().remove(&DataKey::Paused);
+        env.events().publish((symbol_short!("admin"), symbol_short!("unpaused")), admin);
+        Ok(())
+    }
+
+    pub fn set_ad_registry(env: Env, admin: Address, ad_registry: Option<Address>) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        match ad_registry {
+            Some(registry) => env.storage().instance().set(&DataKey::AdRegistry, &registry),
+            None => env.storage().instance().remove(&DataKey::AdRegistry),
         }
+        Ok(())
+    }
+
+    pub fn set_fraud_contract(env: Env, admin: Address, fraud_contract: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
         env.storage().instance().set(&DataKey::FraudContract, &fraud_contract);
+        Ok(())
+    }
+
+    /// Replace the primary oracle set at initialize
+    pub fn set_oracle(env: Env, admin: Address, new_oracle: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+
+        if let Some(old_oracle) = env.storage().instance().get::<DataKey, Address>(&DataKey::OracleAddress) {
+            env.events().publish((symbol_short!("oracle"), symbol_short!("removed")), old_oracle);
+        }
+        env.storage().instance().set(&DataKey::OracleAddress, &new_oracle);
+        env.events().publish((symbol_short!("oracle"), symbol_short!("added")), new_oracle);
+        Ok(())
+    }
+
+    /// Authorize an additional oracle to report performance
+    pub fn add_oracle(env: Env, admin: Address, oracle: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+
+        let _ttl_key = DataKey::Oracle(oracle.clone());
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish((symbol_short!("oracle"), symbol_short!("added")), oracle);
+        Ok(())
+    }
+
+    /// Revoke an oracle, including the primary one
+    pub fn remove_oracle(env: Env, admin: Address, oracle: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        if !Self::_is_oracle(&env, &oracle) {
+            return Err(EscrowError::NotFound);
+        }
+
+        env.storage().persistent().remove(&DataKey::Oracle(oracle.clone()));
+        if env.storage().instance().get::<DataKey, Address>(&DataKey::OracleAddress) == Some(oracle.clone()) {
+            env.storage().instance().remove(&DataKey::OracleAddress);
+        }
+        env.events().publish((symbol_short!("oracle"), symbol_short!("removed")), oracle);
+        Ok(())
+    }
+
+    /// Grant the arbiter role: arbiters can approve and release escrows and
+    /// resolve disputes, but not manage oracles, tokens or upgrades
+    pub fn add_arbiter(env: Env, admin: Address, arbiter: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        if Self::_is_arbiter(&env, &arbiter) {
+            return Ok(());
+        }
+
+        let _ttl_key = DataKey::Arbiter(arbiter.clone());
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let mut arbiters: Vec<Address> = env.storage().instance().get(&DataKey::Arbiters).unwrap_or(Vec::new(&env));
+        arbiters.push_back(arbiter.clone());
+        env.storage().instance().set(&DataKey::Arbiters, &arbiters);
+        env.events().publish((symbol_short!("arbiter"), symbol_short!("added")), arbiter);
+        Ok(())
+    }
+
+    pub fn remove_arbiter(env: Env, admin: Address, arbiter: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        if !Self::_is_arbiter(&env, &arbiter) {
+            return Err(EscrowError::NotFound);
+        }
+
+        env.storage().persistent().remove(&DataKey::Arbiter(arbiter.clone()));
+        let mut arbiters: Vec<Address> = env.storage().instance().get(&DataKey::Arbiters).unwrap_or(Vec::new(&env));
+        if let Some(index) = arbiters.first_index_of(&arbiter) {
+            arbiters.remove(index);
+        }
+        env.storage().instance().set(&DataKey::Arbiters, &arbiters);
+        env.events().publish((symbol_short!("arbiter"), symbol_short!("removed")), arbiter);
+        Ok(())
+    }
+
+    /// Allow escrows to be created in another token (admin only)
+    pub fn add_supported_token(env: Env, admin: Address, token: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        let _ttl_key = DataKey::SupportedToken(token.clone());
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish(
+            (symbol_short!("token"), symbol_short!("added")),
+            token,
+        );
+        Ok(())
+    }
+
+    /// Stop accepting a token for new escrows; existing escrows keep paying out in it
+    pub fn remove_supported_token(env: Env, admin: Address, token: Address) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().persistent().remove(&DataKey::SupportedToken(token.clone()));
+        env.events().publish(
+            (symbol_short!("token"), symbol_short!("removed")),
+            token,
+        );
+        Ok(())
+    }
+
+    /// Number of performance updates kept per escrow (applies to escrows with no updates yet)
+    pub fn set_perf_history_size(env: Env, admin: Address, size: u32) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        if size == 0 || size > MAX_PERF_HISTORY_SIZE {
+            return Err(EscrowError::InvalidAmount);
+        }
+        env.storage().instance().set(&DataKey::PerfHistorySize, &size);
+        Ok(())
     }
 
-    pub fn hold_for_fraud(env: Env, fraud_contract: Address, escrow_id: u64) {
+    /// Extra time the beneficiary gets to claim an expired escrow that is releasable
+    pub fn set_refund_grace(env: Env, admin: Address, seconds: u64) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::RefundGrace, &seconds);
+        Ok(())
+    }
+
+    /// Route releases through the payout-automation earnings ledger (None pays directly)
+    pub fn set_payout_contract(env: Env, admin: Address, payout_contract: Option<Address>) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin)?;
+        match payout_contract {
+            Some(addr) => env.storage().instance().set(&DataKey::PayoutContract, &addr),
+            None => env.storage().instance().remove(&DataKey::PayoutContract),
+        }
+        Ok(())
+    }
+
+    pub fn hold_for_fraud(env: Env, fraud_contract: Address, escrow_id: u64) -> Result<(), EscrowError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         fraud_contract.require_auth();
 
-        let stored_fraud: Address = env.storage().instance().get(&DataKey::FraudContract).expect("fraud contract not set");
+        let stored_fraud: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::FraudContract)
+            .ok_or(EscrowError::FraudContractNotSet)?;
         if fraud_contract != stored_fraud {
-            panic!("unauthorized fraud contract");
+            return Err(EscrowError::Unauthorized);
         }
 
-        let mut escrow: Escrow = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Escrow(escrow_id))
-            .expect("escrow not found");
+        let mut escrow = Self::_load_escrow(&env, escrow_id)?;
 
         escrow.state = EscrowState::Disputed;
         
-        let _ttl_key = DataKey::Escrow(escrow_id);
-        env.storage().persistent().set(&_ttl_key, &escrow);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Self::_save_escrow(&env, escrow_id, &escrow)?;
+        Ok(())
     }
 
-    /// Create a new escrow
+    /// Create a new escrow. With `auto_release` set, the oracle update that
+    /// satisfies the last release condition pays the beneficiary straight away.
     pub fn create_escrow(
         env: Env,
         depositor: Address,
         campaign_id: u64,
         beneficiary: Address,
+        token: Address,
         amount: i128,
         time_lock_duration: u64,
         performance_threshold: u32,
         expires_in: u64,
         required_approvers: Vec<Address>,
-    ) -> u64 {
+        auto_release: bool,
+    ) -> Result<u64, EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
+        depositor.require_auth();
+        let escrow_id = Self::_create_escrow(
+            &env,
+            depositor,
+            campaign_id,
+            beneficiary,
+            token,
+            amount,
+            time_lock_duration,
+            performance_threshold,
+            expires_in,
+            required_approvers,
+        )?;
+        if auto_release {
+            let _ttl_key = DataKey::AutoRelease(escrow_id);
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &true);
+            env.storage()
+                .persistent()
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+        Ok(escrow_id)
+    }
+
+    /// Create one escrow per entry, funded by a single transfer of the summed
+    /// amount. Every entry shares the token and required approvers; if any entry
+    /// is invalid nothing is created.
+    pub fn create_escrows_batch(
+        env: Env,
+        depositor: Address,
+        campaign_id: u64,
+        token: Address,
+        required_approvers: Vec<Address>,
+        entries: Vec<EscrowEntry>,
+    ) -> Result<Vec<u64>, EscrowError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
         depositor.require_auth();
 
-        if amount <= 0 {
-            panic!("invalid amount");
+        if entries.is_empty() || entries.len() > MAX_BATCH_SIZE {
+            return Err(EscrowError::InvalidBatch);
         }
-        if performance_threshold > 100 {
-            panic!("invalid performance threshold");
+        if !Self::_is_token_supported(&env, &token) {
+            return Err(EscrowError::TokenNotSupported);
+        }
+        let mut total: i128 = 0;
+        for entry in entries.iter() {
+            Self::_check_escrow_terms(entry.amount, entry.performance_threshold)?;
+            total = total.checked_add(entry.amount).ok_or(EscrowError::Overflow)?;
         }
 
-        // Transfer funds to escrow contract
-        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
-        let token_client = token::Client::new(&env, &token_addr);
-        token_client.transfer(&depositor, &env.current_contract_address(), &amount);
+        token::Client::new(&env, &token).transfer(&depositor, &env.current_contract_address(), &total);
+
+        let mut ids = Vec::new(&env);
+        for entry in entries.iter() {
+            ids.push_back(Self::_record_escrow(
+                &env,
+                depositor.clone(),
+                campaign_id,
+                entry.beneficiary,
+                token.clone(),
+                entry.amount,
+                entry.time_lock_duration,
+                entry.performance_threshold,
+                entry.expires_in,
+                &required_approvers,
+            )?);
+        }
+        Ok(ids)
+    }
 
-        let nonce: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::EscrowNonce)
-            .unwrap_or(0);
-        let escrow_id = nonce + 1;
+    /// Create an escrow released in steps as performance passes each milestone.
+    /// Milestones must rise strictly in performance and their bps sum to 10000.
+    pub fn create_milestone_escrow(
+        env: Env,
+        depositor: Address,
+        campaign_id: u64,
+        beneficiary: Address,
+        token: Address,
+        amount: i128,
+        time_lock_duration: u64,
+        expires_in: u64,
+        required_approvers: Vec<Address>,
+        milestones: Vec<Milestone>,
+    ) -> Result<u64, EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
+        depositor.require_auth();
 
-        let now = env.ledger().timestamp();
-        let escrow = Escrow {
+        if milestones.is_empty() {
+            return Err(EscrowError::InvalidMilestones);
+        }
+        let mut total_bps: u32 = 0;
+        let mut previous_pct: Option<u32> = None;
+        for milestone in milestones.iter() {
+            if milestone.performance_pct > 100
+                || milestone.release_bps == 0
+                || previous_pct.is_some_and(|pct| milestone.performance_pct <= pct)
+            {
+                return Err(EscrowError::InvalidMilestones);
+            }
+            previous_pct = Some(milestone.performance_pct);
+            total_bps = total_bps.checked_add(milestone.release_bps).ok_or(EscrowError::InvalidMilestones)?;
+        }
+        if total_bps != BPS_DENOMINATOR {
+            return Err(EscrowError::InvalidMilestones);
+        }
+
+        let escrow_id = Self::_create_escrow(
+            &env,
+            depositor,
             campaign_id,
-            depositor: depositor.clone(),
             beneficiary,
+            token,
             amount,
-            locked_amount: amount,
-            released_amount: 0,
-            refunded_amount: 0,
-            state: EscrowState::Locked,
-            time_lock_until: now + time_lock_duration,
-            performance_threshold,
-            created_at: now,
-            locked_at: Some(now),
-            released_at: None,
-            expires_at: now + expires_in,
-        };
-
-        let _ttl_key = DataKey::Escrow(escrow_id);
+            time_lock_duration,
+            0,
+            expires_in,
+            required_approvers,
+        )?;
+        let _ttl_key = DataKey::Milestones(escrow_id);
         env.storage()
             .persistent()
-            .set(&_ttl_key, &escrow);
+            .set(&_ttl_key, &milestones);
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        let _ttl_key = DataKey::ApprovalCount(escrow_id);
-        env.storage()
+        Ok(escrow_id)
+    }
+
+    /// Release one milestone's share once performance has reached it (once per milestone)
+    pub fn release_milestone(env: Env, caller: Address, escrow_id: u64, milestone_index: u32) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
+        caller.require_auth();
+
+        let mut escrow = Self::_load_escrow(&env, escrow_id)?;
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if caller != escrow.depositor && caller != escrow.beneficiary && caller != admin && !Self::_is_arbiter(&env, &caller) {
+            return Err(EscrowError::Unauthorized);
+        }
+
+        let milestones: Vec<Milestone> = env
+            .storage()
             .persistent()
-            .set(&_ttl_key, &0u32);
-        env.storage()
+            .get(&DataKey::Milestones(escrow_id))
+            .ok_or(EscrowError::MilestoneNotFound)?;
+        let milestone = milestones.get(milestone_index).ok_or(EscrowError::MilestoneNotFound)?;
+        let done_key = DataKey::Milestone(escrow_id, milestone_index);
+        if env.storage().persistent().has(&done_key) {
+            return Err(EscrowError::MilestoneReleased);
+        }
+
+        Self::_check_can_release(&env, &escrow, escrow_id)?;
+        let performance = env
+            .storage()
             .persistent()
-            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            .get::<DataKey, PerformanceMetrics>(&DataKey::Performance(escrow_id))
+            .map(|perf| perf.current_performance)
+            .unwrap_or(0);
+        if performance < milestone.performance_pct {
+            return Err(EscrowError::PerformanceNotMet);
+        }
 
-        // Register required approvers
-        for approver in required_approvers.iter() {
-            let _ttl_key = DataKey::RequiredApprover(escrow_id, approver.clone());
-            env.storage()
-                .persistent()
-                .set(&_ttl_key, &true);
-            env.storage()
-                .persistent()
-                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        // Share from cumulative bps so the milestones add up to the exact amount
+        let mut bps_before: u32 = 0;
+        for i in 0..milestone_index {
+            bps_before = bps_before
+                .checked_add(milestones.get(i).unwrap().release_bps)
+                .ok_or(EscrowError::Overflow)?;
+        }
+        let bps_through = bps_before.checked_add(milestone.release_bps).ok_or(EscrowError::Overflow)?;
+        let share = Self::_bps_share(escrow.amount, bps_through)?
+            .checked_sub(Self::_bps_share(escrow.amount, bps_before)?)
+            .ok_or(EscrowError::Overflow)?;
+        if share > escrow.locked_amount {
+            return Err(EscrowError::NothingToRelease);
         }
 
+        env.storage().persistent().set(&done_key, &true);
         env.storage()
-            .instance()
-            .set(&DataKey::EscrowNonce, &escrow_id);
+            .persistent()
+            .extend_ttl(&done_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        escrow.locked_amount = escrow.locked_amount.checked_sub(share).ok_or(EscrowError::Overflow)?;
+        escrow.released_amount = escrow.released_amount.checked_add(share).ok_or(EscrowError::Overflow)?;
+        if escrow.locked_amount == 0 {
+            escrow.state = EscrowState::Released;
+            escrow.released_at = Some(env.ledger().timestamp());
+        } else {
+            escrow.state = EscrowState::PartiallyReleased;
+        }
+        Self::_save_escrow(&env, escrow_id, &escrow)?;
+
+        Self::_pay_beneficiary(&env, escrow_id, &escrow.beneficiary, share);
 
         env.events().publish(
-            (symbol_short!("escrow"), symbol_short!("created")),
-            (escrow_id, campaign_id, amount),
+            (symbol_short!("escrow"), symbol_short!("milestone")),
+            (escrow_id, milestone_index, share),
         );
-
-        escrow_id
+        Ok(())
     }
 
-    /// Approve escrow release
-    pub fn approve_release(env: Env, approver: Address, escrow_id: u64) {
+    /// Approve escrow release; each approver counts once
+    pub fn approve_release(env: Env, approver: Address, escrow_id: u64) -> Result<(), EscrowError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
         approver.require_auth();
 
         let is_required: bool = env
@@ -252,18 +811,17 @@ impl EscrowVaultContract {
             .get(&DataKey::RequiredApprover(escrow_id, approver.clone()))
             .unwrap_or(false);
 
-        if !is_required {
-            panic!("not a required approver");
+        if !is_required && !Self::_is_arbiter(&env, &approver) {
+            return Err(EscrowError::NotApprover);
         }
 
-        let escrow: Escrow = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Escrow(escrow_id))
-            .expect("escrow not found");
+        let escrow = Self::_load_escrow(&env, escrow_id)?;
 
         if escrow.state == EscrowState::Released {
-            panic!("already released");
+            return Err(EscrowError::AlreadyReleased);
+        }
+        if env.storage().persistent().has(&DataKey::Approval(escrow_id, approver.clone())) {
+            return Err(EscrowError::AlreadyApproved);
         }
 
         let approval = EscrowApproval {
@@ -271,7 +829,7 @@ impl EscrowVaultContract {
             timestamp: env.ledger().timestamp(),
         };
 
-        let _ttl_key = DataKey::Approval(escrow_id, approver);
+        let _ttl_key = DataKey::Approval(escrow_id, approver.clone());
         env.storage()
             .persistent()
             .set(&_ttl_key, &approval);
@@ -284,159 +842,335 @@ impl EscrowVaultContract {
             .persistent()
             .get(&DataKey::ApprovalCount(escrow_id))
             .unwrap_or(0);
+        let new_count = count.checked_add(1).ok_or(EscrowError::Overflow)?;
         let _ttl_key = DataKey::ApprovalCount(escrow_id);
         env.storage()
             .persistent()
-            .set(&_ttl_key, &(count + 1));
+            .set(&_ttl_key, &new_count);
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        Self::_bump_escrow(&env, escrow_id, &escrow);
+
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("approved")),
+            (escrow_id, approver, new_count),
+        );
+        Ok(())
     }
 
     /// Release full escrow to beneficiary
-    pub fn release_escrow(env: Env, caller: Address, escrow_id: u64) {
+    pub fn release_escrow(env: Env, caller: Address, escrow_id: u64) -> Result<(), EscrowError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
         caller.require_auth();
 
-        let mut escrow: Escrow = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Escrow(escrow_id))
-            .expect("escrow not found");
+        let mut escrow = Self::_load_escrow(&env, escrow_id)?;
 
-        // Must be depositor or admin
+        // Must be depositor, admin or an arbiter
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if caller != escrow.depositor && caller != admin {
-            panic!("unauthorized");
-        }
-
-        Self::_check_can_release(&env, &escrow, escrow_id);
-
-        let locked = escrow.locked_amount;
-        if locked <= 0 {
-            panic!("nothing to release");
+        if caller != escrow.depositor && caller != admin && !Self::_is_arbiter(&env, &caller) {
+            return Err(EscrowError::Unauthorized);
         }
 
-        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
-        let token_client = token::Client::new(&env, &token_addr);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &escrow.beneficiary,
-            &locked,
-        );
+        Self::_release_all(&env, escrow_id, &mut escrow)
+    }
 
-        escrow.locked_amount = 0;
-        escrow.released_amount = escrow.amount;
-        escrow.state = EscrowState::Released;
-        escrow.released_at = Some(env.ledger().timestamp());
+    /// Beneficiary pulls the locked amount once every release condition holds
+    pub fn claim_release(env: Env, beneficiary: Address, escrow_id: u64) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
+        beneficiary.require_auth();
 
-        let _ttl_key = DataKey::Escrow(escrow_id);
-        env.storage()
-            .persistent()
-            .set(&_ttl_key, &escrow);
-        env.storage()
-            .persistent()
-            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let mut escrow = Self::_load_escrow(&env, escrow_id)?;
+        if beneficiary != escrow.beneficiary {
+            return Err(EscrowError::Unauthorized);
+        }
 
-        env.events().publish(
-            (symbol_short!("escrow"), symbol_short!("release")),
-            (escrow_id, locked),
-        );
+        Self::_release_all(&env, escrow_id, &mut escrow)
     }
 
     /// Partial release
-    pub fn release_partial(env: Env, caller: Address, escrow_id: u64, amount: i128) {
+    pub fn release_partial(env: Env, caller: Address, escrow_id: u64, amount: i128) -> Result<(), EscrowError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
         caller.require_auth();
 
-        let mut escrow: Escrow = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Escrow(escrow_id))
-            .expect("escrow not found");
+        let mut escrow = Self::_load_escrow(&env, escrow_id)?;
 
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if caller != escrow.depositor && caller != admin {
-            panic!("unauthorized");
+        if caller != escrow.depositor && caller != admin && !Self::_is_arbiter(&env, &caller) {
+            return Err(EscrowError::Unauthorized);
         }
 
-        Self::_check_can_release(&env, &escrow, escrow_id);
+        Self::_check_whole_release(&env, &escrow, escrow_id)?;
 
         if amount <= 0 || amount > escrow.locked_amount {
-            panic!("invalid amount");
+            return Err(EscrowError::InvalidAmount);
         }
 
-        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
-        let token_client = token::Client::new(&env, &token_addr);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &escrow.beneficiary,
-            &amount,
-        );
-
-        escrow.locked_amount -= amount;
-        escrow.released_amount += amount;
+        escrow.locked_amount = escrow.locked_amount.checked_sub(amount).ok_or(EscrowError::Overflow)?;
+        escrow.released_amount = escrow.released_amount.checked_add(amount).ok_or(EscrowError::Overflow)?;
         escrow.state = EscrowState::PartiallyReleased;
+        Self::_save_escrow(&env, escrow_id, &escrow)?;
 
-        let _ttl_key = DataKey::Escrow(escrow_id);
-        env.storage()
-            .persistent()
-            .set(&_ttl_key, &escrow);
-        env.storage()
-            .persistent()
-            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Self::_pay_beneficiary(&env, escrow_id, &escrow.beneficiary, amount);
 
         env.events().publish(
             (symbol_short!("escrow"), symbol_short!("release_p")), // "release_partial" is too long for symbol_short
             (escrow_id, amount),
         );
+        Ok(())
     }
 
     /// Refund escrow if expired
-    pub fn refund_escrow(env: Env, caller: Address, escrow_id: u64) {
+    pub fn refund_escrow(env: Env, caller: Address, escrow_id: u64) -> Result<(), EscrowError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         caller.require_auth();
 
-        let mut escrow: Escrow = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Escrow(escrow_id))
-            .expect("escrow not found");
+        let mut escrow = Self::_load_escrow(&env, escrow_id)?;
 
+        if escrow.state == EscrowState::Disputed {
+            return Err(EscrowError::Disputed);
+        }
         let now = env.ledger().timestamp();
         if now < escrow.expires_at {
-            panic!("escrow not yet expired");
+            return Err(EscrowError::NotExpired);
+        }
+        // A beneficiary who met every release condition keeps priority for the grace window
+        if now < Self::_refundable_at(&env, &escrow, escrow_id) {
+            return Err(EscrowError::GracePeriodActive);
         }
 
         if escrow.locked_amount <= 0 {
-            panic!("nothing to refund");
+            return Err(EscrowError::NothingToRefund);
         }
 
         let refund = escrow.locked_amount;
-        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
-        let token_client = token::Client::new(&env, &token_addr);
+        escrow.locked_amount = 0;
+        escrow.refunded_amount = escrow.refunded_amount.checked_add(refund).ok_or(EscrowError::Overflow)?;
+        escrow.state = EscrowState::Refunded;
+        Self::_save_escrow(&env, escrow_id, &escrow)?;
+
+        let token_client = token::Client::new(&env, &Self::_escrow_token(&env, escrow_id));
         token_client.transfer(
             &env.current_contract_address(),
             &escrow.depositor,
             &refund,
         );
 
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("refund")),
+            (escrow_id, refund),
+        );
+        Ok(())
+    }
+
+    /// Unwind an escrow early with both parties' consent. Whatever is still
+    /// locked goes back to the depositor; earlier partial releases stand.
+    pub fn cancel_escrow(env: Env, escrow_id: u64) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        let mut escrow = Self::_load_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+        escrow.beneficiary.require_auth();
+
+        if escrow.state == EscrowState::Disputed {
+            return Err(EscrowError::Disputed);
+        }
+        if escrow.locked_amount <= 0 {
+            return Err(EscrowError::NothingToRefund);
+        }
+
+        let refund = escrow.locked_amount;
         escrow.locked_amount = 0;
-        escrow.refunded_amount = refund;
-        escrow.state = EscrowState::Refunded;
+        escrow.refunded_amount = escrow.refunded_amount.checked_add(refund).ok_or(EscrowError::Overflow)?;
+        escrow.state = EscrowState::Cancelled;
+        Self::_save_escrow(&env, escrow_id, &escrow)?;
 
-        let _ttl_key = DataKey::Escrow(escrow_id);
+        token::Client::new(&env, &Self::_escrow_token(&env, escrow_id)).transfer(
+            &env.current_contract_address(),
+            &escrow.depositor,
+            &refund,
+        );
+
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("cancelled")),
+            (escrow_id, refund),
+        );
+        Ok(())
+    }
+
+    /// Settle an escrow early by paying `release_amount` to the beneficiary and
+    /// refunding `refund_amount` to the depositor. The amounts must add up to
+    /// the locked amount. Needs the depositor's auth plus the `cosigner`'s,
+    /// who must be the beneficiary or the admin.
+    pub fn settle_split(
+        env: Env,
+        escrow_id: u64,
+        cosigner: Address,
+        release_amount: i128,
+        refund_amount: i128,
+    ) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
+
+        let mut escrow = Self::_load_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+        cosigner.require_auth();
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if cosigner != escrow.beneficiary && cosigner != admin {
+            return Err(EscrowError::Unauthorized);
+        }
+
+        if escrow.state == EscrowState::Disputed {
+            return Err(EscrowError::Disputed);
+        }
+        if escrow.locked_amount <= 0 {
+            return Err(EscrowError::NothingToRelease);
+        }
+        if release_amount < 0
+            || refund_amount < 0
+            || release_amount.checked_add(refund_amount) != Some(escrow.locked_amount)
+        {
+            return Err(EscrowError::InvalidSplit);
+        }
+
+        escrow.locked_amount = 0;
+        escrow.released_amount = escrow.released_amount.checked_add(release_amount).ok_or(EscrowError::Overflow)?;
+        escrow.refunded_amount = escrow.refunded_amount.checked_add(refund_amount).ok_or(EscrowError::Overflow)?;
+        escrow.state = EscrowState::SettledSplit;
+        if release_amount > 0 {
+            escrow.released_at = Some(env.ledger().timestamp());
+        }
+        Self::_save_escrow(&env, escrow_id, &escrow)?;
+
+        if release_amount > 0 {
+            Self::_pay_beneficiary(&env, escrow_id, &escrow.beneficiary, release_amount);
+            env.events().publish(
+                (symbol_short!("escrow"), symbol_short!("release")),
+                (escrow_id, release_amount),
+            );
+        }
+        if refund_amount > 0 {
+            token::Client::new(&env, &Self::_escrow_token(&env, escrow_id)).transfer(
+                &env.current_contract_address(),
+                &escrow.depositor,
+                &refund_amount,
+            );
+            env.events().publish(
+                (symbol_short!("escrow"), symbol_short!("refund")),
+                (escrow_id, refund_amount),
+            );
+        }
+        Ok(())
+    }
+
+    /// Freeze an escrow pending admin arbitration (depositor or beneficiary)
+    pub fn raise_dispute(env: Env, caller: Address, escrow_id: u64, reason: String) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        caller.require_auth();
+
+        let mut escrow = Self::_load_escrow(&env, escrow_id)?;
+        if caller != escrow.depositor && caller != escrow.beneficiary {
+            return Err(EscrowError::Unauthorized);
+        }
+        if escrow.state == EscrowState::Disputed {
+            return Err(EscrowError::AlreadyDisputed);
+        }
+        if escrow.locked_amount <= 0 {
+            return Err(EscrowError::NothingToRelease);
+        }
+
+        let dispute = EscrowDispute {
+            raised_by: caller.clone(),
+            reason: reason.clone(),
+            raised_at: env.ledger().timestamp(),
+        };
+        let _ttl_key = DataKey::Dispute(escrow_id);
         env.storage()
             .persistent()
-            .set(&_ttl_key, &escrow);
+            .set(&_ttl_key, &dispute);
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 
+        escrow.state = EscrowState::Disputed;
+        Self::_save_escrow(&env, escrow_id, &escrow)?;
+
         env.events().publish(
-            (symbol_short!("escrow"), symbol_short!("refund")),
-            (escrow_id, refund),
+            (symbol_short!("escrow"), symbol_short!("dispute")),
+            (escrow_id, caller, reason),
         );
+        Ok(())
+    }
+
+    /// Settle a disputed escrow by splitting the locked amount (admin or arbiter)
+    pub fn resolve_dispute(
+        env: Env,
+        caller: Address,
+        escrow_id: u64,
+        beneficiary_amount: i128,
+        depositor_amount: i128,
+    ) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        caller.require_auth();
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if caller != admin && !Self::_is_arbiter(&env, &caller) {
+            return Err(EscrowError::Unauthorized);
+        }
+
+        let mut escrow = Self::_load_escrow(&env, escrow_id)?;
+        if escrow.state != EscrowState::Disputed {
+            return Err(EscrowError::NotDisputed);
+        }
+        if beneficiary_amount < 0
+            || depositor_amount < 0
+            || beneficiary_amount.checked_add(depositor_amount) != Some(escrow.locked_amount)
+        {
+            return Err(EscrowError::InvalidSplit);
+        }
+
+        let now = env.ledger().timestamp();
+        escrow.locked_amount = 0;
+        escrow.released_amount = escrow.released_amount.checked_add(beneficiary_amount).ok_or(EscrowError::Overflow)?;
+        escrow.refunded_amount = escrow.refunded_amount.checked_add(depositor_amount).ok_or(EscrowError::Overflow)?;
+        escrow.state = EscrowState::Resolved;
+        if beneficiary_amount > 0 {
+            escrow.released_at = Some(now);
+        }
+        Self::_save_escrow(&env, escrow_id, &escrow)?;
+
+        if beneficiary_amount > 0 {
+            Self::_pay_beneficiary(&env, escrow_id, &escrow.beneficiary, beneficiary_amount);
+        }
+        if depositor_amount > 0 {
+            token::Client::new(&env, &Self::_escrow_token(&env, escrow_id)).transfer(
+                &env.current_contract_address(),
+                &escrow.depositor,
+                &depositor_amount,
+            );
+        }
+
+        let resolution = DisputeResolution {
+            beneficiary_amount,
+            depositor_amount,
+            resolved_at: now,
+        };
+        let _ttl_key = DataKey::Resolution(escrow_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &resolution);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("resolved")),
+            (escrow_id, beneficiary_amount, depositor_amount),
+        );
+        Ok(())
     }
 
     /// Update performance metrics (oracle only)
@@ -447,42 +1181,168 @@ impl EscrowVaultContract {
         performance: u32,
         views: u64,
         clicks: u64,
-    ) {
+    ) -> Result<(), EscrowError> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
         oracle.require_auth();
-        let stored_oracle: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::OracleAddress)
-            .unwrap();
-        if oracle != stored_oracle {
-            panic!("unauthorized");
+        if !Self::_is_oracle(&env, &oracle) {
+            return Err(EscrowError::Unauthorized);
         }
 
         if performance > 100 {
-            panic!("invalid performance");
+            return Err(EscrowError::InvalidPerformance);
         }
 
-        let metrics = PerformanceMetrics {
-            current_performance: performance,
-            views_delivered: views,
-            clicks_delivered: clicks,
-            last_updated: env.ledger().timestamp(),
-        };
+        Self::_record_performance(&env, escrow_id, performance, views, clicks, oracle)?;
+        Ok(())
+    }
 
-        let _ttl_key = DataKey::Performance(escrow_id);
+    /// Link an escrow to ad-registry content so `sync_performance` can measure it
+    /// by tracked views, while funds are still locked. The link decides what
+    /// the beneficiary gets paid on, so setting or changing it takes both the
+    /// depositor and the beneficiary.
+    pub fn link_content(
+        env: Env,
+        depositor: Address,
+        escrow_id: u64,
+        content_id: u64,
+        views_target: u64,
+    ) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        depositor.require_auth();
+        let escrow = Self::_load_escrow(&env, escrow_id)?;
+        if escrow.depositor != depositor {
+            return Err(EscrowError::Unauthorized);
+        }
+        escrow.beneficiary.require_auth();
+        if escrow.locked_amount <= 0 {
+            return Err(EscrowError::AlreadyReleased);
+        }
+        if views_target == 0 {
+            return Err(EscrowError::InvalidAmount);
+        }
+
+        let _ttl_key = DataKey::ContentLink(escrow_id);
         env.storage()
             .persistent()
-            .set(&_ttl_key, &metrics);
+            .set(&_ttl_key, &ContentLink { content_id, views_target });
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Ok(())
+    }
+
+    /// Record performance from the ad registry's tracked views for a linked
+    /// escrow (anyone may call this). Auto-release applies as for oracle updates.
+    pub fn sync_performance(env: Env, escrow_id: u64) -> Result<PerformanceMetrics, EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env)?;
+        let registry: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdRegistry)
+            .ok_or(EscrowError::AdRegistryNotSet)?;
+        let link: ContentLink = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ContentLink(escrow_id))
+            .ok_or(EscrowError::ContentNotLinked)?;
+
+        let perf: ContentPerformance = env
+            .invoke_contract::<Option<ContentPerformance>>(
+                &registry,
+                &Symbol::new(&env, "get_performance"),
+                vec![&env, link.content_id.into_val(&env)],
+            )
+            .ok_or(EscrowError::NotFound)?;
+        let performance = (perf.total_views.saturating_mul(100) / link.views_target).min(100) as u32;
+
+        Self::_record_performance(&env, escrow_id, performance, perf.total_views, perf.total_clicks, registry)
+    }
+
+    /// Re-extend an escrow's storage TTL; anyone may call this
+    pub fn bump_escrow(env: Env, escrow_id: u64) -> Result<(), EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let escrow = Self::_load_escrow(&env, escrow_id)?;
+        Self::_bump_escrow(&env, escrow_id, &escrow);
+        Ok(())
+    }
+
+    pub fn is_auto_release(env: Env, escrow_id: u64) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().has(&DataKey::AutoRelease(escrow_id))
     }
 
     // ============================================================
     // Read-Only Functions
     // ============================================================
 
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    pub fn is_token_supported(env: Env, token: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_is_token_supported(&env, &token)
+    }
+
+    /// Token an escrow is held in, if the escrow exists
+    pub fn get_escrow_token(env: Env, escrow_id: u64) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        if !env.storage().persistent().has(&DataKey::Escrow(escrow_id)) {
+            return None;
+        }
+        Some(Self::_escrow_token(&env, escrow_id))
+    }
+
+    pub fn is_oracle(env: Env, oracle: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_is_oracle(&env, &oracle)
+    }
+
+    pub fn get_refund_grace(env: Env) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .get(&DataKey::RefundGrace)
+            .unwrap_or(DEFAULT_REFUND_GRACE)
+    }
+
+    /// Earliest time refund_escrow can succeed; 0 for an unknown escrow
+    pub fn get_refundable_at(env: Env, escrow_id: u64) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        match Self::_load_escrow(&env, escrow_id) {
+            Ok(escrow) => Self::_refundable_at(&env, &escrow, escrow_id),
+            Err(_) => 0,
+        }
+    }
+
+    pub fn get_payout_contract(env: Env) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::PayoutContract)
+    }
+
+    pub fn get_ad_registry(env: Env) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::AdRegistry)
+    }
+
+    pub fn get_content_link(env: Env, escrow_id: u64) -> Option<ContentLink> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::ContentLink(escrow_id))
+    }
+
     pub fn get_escrow(env: Env, escrow_id: u64) -> Option<Escrow> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage()
@@ -490,6 +1350,47 @@ impl EscrowVaultContract {
             .get(&DataKey::Escrow(escrow_id))
     }
 
+    pub fn get_dispute(env: Env, escrow_id: u64) -> Option<EscrowDispute> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::Dispute(escrow_id))
+    }
+
+    pub fn get_resolution(env: Env, escrow_id: u64) -> Option<DisputeResolution> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::Resolution(escrow_id))
+    }
+
+    /// Escrow ids for a campaign in creation order; `limit` is capped at 100
+    pub fn list_escrows_by_campaign(env: Env, campaign_id: u64, offset: u32, limit: u32) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_page(&env, DataKey::CampaignEscrows(campaign_id), offset, limit)
+    }
+
+    /// Funds still locked for a campaign in `token`, summed over its Locked
+    /// and PartiallyReleased escrows. Lets other contracts check a campaign
+    /// can cover a spend before accepting it.
+    pub fn get_campaign_locked(env: Env, campaign_id: u64, token: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_campaign_locked(&env, campaign_id, None, &token)
+    }
+
+    /// Like get_campaign_locked, counting only escrows `depositor` funded, so
+    /// callers can tie a spender to the campaign it spends for
+    pub fn get_campaign_locked_by(env: Env, campaign_id: u64, depositor: Address, token: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_campaign_locked(&env, campaign_id, Some(&depositor), &token)
+    }
+
+    /// Escrow ids created by a depositor in creation order; `limit` is capped at 100
+    pub fn list_escrows_by_depositor(env: Env, depositor: Address, offset: u32, limit: u32) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_page(&env, DataKey::DepositorEscrows(depositor), offset, limit)
+    }
+
     pub fn get_performance(env: Env, escrow_id: u64) -> Option<PerformanceMetrics> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage()
@@ -497,6 +1398,62 @@ impl EscrowVaultContract {
             .get(&DataKey::Performance(escrow_id))
     }
 
+    /// Retained performance updates, oldest first; `limit` is capped at 100
+    pub fn get_performance_history(env: Env, escrow_id: u64, offset: u32, limit: u32) -> Vec<PerformanceMetrics> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut result = Vec::new(&env);
+        let head: PerfHistoryHead = match env.storage().persistent().get(&DataKey::PerfHistoryHead(escrow_id)) {
+            Some(head) => head,
+            None => return result,
+        };
+
+        let retained = head.count.min(head.capacity as u64);
+        let oldest = head.count - retained;
+        let end = retained.min((offset as u64).saturating_add(limit.min(MAX_LIST_LIMIT) as u64));
+        for i in offset as u64..end {
+            let slot = ((oldest + i) % head.capacity as u64) as u32;
+            if let Some(metrics) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, PerformanceMetrics>(&DataKey::PerfHistory(escrow_id, slot))
+            {
+                result.push_back(metrics);
+            }
+        }
+        result
+    }
+
+    pub fn get_required_approvers(env: Env, escrow_id: u64) -> Vec<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::Approvers(escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_approval(env: Env, escrow_id: u64, approver: Address) -> Option<EscrowApproval> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::Approval(escrow_id, approver))
+    }
+
+    /// Required approvers who haven't approved yet
+    pub fn get_pending_approvers(env: Env, escrow_id: u64) -> Vec<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_pending_approvers(&env, escrow_id)
+    }
+
+    pub fn is_arbiter(env: Env, address: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_is_arbiter(&env, &address)
+    }
+
+    pub fn get_arbiters(env: Env) -> Vec<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::Arbiters).unwrap_or(Vec::new(&env))
+    }
+
     pub fn get_approval_count(env: Env, escrow_id: u64) -> u32 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage()
@@ -507,52 +1464,534 @@ impl EscrowVaultContract {
 
     pub fn can_release(env: Env, escrow_id: u64) -> bool {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        if let Some(escrow) = env
+        match env
             .storage()
             .persistent()
             .get::<DataKey, Escrow>(&DataKey::Escrow(escrow_id))
         {
-            let now = env.ledger().timestamp();
-            let time_ok = now >= escrow.time_lock_until;
-            let min_threshold: u32 = env
-                .storage()
-                .instance()
-                .get(&DataKey::MinApprovalThreshold)
-                .unwrap_or(1);
-            let approvals: u32 = env
+            Some(escrow) => Self::_check_can_release(&env, &escrow, escrow_id).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Escrow, performance, approvals and timing in one read, for dashboards
+    pub fn get_escrow_summary(env: Env, escrow_id: u64) -> Option<EscrowSummary> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_summary(&env, escrow_id)
+    }
+
+    /// Summaries for up to `MAX_BATCH_SIZE` escrows; unknown ids are skipped
+    pub fn get_escrow_summaries(env: Env, ids: Vec<u64>) -> Result<Vec<EscrowSummary>, EscrowError> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        if ids.len() > MAX_BATCH_SIZE {
+            return Err(EscrowError::InvalidBatch);
+        }
+        let mut summaries = Vec::new(&env);
+        for escrow_id in ids.iter() {
+            if let Some(summary) = Self::_summary(&env, escrow_id) {
+                summaries.push_back(summary);
+            }
+        }
+        Ok(summaries)
+    }
+
+    // ============================================================
+    // Internal Helpers
+    // ============================================================
+
+    fn _campaign_locked(env: &Env, campaign_id: u64, depositor: Option<&Address>, token: &Address) -> i128 {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CampaignEscrows(campaign_id))
+            .unwrap_or(Vec::new(env));
+        let mut locked = 0;
+        for escrow_id in ids.iter() {
+            let escrow: Escrow = match env.storage().persistent().get(&DataKey::Escrow(escrow_id)) {
+                Some(escrow) => escrow,
+                None => continue,
+            };
+            let active = escrow.state == EscrowState::Locked || escrow.state == EscrowState::PartiallyReleased;
+            if active
+                && depositor.is_none_or(|depositor| escrow.depositor == *depositor)
+                && Self::_escrow_token(env, escrow_id) == *token
+            {
+                locked += escrow.locked_amount;
+            }
+        }
+        locked
+    }
+
+    fn _pending_approvers(env: &Env, escrow_id: u64) -> Vec<Address> {
+        let mut pending = Vec::new(env);
+        let approvers: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Approvers(escrow_id))
+            .unwrap_or(Vec::new(env));
+        for approver in approvers.iter() {
+            if !env.storage().persistent().has(&DataKey::Approval(escrow_id, approver.clone())) {
+                pending.push_back(approver);
+            }
+        }
+        pending
+    }
+
+    fn _summary(env: &Env, escrow_id: u64) -> Option<EscrowSummary> {
+        let escrow: Escrow = env.storage().persistent().get(&DataKey::Escrow(escrow_id))?;
+        let now = env.ledger().timestamp();
+        let performance: Option<PerformanceMetrics> = env.storage().persistent().get(&DataKey::Performance(escrow_id));
+        let (current_performance, views_delivered, clicks_delivered, performance_updated_at) = match performance {
+            Some(perf) => (perf.current_performance, perf.views_delivered, perf.clicks_delivered, Some(perf.last_updated)),
+            None => (0, 0, 0, None),
+        };
+        Some(EscrowSummary {
+            escrow_id,
+            current_performance,
+            views_delivered,
+            clicks_delivered,
+            performance_updated_at,
+            approval_count: env
                 .storage()
                 .persistent()
                 .get(&DataKey::ApprovalCount(escrow_id))
-                .unwrap_or(0);
-            let approvals_ok = approvals >= min_threshold;
+                .unwrap_or(0),
+            pending_approvers: Self::_pending_approvers(env, escrow_id),
+            can_release: Self::_check_can_release(env, &escrow, escrow_id).is_ok(),
+            seconds_until_unlock: escrow.time_lock_until.saturating_sub(now),
+            seconds_until_expiry: escrow.expires_at.saturating_sub(now),
+            escrow,
+        })
+    }
 
-            let perf_ok = if let Some(perf) = env
-                .storage()
+    /// Take the deposit and store a new escrow
+    fn _create_escrow(
+        env: &Env,
+        depositor: Address,
+        campaign_id: u64,
+        beneficiary: Address,
+        token: Address,
+        amount: i128,
+        time_lock_duration: u64,
+        performance_threshold: u32,
+        expires_in: u64,
+        required_approvers: Vec<Address>,
+    ) -> Result<u64, EscrowError> {
+        if !Self::_is_token_supported(env, &token) {
+            return Err(EscrowError::TokenNotSupported);
+        }
+        Self::_check_escrow_terms(amount, performance_threshold)?;
+
+        // Transfer funds to escrow contract
+        token::Client::new(env, &token).transfer(&depositor, &env.current_contract_address(), &amount);
+
+        Self::_record_escrow(
+            env,
+            depositor,
+            campaign_id,
+            beneficiary,
+            token,
+            amount,
+            time_lock_duration,
+            performance_threshold,
+            expires_in,
+            &required_approvers,
+        )
+    }
+
+    fn _check_escrow_terms(amount: i128, performance_threshold: u32) -> Result<(), EscrowError> {
+        if amount <= 0 {
+            return Err(EscrowError::InvalidAmount);
+        }
+        if performance_threshold > 100 {
+            return Err(EscrowError::InvalidPerformance);
+        }
+        Ok(())
+    }
+
+    /// Store a new escrow whose deposit has already been transferred in
+    fn _record_escrow(
+        env: &Env,
+        depositor: Address,
+        campaign_id: u64,
+        beneficiary: Address,
+        token: Address,
+        amount: i128,
+        time_lock_duration: u64,
+        performance_threshold: u32,
+        expires_in: u64,
+        required_approvers: &Vec<Address>,
+    ) -> Result<u64, EscrowError> {
+        let now = env.ledger().timestamp();
+        let time_lock_until = now.checked_add(time_lock_duration).ok_or(EscrowError::Overflow)?;
+        let expires_at = now.checked_add(expires_in).ok_or(EscrowError::Overflow)?;
+
+        let nonce: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::EscrowNonce)
+            .unwrap_or(0);
+        let escrow_id = nonce.checked_add(1).ok_or(EscrowError::Overflow)?;
+
+        let escrow = Escrow {
+            campaign_id,
+            depositor: depositor.clone(),
+            beneficiary,
+            amount,
+            locked_amount: amount,
+            released_amount: 0,
+            refunded_amount: 0,
+            state: EscrowState::Locked,
+            time_lock_until,
+            performance_threshold,
+            created_at: now,
+            locked_at: Some(now),
+            released_at: None,
+            expires_at,
+        };
+
+        Self::_save_escrow(env, escrow_id, &escrow)?;
+        let _ttl_key = DataKey::EscrowToken(escrow_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &token);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::ApprovalCount(escrow_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &0u32);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        // Register required approvers
+        let _ttl_key = DataKey::Approvers(escrow_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, required_approvers);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        for approver in required_approvers.iter() {
+            let _ttl_key = DataKey::RequiredApprover(escrow_id, approver.clone());
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &true);
+            env.storage()
                 .persistent()
-                .get::<DataKey, PerformanceMetrics>(&DataKey::Performance(escrow_id))
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+
+        // Now that every per-escrow entry exists, stretch them all to the escrow's lifetime
+        Self::_bump_escrow(env, escrow_id, &escrow);
+
+        Self::_index_escrow(env, DataKey::CampaignEscrows(campaign_id), escrow_id);
+        Self::_index_escrow(env, DataKey::DepositorEscrows(depositor), escrow_id);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::EscrowNonce, &escrow_id);
+
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("created")),
+            (escrow_id, campaign_id, amount),
+        );
+
+        Ok(escrow_id)
+    }
+
+    /// Persist an escrow, refusing any state where the buckets don't add up to the deposit
+    fn _save_escrow(env: &Env, escrow_id: u64, escrow: &Escrow) -> Result<(), EscrowError> {
+        let accounted = escrow
+            .released_amount
+            .checked_add(escrow.refunded_amount)
+            .and_then(|sum| sum.checked_add(escrow.locked_amount))
+            .ok_or(EscrowError::Overflow)?;
+        if accounted != escrow.amount || escrow.locked_amount < 0 {
+            return Err(EscrowError::InvariantViolated);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(escrow_id), escrow);
+        Self::_bump_escrow(env, escrow_id, escrow);
+        Ok(())
+    }
+
+    /// Keep an escrow's core entries alive until `ttl_bump` ledgers past its
+    /// expiry, re-extending once fewer than `ttl_threshold` ledgers remain past it
+    fn _bump_escrow(env: &Env, escrow_id: u64, escrow: &Escrow) {
+        let threshold: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::EscrowTtlThreshold)
+            .unwrap_or(PERSISTENT_LIFETIME_THRESHOLD);
+        let bump: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::EscrowTtlBump)
+            .unwrap_or(PERSISTENT_BUMP_AMOUNT);
+        let until_expiry = escrow.expires_at.saturating_sub(env.ledger().timestamp()) / LEDGER_SECONDS;
+        let until_expiry = u32::try_from(until_expiry).unwrap_or(u32::MAX);
+        let max_ttl = env.storage().max_ttl();
+        let extend_to = until_expiry.saturating_add(bump).min(max_ttl);
+        let threshold = until_expiry.saturating_add(threshold).min(extend_to);
+
+        let keys = [
+            DataKey::Escrow(escrow_id),
+            DataKey::ApprovalCount(escrow_id),
+            DataKey::Performance(escrow_id),
+            DataKey::EscrowToken(escrow_id),
+            DataKey::Approvers(escrow_id),
+        ];
+        for key in keys.iter() {
+            if env.storage().persistent().has(key) {
+                env.storage().persistent().extend_ttl(key, threshold, extend_to);
+            }
+        }
+    }
+
+    /// `bps` of `amount`, rounded down, without overflowing for large amounts
+    fn _bps_share(amount: i128, bps: u32) -> Result<i128, EscrowError> {
+        let denominator = BPS_DENOMINATOR as i128;
+        let whole = (amount / denominator).checked_mul(bps as i128).ok_or(EscrowError::Overflow)?;
+        let rest = (amount % denominator) * bps as i128 / denominator;
+        whole.checked_add(rest).ok_or(EscrowError::Overflow)
+    }
+
+    fn _index_escrow(env: &Env, key: DataKey, escrow_id: u64) {
+        let mut ids: Vec<u64> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+        ids.push_back(escrow_id);
+        env.storage().persistent().set(&key, &ids);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    fn _page(env: &Env, key: DataKey, offset: u32, limit: u32) -> Vec<u64> {
+        let ids: Vec<u64> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+        if offset >= ids.len() {
+            return Vec::new(env);
+        }
+        let end = offset.saturating_add(limit.min(MAX_LIST_LIMIT)).min(ids.len());
+        ids.slice(offset..end)
+    }
+
+    /// Pay released funds to the beneficiary, either directly or by crediting
+    /// their pending earnings on the configured payout-automation contract.
+    /// Beneficiaries payout-automation would refuse (unregistered or
+    /// suspended) are paid directly so releases never fail on their status.
+    fn _pay_beneficiary(env: &Env, escrow_id: u64, beneficiary: &Address, amount: i128) {
+        let token_addr = Self::_escrow_token(env, escrow_id);
+        let token_client = token::Client::new(env, &token_addr);
+
+        match env.storage().instance().get::<DataKey, Address>(&DataKey::PayoutContract) {
+            Some(payout_contract) if Self::_payout_accepts(env, &payout_contract, beneficiary) => {
+                token_client.transfer(&env.current_contract_address(), &payout_contract, &amount);
+                env.invoke_contract::<()>(
+                    &payout_contract,
+                    &Symbol::new(env, "credit_from_escrow"),
+                    vec![
+                        env,
+                        env.current_contract_address().into_val(env),
+                        beneficiary.into_val(env),
+                        token_addr.into_val(env),
+                        amount.into_val(env),
+                        escrow_id.into_val(env),
+                    ],
+                );
+            }
+            _ => token_client.transfer(&env.current_contract_address(), beneficiary, &amount),
+        }
+    }
+
+    fn _payout_accepts(env: &Env, payout_contract: &Address, beneficiary: &Address) -> bool {
+        env.invoke_contract::<bool>(
+            payout_contract,
+            &Symbol::new(env, "is_active_publisher"),
+            vec![env, beneficiary.into_val(env)],
+        )
+    }
+
+    /// Store a performance update and its history slot, then auto-release if
+    /// the escrow opted in and every release condition now holds. Returns the stored metrics.
+    fn _record_performance(
+        env: &Env,
+        escrow_id: u64,
+        performance: u32,
+        views: u64,
+        clicks: u64,
+        source: Address,
+    ) -> Result<PerformanceMetrics, EscrowError> {
+        let head_key = DataKey::PerfHistoryHead(escrow_id);
+        let mut head: PerfHistoryHead = env.storage().persistent().get(&head_key).unwrap_or(PerfHistoryHead {
+            count: 0,
+            capacity: env
+                .storage()
+                .instance()
+                .get(&DataKey::PerfHistorySize)
+                .unwrap_or(DEFAULT_PERF_HISTORY_SIZE),
+        });
+        head.count = head.count.checked_add(1).ok_or(EscrowError::Overflow)?;
+
+        let metrics = PerformanceMetrics {
+            current_performance: performance,
+            views_delivered: views,
+            clicks_delivered: clicks,
+            last_updated: env.ledger().timestamp(),
+            last_oracle: source,
+            sequence: head.count,
+        };
+
+        let _ttl_key = DataKey::Performance(escrow_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &metrics);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let slot = ((head.count - 1) % head.capacity as u64) as u32;
+        let _ttl_key = DataKey::PerfHistory(escrow_id, slot);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &metrics);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .set(&head_key, &head);
+        env.storage()
+            .persistent()
+            .extend_ttl(&head_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("perf")),
+            (escrow_id, performance, views, clicks),
+        );
+
+        if let Ok(mut escrow) = Self::_load_escrow(env, escrow_id) {
+            Self::_bump_escrow(env, escrow_id, &escrow);
+
+            // Opt-in auto-release; if a condition is still unmet the update just stands
+            if env.storage().persistent().has(&DataKey::AutoRelease(escrow_id))
+                && escrow.locked_amount > 0
+                && Self::_check_whole_release(env, &escrow, escrow_id).is_ok()
             {
-                perf.current_performance >= escrow.performance_threshold
-            } else {
-                true
-            };
+                let amount = escrow.locked_amount;
+                Self::_release_all(env, escrow_id, &mut escrow)?;
+                env.events().publish(
+                    (symbol_short!("escrow"), symbol_short!("auto")),
+                    (escrow_id, amount),
+                );
+            }
+        }
+        Ok(metrics)
+    }
 
-            time_ok && approvals_ok && perf_ok
-        } else {
-            false
+    fn _require_not_paused(env: &Env) -> Result<(), EscrowError> {
+        if env.storage().instance().get(&DataKey::Paused).unwrap_or(false) {
+            return Err(EscrowError::Paused);
         }
+        Ok(())
     }
 
-    // ============================================================
-    // Internal Helpers
-    // ============================================================
+    fn _require_admin(env: &Env, admin: &Address) -> Result<(), EscrowError> {
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *admin != stored_admin {
+            return Err(EscrowError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn _is_arbiter(env: &Env, caller: &Address) -> bool {
+        env.storage().persistent().has(&DataKey::Arbiter(caller.clone()))
+    }
+
+    fn _is_oracle(env: &Env, oracle: &Address) -> bool {
+        env.storage().instance().get::<DataKey, Address>(&DataKey::OracleAddress) == Some(oracle.clone())
+            || env.storage().persistent().has(&DataKey::Oracle(oracle.clone()))
+    }
+
+    /// The token set at initialize is always accepted
+    fn _is_token_supported(env: &Env, token: &Address) -> bool {
+        env.storage().instance().get::<DataKey, Address>(&DataKey::TokenAddress) == Some(token.clone())
+            || env.storage().persistent().has(&DataKey::SupportedToken(token.clone()))
+    }
+
+    /// Escrows created before per-escrow tokens fall back to the initialize token
+    fn _escrow_token(env: &Env, escrow_id: u64) -> Address {
+        env.storage()
+            .persistent()
+            .get(&DataKey::EscrowToken(escrow_id))
+            .unwrap_or_else(|| env.storage().instance().get(&DataKey::TokenAddress).unwrap())
+    }
+
+    fn _load_escrow(env: &Env, escrow_id: u64) -> Result<Escrow, EscrowError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Escrow(escrow_id))
+            .ok_or(EscrowError::NotFound)
+    }
+
+    /// Expiry, pushed back by the grace period while the escrow is releasable
+    fn _refundable_at(env: &Env, escrow: &Escrow, escrow_id: u64) -> u64 {
+        if Self::_check_can_release(env, escrow, escrow_id).is_err() {
+            return escrow.expires_at;
+        }
+        let grace: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RefundGrace)
+            .unwrap_or(DEFAULT_REFUND_GRACE);
+        escrow.expires_at.saturating_add(grace)
+    }
+
+    /// Release everything still locked to the beneficiary
+    fn _release_all(env: &Env, escrow_id: u64, escrow: &mut Escrow) -> Result<(), EscrowError> {
+        Self::_check_whole_release(env, escrow, escrow_id)?;
+
+        let locked = escrow.locked_amount;
+        if locked <= 0 {
+            return Err(EscrowError::NothingToRelease);
+        }
+
+        // Persist the release before the token call so a re-entrant call sees nothing locked
+        escrow.locked_amount = 0;
+        escrow.released_amount = escrow.released_amount.checked_add(locked).ok_or(EscrowError::Overflow)?;
+        escrow.state = EscrowState::Released;
+        escrow.released_at = Some(env.ledger().timestamp());
+        Self::_save_escrow(env, escrow_id, escrow)?;
+
+        Self::_pay_beneficiary(env, escrow_id, &escrow.beneficiary, locked);
+
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("release")),
+            (escrow_id, locked),
+        );
+        Ok(())
+    }
+
+    /// Release checks for paths that pay out regardless of milestones; milestone
+    /// escrows only pay out through `release_milestone`
+    fn _check_whole_release(env: &Env, escrow: &Escrow, escrow_id: u64) -> Result<(), EscrowError> {
+        if env.storage().persistent().has(&DataKey::Milestones(escrow_id)) {
+            return Err(EscrowError::MilestoneEscrow);
+        }
+        Self::_check_can_release(env, escrow, escrow_id)
+    }
 
-    fn _check_can_release(env: &Env, escrow: &Escrow, escrow_id: u64) {
+    fn _check_can_release(env: &Env, escrow: &Escrow, escrow_id: u64) -> Result<(), EscrowError> {
         if escrow.state == EscrowState::Disputed {
-            panic!("escrow is disputed due to fraud");
+            return Err(EscrowError::Disputed);
         }
         let now = env.ledger().timestamp();
         if now < escrow.time_lock_until {
-            panic!("time lock active");
+            return Err(EscrowError::TimeLockActive);
         }
 
         let min_threshold: u32 = env
@@ -566,7 +2005,7 @@ impl EscrowVaultContract {
             .get(&DataKey::ApprovalCount(escrow_id))
             .unwrap_or(0);
         if approvals < min_threshold {
-            panic!("approval required");
+            return Err(EscrowError::ApprovalRequired);
         }
 
         if let Some(perf) = env
@@ -575,9 +2014,10 @@ impl EscrowVaultContract {
             .get::<DataKey, PerformanceMetrics>(&DataKey::Performance(escrow_id))
         {
             if perf.current_performance < escrow.performance_threshold {
-                panic!("performance threshold not met");
+                return Err(EscrowError::PerformanceNotMet);
             }
         }
+        Ok(())
     }
 }
 
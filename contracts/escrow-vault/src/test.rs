@@ -1,6 +1,6 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::{Address as _, Events}, Address, Env, vec, IntoVal};
+use soroban_sdk::{testutils::{storage::Persistent as _, Address as _, Events, Ledger}, token, Address, Bytes, BytesN, Env, String, Vec, vec, IntoVal};
 
 #[test]
 fn test_initialize() {
@@ -14,11 +14,10 @@ fn test_initialize() {
     let token = Address::generate(&env);
     let oracle = Address::generate(&env);
 
-    client.initialize(&admin, &token, &oracle);
+    client.initialize(&admin, &token, &oracle, &None, &None, &None);
 }
 
 #[test]
-#[should_panic(expected = "already initialized")]
 fn test_initialize_twice() {
     let env = Env::default();
     env.mock_all_auths();
@@ -30,8 +29,9 @@ fn test_initialize_twice() {
     let token = Address::generate(&env);
     let oracle = Address::generate(&env);
 
-    client.initialize(&admin, &token, &oracle);
-    client.initialize(&admin, &token, &oracle);
+    client.initialize(&admin, &token, &oracle, &None, &None, &None);
+    let result = client.try_initialize(&admin, &token, &oracle, &None, &None, &None);
+    assert_eq!(result, Err(Ok(EscrowError::AlreadyInitialized)));
 }
 
 #[test]
@@ -47,5 +47,1778 @@ fn test_initialize_non_admin_fails() {
     let oracle = Address::generate(&env);
 
     // This should panic because admin didn't authorize it and we haven't mocked it
-    client.initialize(&admin, &token, &oracle);
+    client.initialize(&admin, &token, &oracle, &None, &None, &None);
+}
+
+// ============================================================
+// Payout Automation Integration
+// ============================================================
+
+#[test]
+fn test_release_credits_payout_earnings() {
+    use pulsar_payout_automation::{PayoutAutomationContract, PayoutAutomationContractClient};
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    token::StellarAssetClient::new(&env, &token).mint(&depositor, &10_000_000);
+    let token_client = token::Client::new(&env, &token);
+
+    let vault_id = env.register(EscrowVaultContract, ());
+    let vault = EscrowVaultContractClient::new(&env, &vault_id);
+    vault.initialize(&admin, &token, &oracle, &None, &None, &None);
+
+    let payout_id = env.register(PayoutAutomationContract, ());
+    let payout = PayoutAutomationContractClient::new(&env, &payout_id);
+    payout.initialize(&admin, &token);
+
+    payout.set_escrow_source(&admin, &vault_id);
+    payout.register_publisher(&admin, &beneficiary, &BytesN::from_array(&env, &[1; 32]));
+    vault.set_payout_contract(&admin, &Some(payout_id.clone()));
+
+    let escrow_id = vault.create_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token,
+        &6_000_000,
+        &0,
+        &0,
+        &1_000,
+        &vec![&env, approver.clone()],
+        &false,
+    );
+    vault.approve_release(&approver, &escrow_id);
+    vault.release_escrow(&depositor, &escrow_id);
+
+    let earnings = payout.get_publisher_earnings(&beneficiary).unwrap();
+    assert_eq!(earnings.pending_amount, 6_000_000);
+    assert_eq!(token_client.balance(&payout_id), 6_000_000);
+    assert_eq!(token_client.balance(&beneficiary), 0);
+
+    let accrual = payout.get_accrual(&0).unwrap();
+    assert_eq!(accrual.escrow_id, escrow_id);
+    assert_eq!(accrual.publisher, beneficiary);
+}
+
+#[test]
+fn test_release_to_unregistered_beneficiary_pays_directly() {
+    use pulsar_payout_automation::{PayoutAutomationContract, PayoutAutomationContractClient};
+
+    let env = Env::default();
+    let (vault, admin, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let token_client = token::Client::new(&env, &token);
+
+    let payout_id = env.register(PayoutAutomationContract, ());
+    let payout = PayoutAutomationContractClient::new(&env, &payout_id);
+    payout.initialize(&admin, &token);
+    payout.set_escrow_source(&admin, &vault.address);
+    vault.set_payout_contract(&admin, &Some(payout_id.clone()));
+
+    let escrow_id = vault.create_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token,
+        &6_000_000,
+        &0,
+        &0,
+        &1_000,
+        &vec![&env, approver.clone()],
+        &false,
+    );
+    vault.approve_release(&approver, &escrow_id);
+    vault.release_escrow(&depositor, &escrow_id);
+
+    assert_eq!(token_client.balance(&beneficiary), 6_000_000);
+    assert_eq!(token_client.balance(&payout_id), 0);
+    assert!(payout.get_publisher_earnings(&beneficiary).is_none());
+}
+
+// ============================================================
+// Ad Registry Integration
+// ============================================================
+
+fn registry_content(env: &Env, admin: &Address, tracker: &Address) -> pulsar_ad_registry::AdRegistryContractClient<'static> {
+    use pulsar_ad_registry::{AdRegistryContract, AdRegistryContractClient};
+
+    let registry_id = env.register(AdRegistryContract, ());
+    let registry = AdRegistryContractClient::new(env, &registry_id);
+    registry.initialize(admin);
+    registry.add_tracker(admin, tracker);
+    registry
+}
+
+#[test]
+fn test_sync_performance_releases_on_views() {
+    use pulsar_ad_registry::{ContentFormat, ContentStatus};
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let tracker = Address::generate(&env);
+
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    token::StellarAssetClient::new(&env, &token).mint(&depositor, &10_000);
+    let token_client = token::Client::new(&env, &token);
+
+    let registry = registry_content(&env, &admin, &tracker);
+    let content_id = registry.register_content(
+        &beneficiary,
+        &1,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+        &ContentFormat::Image,
+        &50_000,
+        &String::from_str(&env, "Summer sale"),
+        &String::from_str(&env, "Everything must go"),
+        &String::from_str(&env, "Shop now"),
+        &String::from_str(&env, "https://example.com"),
+        &0,
+    );
+    registry.update_status(&admin, &content_id, &ContentStatus::Approved);
+
+    let vault_id = env.register(EscrowVaultContract, ());
+    let vault = EscrowVaultContractClient::new(&env, &vault_id);
+    vault.initialize(&admin, &token, &oracle, &None, &None, &Some(registry.address.clone()));
+    assert_eq!(vault.get_ad_registry(), Some(registry.address.clone()));
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000, &0, &80, &1_000, &vec![&env, approver.clone()], &true);
+    vault.approve_release(&approver, &escrow_id);
+    vault.link_content(&depositor, &escrow_id, &content_id, &10);
+    let link = vault.get_content_link(&escrow_id).unwrap();
+    assert_eq!((link.content_id, link.views_target), (content_id, 10));
+
+    // Nothing tracked yet
+    assert_eq!(vault.sync_performance(&escrow_id).current_performance, 0);
+
+    for i in 0..7u8 {
+        registry.track_view(&tracker, &content_id, &BytesN::from_array(&env, &[i; 32]));
+    }
+    let metrics = vault.sync_performance(&escrow_id);
+    assert_eq!(metrics.current_performance, 70);
+    assert_eq!(metrics.views_delivered, 7);
+    assert_eq!(metrics.last_oracle, registry.address);
+    assert_eq!(vault.get_escrow(&escrow_id).unwrap().locked_amount, 5_000);
+
+    // Views past the target cap performance at 100
+    for i in 7..12u8 {
+        registry.track_view(&tracker, &content_id, &BytesN::from_array(&env, &[i; 32]));
+    }
+    let metrics = vault.sync_performance(&escrow_id);
+    assert_eq!(metrics.current_performance, 100);
+    assert!(vault.get_escrow(&escrow_id).unwrap().state == EscrowState::Released);
+    assert_eq!(token_client.balance(&beneficiary), 5_000);
+}
+
+#[test]
+fn test_link_content_errors() {
+    let env = Env::default();
+    let (vault, admin, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000, &0, &0, &1_000, &vec![&env, approver.clone()], &true);
+
+    assert_eq!(vault.try_sync_performance(&escrow_id).err(), Some(Ok(EscrowError::AdRegistryNotSet)));
+
+    let registry = registry_content(&env, &admin, &Address::generate(&env));
+    vault.set_ad_registry(&admin, &Some(registry.address.clone()));
+    assert_eq!(vault.try_sync_performance(&escrow_id).err(), Some(Ok(EscrowError::ContentNotLinked)));
+
+    assert_eq!(vault.try_link_content(&beneficiary, &escrow_id, &1, &10), Err(Ok(EscrowError::Unauthorized)));
+    assert_eq!(vault.try_link_content(&depositor, &escrow_id, &1, &0), Err(Ok(EscrowError::InvalidAmount)));
+    assert_eq!(vault.try_link_content(&depositor, &99, &1, &10), Err(Ok(EscrowError::NotFound)));
+
+    // Linked to content the registry does not know
+    vault.link_content(&depositor, &escrow_id, &99, &10);
+    let auths = env.auths();
+    assert!(auths.iter().any(|(address, _)| *address == depositor));
+    assert!(auths.iter().any(|(address, _)| *address == beneficiary));
+    assert_eq!(vault.try_sync_performance(&escrow_id).err(), Some(Ok(EscrowError::NotFound)));
+
+    vault.approve_release(&approver, &escrow_id);
+    vault.release_escrow(&depositor, &escrow_id);
+    assert_eq!(vault.try_link_content(&depositor, &escrow_id, &1, &10), Err(Ok(EscrowError::AlreadyReleased)));
+}
+
+#[test]
+#[should_panic]
+fn test_relink_content_requires_beneficiary() {
+    let env = Env::default();
+    let (vault, admin, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000, &0, &0, &1_000, &vec![&env], &false);
+    let registry = registry_content(&env, &admin, &Address::generate(&env));
+    vault.set_ad_registry(&admin, &Some(registry.address.clone()));
+    vault.link_content(&depositor, &escrow_id, &1, &10);
+
+    // The depositor alone can't point the escrow at content with no views
+    vault
+        .mock_auths(&[soroban_sdk::testutils::MockAuth {
+            address: &depositor,
+            invoke: &soroban_sdk::testutils::MockAuthInvoke {
+                contract: &vault.address,
+                fn_name: "link_content",
+                args: (depositor.clone(), escrow_id, 2u64, 1_000_000u64).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .link_content(&depositor, &escrow_id, &2, &1_000_000);
+}
+
+// ============================================================
+// Error Codes
+// ============================================================
+
+fn setup_vault(env: &Env) -> (EscrowVaultContractClient<'_>, Address, Address, Address, Address) {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let oracle = Address::generate(env);
+    let depositor = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    token::StellarAssetClient::new(env, &token).mint(&depositor, &100_000_000);
+
+    let vault_id = env.register(EscrowVaultContract, ());
+    let vault = EscrowVaultContractClient::new(env, &vault_id);
+    vault.initialize(&admin, &token, &oracle, &None, &None, &None);
+    (vault, admin, oracle, depositor, token)
+}
+
+#[test]
+fn test_create_escrow_errors() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+
+    let result = vault.try_create_escrow(&depositor, &1, &beneficiary, &token, &0, &0, &0, &1_000, &vec![&env], &false);
+    assert_eq!(result, Err(Ok(EscrowError::InvalidAmount)));
+    let result = vault.try_create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &0, &101, &1_000, &vec![&env], &false);
+    assert_eq!(result, Err(Ok(EscrowError::InvalidPerformance)));
+}
+
+#[test]
+fn test_release_errors() {
+    let env = Env::default();
+    let (vault, _, oracle, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+
+    assert_eq!(vault.try_release_escrow(&depositor, &9), Err(Ok(EscrowError::NotFound)));
+
+    let escrow_id = vault.create_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token,
+        &5_000_000,
+        &100,
+        &80,
+        &1_000,
+        &vec![&env, approver.clone()],
+        &false,
+    );
+    assert_eq!(
+        vault.try_release_escrow(&Address::generate(&env), &escrow_id),
+        Err(Ok(EscrowError::Unauthorized))
+    );
+    assert_eq!(vault.try_release_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::TimeLockActive)));
+
+    env.ledger().set_timestamp(100);
+    assert_eq!(vault.try_release_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::ApprovalRequired)));
+
+    assert_eq!(
+        vault.try_approve_release(&Address::generate(&env), &escrow_id),
+        Err(Ok(EscrowError::NotApprover))
+    );
+    vault.approve_release(&approver, &escrow_id);
+    vault.update_performance(&oracle, &escrow_id, &50, &1_000, &10);
+    assert_eq!(vault.try_release_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::PerformanceNotMet)));
+
+    vault.update_performance(&oracle, &escrow_id, &90, &2_000, &20);
+    vault.release_escrow(&depositor, &escrow_id);
+    assert_eq!(vault.try_approve_release(&approver, &escrow_id), Err(Ok(EscrowError::AlreadyReleased)));
+    assert_eq!(vault.try_release_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::NothingToRelease)));
+}
+
+#[test]
+fn test_refund_errors() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000_000, &0, &0, &1_000, &vec![&env], &false);
+    assert_eq!(vault.try_refund_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::NotExpired)));
+
+    env.ledger().set_timestamp(1_000);
+    vault.refund_escrow(&depositor, &escrow_id);
+    assert_eq!(vault.try_refund_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::NothingToRefund)));
+}
+
+// ============================================================
+// Beneficiary Claims
+// ============================================================
+
+#[test]
+fn test_claim_release_by_beneficiary() {
+    let env = Env::default();
+    let (vault, _, oracle, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+
+    let escrow_id = vault.create_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token,
+        &5_000_000,
+        &100,
+        &80,
+        &1_000,
+        &vec![&env, approver.clone()],
+        &false,
+    );
+    assert_eq!(vault.try_claim_release(&beneficiary, &escrow_id), Err(Ok(EscrowError::TimeLockActive)));
+
+    env.ledger().set_timestamp(100);
+    assert_eq!(vault.try_claim_release(&beneficiary, &escrow_id), Err(Ok(EscrowError::ApprovalRequired)));
+    vault.approve_release(&approver, &escrow_id);
+    vault.update_performance(&oracle, &escrow_id, &79, &1_000, &10);
+    assert_eq!(vault.try_claim_release(&beneficiary, &escrow_id), Err(Ok(EscrowError::PerformanceNotMet)));
+    vault.update_performance(&oracle, &escrow_id, &80, &1_000, &10);
+
+    assert_eq!(vault.try_claim_release(&depositor, &escrow_id), Err(Ok(EscrowError::Unauthorized)));
+    assert!(vault.can_release(&escrow_id));
+    vault.claim_release(&beneficiary, &escrow_id);
+
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("escrow"), symbol_short!("release")).into_val(&env),
+                (escrow_id, 5_000_000i128).into_val(&env),
+            ),
+        ]
+    );
+    let escrow = vault.get_escrow(&escrow_id).unwrap();
+    assert!(escrow.state == EscrowState::Released);
+    assert_eq!(escrow.locked_amount, 0);
+}
+
+// ============================================================
+// Disputes
+// ============================================================
+
+#[test]
+fn test_dispute_after_partial_release() {
+    let env = Env::default();
+    let (vault, admin, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let token = token::Client::new(&env, &token);
+
+    let escrow_id = vault.create_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token.address,
+        &10_000_000,
+        &0,
+        &0,
+        &1_000,
+        &vec![&env, approver.clone()],
+        &false,
+    );
+    vault.approve_release(&approver, &escrow_id);
+    vault.release_partial(&depositor, &escrow_id, &4_000_000);
+
+    env.ledger().set_timestamp(50);
+    let reason = String::from_str(&env, "undelivered impressions");
+    vault.raise_dispute(&beneficiary, &escrow_id, &reason);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("escrow"), symbol_short!("dispute")).into_val(&env),
+                (escrow_id, beneficiary.clone(), reason.clone()).into_val(&env),
+            ),
+        ]
+    );
+    let dispute = vault.get_dispute(&escrow_id).unwrap();
+    assert_eq!(dispute.raised_by, beneficiary);
+    assert_eq!(dispute.raised_at, 50);
+
+    // Frozen while disputed
+    assert!(!vault.can_release(&escrow_id));
+    assert!(!vault.get_escrow_summary(&escrow_id).unwrap().can_release);
+    assert_eq!(vault.try_release_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::Disputed)));
+    assert_eq!(vault.try_release_partial(&depositor, &escrow_id, &1), Err(Ok(EscrowError::Disputed)));
+    env.ledger().set_timestamp(1_000);
+    assert_eq!(vault.try_refund_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::Disputed)));
+    assert_eq!(vault.try_raise_dispute(&depositor, &escrow_id, &reason), Err(Ok(EscrowError::AlreadyDisputed)));
+
+    // Split must cover exactly the 6_000_000 still locked
+    assert_eq!(
+        vault.try_resolve_dispute(&admin, &escrow_id, &4_000_000, &1_000_000),
+        Err(Ok(EscrowError::InvalidSplit))
+    );
+    vault.resolve_dispute(&admin, &escrow_id, &2_000_000, &4_000_000);
+
+    assert_eq!(token.balance(&beneficiary), 6_000_000);
+    assert_eq!(token.balance(&depositor), 94_000_000);
+    let escrow = vault.get_escrow(&escrow_id).unwrap();
+    assert!(escrow.state == EscrowState::Resolved);
+    assert_eq!(escrow.locked_amount, 0);
+    assert_eq!(escrow.released_amount, 6_000_000);
+    assert_eq!(escrow.refunded_amount, 4_000_000);
+    assert_eq!(vault.get_resolution(&escrow_id).unwrap().resolved_at, 1_000);
+    assert_eq!(
+        vault.try_resolve_dispute(&admin, &escrow_id, &0, &0),
+        Err(Ok(EscrowError::NotDisputed))
+    );
+}
+
+#[test]
+fn test_non_party_cannot_raise_dispute() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000_000, &0, &0, &1_000, &vec![&env], &false);
+    let result = vault.try_raise_dispute(&Address::generate(&env), &escrow_id, &String::from_str(&env, "spam"));
+    assert_eq!(result, Err(Ok(EscrowError::Unauthorized)));
+    assert!(vault.get_escrow(&escrow_id).unwrap().state == EscrowState::Locked);
+}
+
+// ============================================================
+// Milestones
+// ============================================================
+
+fn milestones(env: &Env, steps: &[(u32, u32)]) -> Vec<Milestone> {
+    let mut result = Vec::new(env);
+    for (performance_pct, release_bps) in steps {
+        result.push_back(Milestone { performance_pct: *performance_pct, release_bps: *release_bps });
+    }
+    result
+}
+
+#[test]
+fn test_milestone_validation() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+
+    for steps in [
+        &[][..],
+        &[(50, 5_000), (50, 5_000)][..],
+        &[(60, 5_000), (40, 5_000)][..],
+        &[(25, 3_000), (50, 3_000)][..],
+        &[(50, 5_000), (101, 5_000)][..],
+    ] {
+        let result = vault.try_create_milestone_escrow(
+            &depositor,
+            &1,
+            &beneficiary,
+            &token,
+            &9_000_000,
+            &0,
+            &1_000,
+            &vec![&env],
+            &milestones(&env, steps),
+        );
+        assert_eq!(result, Err(Ok(EscrowError::InvalidMilestones)));
+    }
+}
+
+#[test]
+fn test_milestones_release_once_each() {
+    let env = Env::default();
+    let (vault, _, oracle, depositor, token) = setup_vault(&env);
+    let token = token::Client::new(&env, &token);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+
+    let escrow_id = vault.create_milestone_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token.address,
+        &10_000_001,
+        &0,
+        &1_000,
+        &vec![&env, approver.clone()],
+        &milestones(&env, &[(25, 3_333), (50, 3_333), (75, 3_334)]),
+    );
+    vault.approve_release(&approver, &escrow_id);
+
+    assert_eq!(vault.try_release_milestone(&beneficiary, &escrow_id, &0), Err(Ok(EscrowError::PerformanceNotMet)));
+
+    // No milestone reached, so the deposit can't leave through the whole-escrow paths
+    assert_eq!(vault.try_claim_release(&beneficiary, &escrow_id), Err(Ok(EscrowError::MilestoneEscrow)));
+    assert_eq!(vault.try_release_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::MilestoneEscrow)));
+    assert_eq!(vault.try_release_partial(&depositor, &escrow_id, &1_000), Err(Ok(EscrowError::MilestoneEscrow)));
+    assert_eq!(token.balance(&beneficiary), 0);
+
+    vault.update_performance(&oracle, &escrow_id, &60, &1_000, &10);
+    assert_eq!(vault.try_release_milestone(&beneficiary, &escrow_id, &2), Err(Ok(EscrowError::PerformanceNotMet)));
+    assert_eq!(vault.try_release_milestone(&beneficiary, &escrow_id, &3), Err(Ok(EscrowError::MilestoneNotFound)));
+    assert_eq!(
+        vault.try_release_milestone(&Address::generate(&env), &escrow_id, &0),
+        Err(Ok(EscrowError::Unauthorized))
+    );
+
+    vault.release_milestone(&beneficiary, &escrow_id, &1);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("escrow"), symbol_short!("milestone")).into_val(&env),
+                (escrow_id, 1u32, 3_333_000i128).into_val(&env),
+            ),
+        ]
+    );
+    vault.release_milestone(&depositor, &escrow_id, &0);
+    assert_eq!(vault.try_release_milestone(&beneficiary, &escrow_id, &0), Err(Ok(EscrowError::MilestoneReleased)));
+    assert!(vault.get_escrow(&escrow_id).unwrap().state == EscrowState::PartiallyReleased);
+
+    vault.update_performance(&oracle, &escrow_id, &75, &2_000, &20);
+    vault.release_milestone(&beneficiary, &escrow_id, &2);
+
+    // Rounding never leaves dust behind
+    let escrow = vault.get_escrow(&escrow_id).unwrap();
+    assert_eq!(token.balance(&beneficiary), 10_000_001);
+    assert_eq!(escrow.locked_amount, 0);
+    assert!(escrow.state == EscrowState::Released);
+}
+
+// ============================================================
+// Listing
+// ============================================================
+
+#[test]
+fn test_campaign_locked_balance() {
+    let env = Env::default();
+    let (vault, admin, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let other_token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    token::StellarAssetClient::new(&env, &other_token).mint(&depositor, &10_000);
+    vault.add_supported_token(&admin, &other_token);
+
+    vault.create_escrow(&depositor, &7, &beneficiary, &token, &1_000, &0, &0, &1_000, &vec![&env], &false);
+    let cancelled = vault.create_escrow(&depositor, &7, &beneficiary, &token, &500, &0, &0, &1_000, &vec![&env], &false);
+    vault.create_escrow(&depositor, &7, &beneficiary, &other_token, &300, &0, &0, &1_000, &vec![&env], &false);
+    vault.create_escrow(&depositor, &8, &beneficiary, &token, &2_000, &0, &0, &1_000, &vec![&env], &false);
+    assert_eq!(vault.get_campaign_locked(&7, &token), 1_500);
+
+    vault.cancel_escrow(&cancelled);
+    assert_eq!(vault.get_campaign_locked(&7, &token), 1_000);
+    assert_eq!(vault.get_campaign_locked(&7, &other_token), 300);
+    assert_eq!(vault.get_campaign_locked(&9, &token), 0);
+
+    // Per-depositor totals leave out other depositors' escrows
+    let other_depositor = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&other_depositor, &10_000);
+    vault.create_escrow(&other_depositor, &7, &beneficiary, &token, &400, &0, &0, &1_000, &vec![&env], &false);
+    assert_eq!(vault.get_campaign_locked(&7, &token), 1_400);
+    assert_eq!(vault.get_campaign_locked_by(&7, &depositor, &token), 1_000);
+    assert_eq!(vault.get_campaign_locked_by(&7, &other_depositor, &token), 400);
+    assert_eq!(vault.get_campaign_locked_by(&8, &other_depositor, &token), 0);
+}
+
+#[test]
+fn test_list_escrows_pagination() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let other_depositor = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&other_depositor, &10_000_000);
+    let beneficiary = Address::generate(&env);
+
+    let mut ids = Vec::new(&env);
+    for (campaign_id, from) in [
+        (1u64, &depositor),
+        (2, &depositor),
+        (1, &other_depositor),
+        (1, &depositor),
+        (2, &other_depositor),
+    ] {
+        ids.push_back(vault.create_escrow(from, &campaign_id, &beneficiary, &token, &1_000, &0, &0, &1_000, &vec![&env], &false));
+    }
+    let id = |i: u32| ids.get(i).unwrap();
+
+    assert_eq!(vault.list_escrows_by_campaign(&1, &0, &10), vec![&env, id(0), id(2), id(3)]);
+    assert_eq!(vault.list_escrows_by_campaign(&1, &1, &1), vec![&env, id(2)]);
+    assert_eq!(vault.list_escrows_by_campaign(&1, &2, &10), vec![&env, id(3)]);
+    assert_eq!(vault.list_escrows_by_campaign(&1, &3, &10), vec![&env]);
+    assert_eq!(vault.list_escrows_by_campaign(&2, &0, &10), vec![&env, id(1), id(4)]);
+    assert_eq!(vault.list_escrows_by_campaign(&3, &0, &10), vec![&env]);
+
+    assert_eq!(vault.list_escrows_by_depositor(&depositor, &0, &2), vec![&env, id(0), id(1)]);
+    assert_eq!(vault.list_escrows_by_depositor(&depositor, &2, &2), vec![&env, id(3)]);
+    assert_eq!(vault.list_escrows_by_depositor(&other_depositor, &0, &u32::MAX), vec![&env, id(2), id(4)]);
+    assert_eq!(vault.list_escrows_by_depositor(&other_depositor, &u32::MAX, &10), vec![&env]);
+}
+
+#[test]
+fn test_escrow_summary_around_lock_boundary() {
+    let env = Env::default();
+    let (vault, _, oracle, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let other_approver = Address::generate(&env);
+
+    env.ledger().set_timestamp(1_000);
+    let escrow_id = vault.create_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token,
+        &5_000,
+        &100,
+        &50,
+        &500,
+        &vec![&env, approver.clone(), other_approver.clone()],
+        &false,
+    );
+    assert!(vault.get_escrow_summary(&99).is_none());
+
+    let summary = vault.get_escrow_summary(&escrow_id).unwrap();
+    assert_eq!(summary.escrow_id, escrow_id);
+    assert_eq!(summary.escrow.amount, 5_000);
+    assert_eq!(summary.performance_updated_at, None);
+    assert_eq!(summary.approval_count, 0);
+    assert_eq!(summary.pending_approvers, vec![&env, approver.clone(), other_approver.clone()]);
+    assert!(!summary.can_release);
+    assert_eq!(summary.seconds_until_unlock, 100);
+    assert_eq!(summary.seconds_until_expiry, 500);
+
+    vault.approve_release(&approver, &escrow_id);
+    vault.update_performance(&oracle, &escrow_id, &60, &1_200, &30);
+
+    env.ledger().set_timestamp(1_099);
+    let summary = vault.get_escrow_summary(&escrow_id).unwrap();
+    assert_eq!(summary.current_performance, 60);
+    assert_eq!(summary.views_delivered, 1_200);
+    assert_eq!(summary.clicks_delivered, 30);
+    assert_eq!(summary.performance_updated_at, Some(1_000));
+    assert_eq!(summary.approval_count, 1);
+    assert_eq!(summary.pending_approvers, vec![&env, other_approver.clone()]);
+    assert_eq!(summary.seconds_until_unlock, 1);
+    assert!(!summary.can_release);
+
+    env.ledger().set_timestamp(1_100);
+    let summary = vault.get_escrow_summary(&escrow_id).unwrap();
+    assert_eq!(summary.seconds_until_unlock, 0);
+    assert_eq!(summary.seconds_until_expiry, 400);
+    assert!(summary.can_release);
+    assert_eq!(summary.can_release, vault.can_release(&escrow_id));
+
+    env.ledger().set_timestamp(1_600);
+    let summary = vault.get_escrow_summary(&escrow_id).unwrap();
+    assert_eq!(summary.seconds_until_unlock, 0);
+    assert_eq!(summary.seconds_until_expiry, 0);
+}
+
+#[test]
+fn test_escrow_summaries_batch() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+
+    let first = vault.create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &0, &0, &1_000, &vec![&env], &false);
+    let second = vault.create_escrow(&depositor, &2, &beneficiary, &token, &2_000, &0, &0, &1_000, &vec![&env], &false);
+
+    let summaries = vault.get_escrow_summaries(&vec![&env, second, 99, first]);
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries.get(0).unwrap().escrow_id, second);
+    assert_eq!(summaries.get(1).unwrap().escrow.amount, 1_000);
+    assert_eq!(vault.get_escrow_summaries(&vec![&env]).len(), 0);
+
+    let mut too_many = Vec::new(&env);
+    for id in 0..21u64 {
+        too_many.push_back(id);
+    }
+    assert_eq!(vault.try_get_escrow_summaries(&too_many).err(), Some(Ok(EscrowError::InvalidBatch)));
+}
+
+// ============================================================
+// Cancellation
+// ============================================================
+
+#[test]
+fn test_cancel_escrow_with_both_parties() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let token = token::Client::new(&env, &token);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+
+    let escrow_id = vault.create_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token.address,
+        &5_000_000,
+        &0,
+        &0,
+        &1_000,
+        &vec![&env, approver.clone()],
+        &false,
+    );
+    vault.approve_release(&approver, &escrow_id);
+    vault.release_partial(&depositor, &escrow_id, &2_000_000);
+
+    vault.cancel_escrow(&escrow_id);
+    let auths = env.auths();
+    assert!(auths.iter().any(|(address, _)| *address == depositor));
+    assert!(auths.iter().any(|(address, _)| *address == beneficiary));
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("escrow"), symbol_short!("cancelled")).into_val(&env),
+                (escrow_id, 3_000_000i128).into_val(&env),
+            ),
+        ]
+    );
+
+    // Only what was still locked is refunded
+    assert_eq!(token.balance(&depositor), 98_000_000);
+    assert_eq!(token.balance(&beneficiary), 2_000_000);
+    let escrow = vault.get_escrow(&escrow_id).unwrap();
+    assert!(escrow.state == EscrowState::Cancelled);
+    assert_eq!(escrow.refunded_amount, 3_000_000);
+    assert_eq!(vault.try_cancel_escrow(&escrow_id), Err(Ok(EscrowError::NothingToRefund)));
+}
+
+#[test]
+#[should_panic]
+fn test_cancel_escrow_requires_beneficiary() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000_000, &0, &0, &1_000, &vec![&env], &false);
+
+    vault
+        .mock_auths(&[soroban_sdk::testutils::MockAuth {
+            address: &depositor,
+            invoke: &soroban_sdk::testutils::MockAuthInvoke {
+                contract: &vault.address,
+                fn_name: "cancel_escrow",
+                args: (escrow_id,).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .cancel_escrow(&escrow_id);
+}
+
+#[test]
+fn test_settle_split_pays_both_parties() {
+    let env = Env::default();
+    let (vault, admin, _, depositor, token) = setup_vault(&env);
+    let token = token::Client::new(&env, &token);
+    let beneficiary = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token.address, &5_000_000, &1_000, &90, &10_000, &vec![&env], &false);
+    let depositor_before = token.balance(&depositor);
+
+    vault.settle_split(&escrow_id, &beneficiary, &3_000_000, &2_000_000);
+    let events = env.events().all();
+    let refund_event = events.get(events.len() - 1).unwrap();
+    let release_event = events.get(events.len() - 3).unwrap();
+    assert_eq!(
+        vec![&env, release_event, refund_event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("escrow"), symbol_short!("release")).into_val(&env),
+                (escrow_id, 3_000_000i128).into_val(&env),
+            ),
+            (
+                vault.address.clone(),
+                (symbol_short!("escrow"), symbol_short!("refund")).into_val(&env),
+                (escrow_id, 2_000_000i128).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(token.balance(&beneficiary), 3_000_000);
+    assert_eq!(token.balance(&depositor), depositor_before + 2_000_000);
+
+    let escrow = vault.get_escrow(&escrow_id).unwrap();
+    assert!(escrow.state == EscrowState::SettledSplit);
+    assert_eq!(escrow.locked_amount, 0);
+    assert_eq!(escrow.released_amount, 3_000_000);
+    assert_eq!(escrow.refunded_amount, 2_000_000);
+    assert_eq!(
+        vault.try_settle_split(&escrow_id, &beneficiary, &0, &0),
+        Err(Ok(EscrowError::NothingToRelease))
+    );
+
+    // The admin can co-sign in place of the beneficiary
+    let other = vault.create_escrow(&depositor, &1, &beneficiary, &token.address, &1_000_000, &0, &0, &1_000, &vec![&env], &false);
+    vault.settle_split(&other, &admin, &0, &1_000_000);
+    assert_eq!(vault.get_escrow(&other).unwrap().refunded_amount, 1_000_000);
+}
+
+#[test]
+fn test_settle_split_errors() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000_000, &0, &0, &1_000, &vec![&env], &false);
+
+    assert_eq!(
+        vault.try_settle_split(&escrow_id, &beneficiary, &3_000_000, &1_000_000),
+        Err(Ok(EscrowError::InvalidSplit))
+    );
+    assert_eq!(
+        vault.try_settle_split(&escrow_id, &beneficiary, &6_000_000, &-1_000_000),
+        Err(Ok(EscrowError::InvalidSplit))
+    );
+    assert_eq!(
+        vault.try_settle_split(&escrow_id, &Address::generate(&env), &3_000_000, &2_000_000),
+        Err(Ok(EscrowError::Unauthorized))
+    );
+    assert_eq!(
+        vault.try_settle_split(&escrow_id, &depositor, &3_000_000, &2_000_000),
+        Err(Ok(EscrowError::Unauthorized))
+    );
+
+    vault.raise_dispute(&beneficiary, &escrow_id, &String::from_str(&env, "short delivery"));
+    assert_eq!(
+        vault.try_settle_split(&escrow_id, &beneficiary, &3_000_000, &2_000_000),
+        Err(Ok(EscrowError::Disputed))
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_settle_split_requires_cosigner_auth() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000_000, &0, &0, &1_000, &vec![&env], &false);
+
+    vault
+        .mock_auths(&[soroban_sdk::testutils::MockAuth {
+            address: &depositor,
+            invoke: &soroban_sdk::testutils::MockAuthInvoke {
+                contract: &vault.address,
+                fn_name: "settle_split",
+                args: (escrow_id, beneficiary.clone(), 3_000_000i128, 2_000_000i128).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .settle_split(&escrow_id, &beneficiary, &3_000_000, &2_000_000);
+}
+
+// ============================================================
+// Refund Grace Period
+// ============================================================
+
+#[test]
+fn test_refund_grace_protects_releasable_escrow() {
+    let env = Env::default();
+    let (vault, admin, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+    vault.set_refund_grace(&admin, &500);
+
+    let escrow_id = vault.create_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token,
+        &5_000_000,
+        &0,
+        &0,
+        &1_000,
+        &vec![&env, approver.clone()],
+        &false,
+    );
+    assert_eq!(vault.get_refundable_at(&escrow_id), 1_000);
+    vault.approve_release(&approver, &escrow_id);
+    assert_eq!(vault.get_refundable_at(&escrow_id), 1_500);
+
+    env.ledger().set_timestamp(1_499);
+    assert_eq!(vault.try_refund_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::GracePeriodActive)));
+    vault.claim_release(&beneficiary, &escrow_id);
+}
+
+#[test]
+fn test_refund_after_grace_window() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+    assert_eq!(vault.get_refund_grace(), 7 * 86_400);
+
+    let escrow_id = vault.create_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token,
+        &5_000_000,
+        &0,
+        &0,
+        &1_000,
+        &vec![&env, approver.clone()],
+        &false,
+    );
+    vault.approve_release(&approver, &escrow_id);
+
+    env.ledger().set_timestamp(1_000 + 7 * 86_400);
+    vault.refund_escrow(&depositor, &escrow_id);
+    assert!(vault.get_escrow(&escrow_id).unwrap().state == EscrowState::Refunded);
+    assert_eq!(vault.get_refundable_at(&99), 0);
+}
+
+// ============================================================
+// Emergency Stop
+// ============================================================
+
+#[test]
+fn test_pause_blocks_releases_but_not_refunds() {
+    let env = Env::default();
+    let (vault, admin, oracle, depositor, token) = setup_vault(&env);
+    let token_client = token::Client::new(&env, &token);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000, &0, &0, &1_000, &vec![&env, approver.clone()], &false);
+    assert!(vault.try_pause(&depositor).is_err());
+    vault.pause(&admin);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![&env, (vault.address.clone(), (symbol_short!("admin"), symbol_short!("paused")).into_val(&env), admin.into_val(&env))]
+    );
+    assert!(vault.is_paused());
+
+    assert_eq!(
+        vault.try_create_escrow(&depositor, &1, &beneficiary, &token, &5_000, &0, &0, &1_000, &vec![&env], &false),
+        Err(Ok(EscrowError::Paused))
+    );
+    assert_eq!(vault.try_approve_release(&approver, &escrow_id), Err(Ok(EscrowError::Paused)));
+    assert_eq!(vault.try_release_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::Paused)));
+    assert_eq!(vault.try_release_partial(&depositor, &escrow_id, &1_000), Err(Ok(EscrowError::Paused)));
+    assert_eq!(vault.try_claim_release(&beneficiary, &escrow_id), Err(Ok(EscrowError::Paused)));
+    assert_eq!(vault.try_update_performance(&oracle, &escrow_id, &90, &100, &1), Err(Ok(EscrowError::Paused)));
+
+    // Depositors can still exit once the escrow has expired
+    assert_eq!(vault.try_refund_escrow(&depositor, &escrow_id), Err(Ok(EscrowError::NotExpired)));
+    env.ledger().set_timestamp(1_000);
+    vault.refund_escrow(&depositor, &escrow_id);
+    assert!(vault.get_escrow(&escrow_id).unwrap().state == EscrowState::Refunded);
+    assert_eq!(token_client.balance(&vault.address), 0);
+
+    vault.unpause(&admin);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![&env, (vault.address.clone(), (symbol_short!("admin"), symbol_short!("unpaused")).into_val(&env), admin.into_val(&env))]
+    );
+    assert!(!vault.is_paused());
+    vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000, &0, &0, &1_000, &vec![&env], &false);
+}
+
+// ============================================================
+// Oracles
+// ============================================================
+
+#[test]
+fn test_multiple_oracles_and_removal() {
+    let env = Env::default();
+    let (vault, admin, oracle, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let backup = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000_000, &0, &50, &1_000, &vec![&env], &false);
+
+    vault.add_oracle(&admin, &backup);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("oracle"), symbol_short!("added")).into_val(&env),
+                backup.into_val(&env),
+            ),
+        ]
+    );
+    vault.update_performance(&oracle, &escrow_id, &40, &100, &1);
+    vault.update_performance(&backup, &escrow_id, &60, &200, &2);
+    assert_eq!(vault.get_performance(&escrow_id).unwrap().last_oracle, backup);
+
+    vault.remove_oracle(&admin, &backup);
+    assert!(!vault.is_oracle(&backup));
+    assert_eq!(
+        vault.try_update_performance(&backup, &escrow_id, &70, &300, &3),
+        Err(Ok(EscrowError::Unauthorized))
+    );
+    assert_eq!(vault.get_performance(&escrow_id).unwrap().current_performance, 60);
+}
+
+#[test]
+fn test_rotate_primary_oracle() {
+    let env = Env::default();
+    let (vault, admin, oracle, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let new_oracle = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000_000, &0, &50, &1_000, &vec![&env], &false);
+
+    vault.set_oracle(&admin, &new_oracle);
+    assert_eq!(
+        vault.try_update_performance(&oracle, &escrow_id, &70, &300, &3),
+        Err(Ok(EscrowError::Unauthorized))
+    );
+    vault.update_performance(&new_oracle, &escrow_id, &70, &300, &3);
+    assert_eq!(vault.get_performance(&escrow_id).unwrap().last_oracle, new_oracle);
+    assert_eq!(vault.try_set_oracle(&depositor, &oracle), Err(Ok(EscrowError::Unauthorized)));
+}
+
+// ============================================================
+// Arbiters
+// ============================================================
+
+#[test]
+fn test_arbiter_releases_but_cannot_manage_oracles() {
+    let env = Env::default();
+    let (vault, admin, oracle, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000_000, &0, &50, &1_000, &vec![&env], &false);
+
+    assert_eq!(vault.try_add_arbiter(&depositor, &arbiter), Err(Ok(EscrowError::Unauthorized)));
+    assert_eq!(vault.try_release_escrow(&arbiter, &escrow_id), Err(Ok(EscrowError::Unauthorized)));
+    assert_eq!(vault.try_approve_release(&arbiter, &escrow_id), Err(Ok(EscrowError::NotApprover)));
+
+    vault.add_arbiter(&admin, &arbiter);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("arbiter"), symbol_short!("added")).into_val(&env),
+                arbiter.into_val(&env),
+            ),
+        ]
+    );
+    assert!(vault.is_arbiter(&arbiter));
+    vault.add_arbiter(&admin, &arbiter);
+    assert_eq!(vault.get_arbiters(), vec![&env, arbiter.clone()]);
+
+    assert_eq!(vault.try_set_oracle(&arbiter, &arbiter), Err(Ok(EscrowError::Unauthorized)));
+    assert_eq!(vault.try_add_oracle(&arbiter, &arbiter), Err(Ok(EscrowError::Unauthorized)));
+
+    vault.approve_release(&arbiter, &escrow_id);
+    vault.update_performance(&oracle, &escrow_id, &60, &1_000, &10);
+    vault.release_escrow(&arbiter, &escrow_id);
+    assert_eq!(token::Client::new(&env, &token).balance(&beneficiary), 5_000_000);
+
+    vault.remove_arbiter(&admin, &arbiter);
+    assert!(!vault.is_arbiter(&arbiter));
+    assert_eq!(vault.get_arbiters().len(), 0);
+    assert_eq!(vault.try_remove_arbiter(&admin, &arbiter), Err(Ok(EscrowError::NotFound)));
+}
+
+#[test]
+fn test_arbiter_resolves_dispute() {
+    let env = Env::default();
+    let (vault, admin, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    vault.add_arbiter(&admin, &arbiter);
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000_000, &0, &0, &1_000, &vec![&env], &false);
+    vault.raise_dispute(&beneficiary, &escrow_id, &String::from_str(&env, "under-reported views"));
+    assert_eq!(
+        vault.try_resolve_dispute(&beneficiary, &escrow_id, &5_000_000, &0),
+        Err(Ok(EscrowError::Unauthorized))
+    );
+
+    vault.resolve_dispute(&arbiter, &escrow_id, &3_000_000, &2_000_000);
+    assert!(vault.get_escrow(&escrow_id).unwrap().state == EscrowState::Resolved);
+    assert_eq!(token::Client::new(&env, &token).balance(&beneficiary), 3_000_000);
+}
+
+// ============================================================
+// Performance History
+// ============================================================
+
+#[test]
+fn test_performance_history_ring_buffer() {
+    let env = Env::default();
+    let (vault, admin, oracle, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    vault.set_perf_history_size(&admin, &3);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000_000, &0, &50, &1_000, &vec![&env], &false);
+    assert_eq!(vault.get_performance_history(&escrow_id, &0, &10).len(), 0);
+
+    for performance in 1..=5u32 {
+        vault.update_performance(&oracle, &escrow_id, &(performance * 10), &0, &0);
+    }
+    assert_eq!(vault.get_performance(&escrow_id).unwrap().sequence, 5);
+
+    let history = vault.get_performance_history(&escrow_id, &0, &10);
+    assert_eq!(history.len(), 3);
+    for (i, metrics) in history.iter().enumerate() {
+        assert_eq!(metrics.sequence, 3 + i as u64);
+        assert_eq!(metrics.current_performance, 30 + 10 * i as u32);
+    }
+
+    let page = vault.get_performance_history(&escrow_id, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().sequence, 4);
+    assert_eq!(vault.get_performance_history(&escrow_id, &3, &10).len(), 0);
+
+    // Escrows that already have history keep their capacity
+    vault.set_perf_history_size(&admin, &10);
+    vault.update_performance(&oracle, &escrow_id, &90, &0, &0);
+    assert_eq!(vault.get_performance_history(&escrow_id, &0, &10).len(), 3);
+}
+
+// ============================================================
+// Overflow Safety
+// ============================================================
+
+#[test]
+fn test_near_overflow_durations() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    env.ledger().set_timestamp(1_000);
+
+    let result = vault.try_create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &u64::MAX, &0, &1_000, &vec![&env], &false);
+    assert_eq!(result, Err(Ok(EscrowError::Overflow)));
+    let result = vault.try_create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &0, &0, &(u64::MAX - 999), &vec![&env], &false);
+    assert_eq!(result, Err(Ok(EscrowError::Overflow)));
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &(u64::MAX - 1_000), &0, &(u64::MAX - 1_000), &vec![&env], &false);
+    let escrow = vault.get_escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.time_lock_until, u64::MAX);
+    assert_eq!(escrow.expires_at, u64::MAX);
+    // The grace window saturates instead of wrapping past the far-future expiry
+    assert_eq!(vault.get_refundable_at(&escrow_id), u64::MAX);
+}
+
+#[test]
+fn test_near_overflow_amounts() {
+    let env = Env::default();
+    let (vault, _, oracle, depositor, token) = setup_vault(&env);
+    let huge = i128::MAX / 2;
+    token::StellarAssetClient::new(&env, &token).mint(&depositor, &huge);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+
+    // Milestone shares on a huge deposit still add up to exactly the amount
+    let escrow_id = vault.create_milestone_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token,
+        &(huge - 100_000_000),
+        &0,
+        &1_000,
+        &vec![&env, approver.clone()],
+        &milestones(&env, &[(10, 3_333), (20, 3_333), (30, 3_334)]),
+    );
+    vault.approve_release(&approver, &escrow_id);
+    vault.update_performance(&oracle, &escrow_id, &100, &0, &0);
+    for idx in 0..3u32 {
+        vault.release_milestone(&beneficiary, &escrow_id, &idx);
+    }
+    let escrow = vault.get_escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.released_amount, huge - 100_000_000);
+    assert_eq!(escrow.locked_amount, 0);
+    assert!(escrow.state == EscrowState::Released);
+
+    let result = vault.try_create_milestone_escrow(
+        &depositor,
+        &1,
+        &beneficiary,
+        &token,
+        &1_000,
+        &0,
+        &1_000,
+        &vec![&env],
+        &milestones(&env, &[(10, u32::MAX), (20, 10_001)]),
+    );
+    assert_eq!(result, Err(Ok(EscrowError::InvalidMilestones)));
+}
+
+#[test]
+fn test_partial_releases_keep_accounting_balanced() {
+    let env = Env::default();
+    let (vault, admin, _, depositor, token) = setup_vault(&env);
+    let huge = i128::MAX / 2;
+    token::StellarAssetClient::new(&env, &token).mint(&depositor, &huge);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &huge, &0, &0, &1_000, &vec![&env, approver.clone()], &false);
+    vault.approve_release(&approver, &escrow_id);
+    assert_eq!(vault.try_release_partial(&admin, &escrow_id, &i128::MAX), Err(Ok(EscrowError::InvalidAmount)));
+
+    vault.release_partial(&admin, &escrow_id, &(huge - 1));
+    let escrow = vault.get_escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.released_amount + escrow.refunded_amount + escrow.locked_amount, escrow.amount);
+    assert_eq!(escrow.locked_amount, 1);
+
+    env.ledger().set_timestamp(1_000 + 7 * 86_400);
+    vault.refund_escrow(&depositor, &escrow_id);
+    let escrow = vault.get_escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.released_amount, huge - 1);
+    assert_eq!(escrow.refunded_amount, 1);
+    assert_eq!(escrow.released_amount + escrow.refunded_amount + escrow.locked_amount, escrow.amount);
+}
+
+// ============================================================
+// Approver Views
+// ============================================================
+
+#[test]
+fn test_required_and_pending_approvers() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    env.ledger().set_timestamp(500);
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &0, &0, &1_000, &vec![&env, first.clone(), second.clone()], &false);
+    assert_eq!(vault.get_required_approvers(&escrow_id), vec![&env, first.clone(), second.clone()]);
+    assert_eq!(vault.get_pending_approvers(&escrow_id), vec![&env, first.clone(), second.clone()]);
+    assert!(vault.get_approval(&escrow_id, &first).is_none());
+
+    vault.approve_release(&first, &escrow_id);
+    let approval = vault.get_approval(&escrow_id, &first).unwrap();
+    assert!(approval.approved);
+    assert_eq!(approval.timestamp, 500);
+    assert_eq!(vault.get_pending_approvers(&escrow_id), vec![&env, second.clone()]);
+
+    vault.approve_release(&second, &escrow_id);
+    assert_eq!(vault.get_pending_approvers(&escrow_id).len(), 0);
+    assert_eq!(vault.get_required_approvers(&99).len(), 0);
+}
+
+#[test]
+fn test_duplicate_approval_rejected() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &0, &0, &1_000, &vec![&env, first.clone(), second.clone()], &false);
+    vault.approve_release(&first, &escrow_id);
+    assert_eq!(vault.try_approve_release(&first, &escrow_id), Err(Ok(EscrowError::AlreadyApproved)));
+    assert_eq!(vault.get_approval_count(&escrow_id), 1);
+    assert_eq!(vault.get_pending_approvers(&escrow_id), vec![&env, second.clone()]);
+}
+
+// ============================================================
+// Multi-token Escrows
+// ============================================================
+
+#[test]
+fn test_escrow_in_supported_second_token() {
+    let env = Env::default();
+    let (vault, admin, oracle, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    token::StellarAssetClient::new(&env, &usdc).mint(&depositor, &10_000);
+    let usdc_client = token::Client::new(&env, &usdc);
+
+    assert!(vault.is_token_supported(&token));
+    assert!(!vault.is_token_supported(&usdc));
+    let result = vault.try_create_escrow(&depositor, &1, &beneficiary, &usdc, &1_000, &0, &0, &1_000, &vec![&env], &false);
+    assert_eq!(result, Err(Ok(EscrowError::TokenNotSupported)));
+
+    vault.add_supported_token(&admin, &usdc);
+    assert!(vault.is_token_supported(&usdc));
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &usdc, &1_000, &0, &0, &1_000, &vec![&env, approver.clone()], &false);
+    assert_eq!(vault.get_escrow_token(&escrow_id), Some(usdc.clone()));
+    assert_eq!(usdc_client.balance(&vault.address), 1_000);
+
+    // Delisting only stops new escrows
+    vault.remove_supported_token(&admin, &usdc);
+    let result = vault.try_create_escrow(&depositor, &1, &beneficiary, &usdc, &1_000, &0, &0, &1_000, &vec![&env], &false);
+    assert_eq!(result, Err(Ok(EscrowError::TokenNotSupported)));
+
+    vault.approve_release(&approver, &escrow_id);
+    vault.update_performance(&oracle, &escrow_id, &100, &0, &0);
+    vault.release_partial(&admin, &escrow_id, &400);
+    vault.release_escrow(&depositor, &escrow_id);
+    assert_eq!(usdc_client.balance(&beneficiary), 1_000);
+    assert_eq!(token::Client::new(&env, &token).balance(&beneficiary), 0);
+}
+
+#[test]
+fn test_escrow_without_stored_token_uses_default() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let token_client = token::Client::new(&env, &token);
+    let beneficiary = Address::generate(&env);
+
+    // Simulate an escrow created before tokens were stored per escrow
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &0, &0, &10, &vec![&env], &false);
+    env.as_contract(&vault.address, || env.storage().persistent().remove(&DataKey::EscrowToken(escrow_id)));
+    assert_eq!(vault.get_escrow_token(&escrow_id), Some(token.clone()));
+    assert_eq!(vault.get_escrow_token(&99), None);
+
+    env.ledger().set_timestamp(10);
+    let before = token_client.balance(&depositor);
+    vault.refund_escrow(&depositor, &escrow_id);
+    assert_eq!(token_client.balance(&depositor), before + 1_000);
+}
+
+// ============================================================
+// Events
+// ============================================================
+
+#[test]
+fn test_approval_and_performance_events() {
+    let env = Env::default();
+    let (vault, _, oracle, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &0, &0, &1_000, &vec![&env, first.clone(), second.clone()], &false);
+
+    vault.approve_release(&first, &escrow_id);
+    vault.approve_release(&second, &escrow_id);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("escrow"), symbol_short!("approved")).into_val(&env),
+                (escrow_id, second.clone(), 2u32).into_val(&env),
+            ),
+        ]
+    );
+
+    vault.update_performance(&oracle, &escrow_id, &75, &5_000, &120);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("escrow"), symbol_short!("perf")).into_val(&env),
+                (escrow_id, 75u32, 5_000u64, 120u64).into_val(&env),
+            ),
+        ]
+    );
+}
+
+// ============================================================
+// Batch Creation
+// ============================================================
+
+fn batch_entries(env: &Env, count: u32, amount: i128) -> Vec<EscrowEntry> {
+    let mut entries = Vec::new(env);
+    for _ in 0..count {
+        entries.push_back(EscrowEntry {
+            beneficiary: Address::generate(env),
+            amount,
+            time_lock_duration: 0,
+            performance_threshold: 50,
+            expires_in: 1_000,
+        });
+    }
+    entries
+}
+
+#[test]
+fn test_create_escrows_batch() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let token_client = token::Client::new(&env, &token);
+    let approver = Address::generate(&env);
+    let entries = batch_entries(&env, 3, 1_000);
+
+    let ids = vault.create_escrows_batch(&depositor, &7, &token, &vec![&env, approver.clone()], &entries);
+    assert_eq!(ids, vec![&env, 1u64, 2, 3]);
+    assert_eq!(token_client.balance(&vault.address), 3_000);
+    assert_eq!(vault.list_escrows_by_campaign(&7, &0, &10), ids);
+    for (i, id) in ids.iter().enumerate() {
+        let escrow = vault.get_escrow(&id).unwrap();
+        assert_eq!(escrow.beneficiary, entries.get(i as u32).unwrap().beneficiary);
+        assert_eq!(escrow.amount, 1_000);
+        assert_eq!(escrow.performance_threshold, 50);
+        assert_eq!(vault.get_required_approvers(&id), vec![&env, approver.clone()]);
+    }
+}
+
+#[test]
+fn test_create_escrows_batch_is_all_or_nothing() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+    let token_client = token::Client::new(&env, &token);
+
+    let mut entries = batch_entries(&env, 3, 1_000);
+    let mut bad = entries.get(1).unwrap();
+    bad.performance_threshold = 101;
+    entries.set(1, bad);
+    let result = vault.try_create_escrows_batch(&depositor, &7, &token, &vec![&env], &entries);
+    assert_eq!(result, Err(Ok(EscrowError::InvalidPerformance)));
+
+    let mut entries = batch_entries(&env, 2, 1_000);
+    let mut bad = entries.get(1).unwrap();
+    bad.expires_in = u64::MAX;
+    entries.set(1, bad);
+    env.ledger().set_timestamp(10);
+    let result = vault.try_create_escrows_batch(&depositor, &7, &token, &vec![&env], &entries);
+    assert_eq!(result, Err(Ok(EscrowError::Overflow)));
+
+    assert_eq!(token_client.balance(&vault.address), 0);
+    assert!(vault.get_escrow(&1).is_none());
+    assert_eq!(vault.list_escrows_by_campaign(&7, &0, &10).len(), 0);
+}
+
+#[test]
+fn test_create_escrows_batch_size_cap() {
+    let env = Env::default();
+    let (vault, _, _, depositor, token) = setup_vault(&env);
+
+    let result = vault.try_create_escrows_batch(&depositor, &7, &token, &vec![&env], &batch_entries(&env, 21, 1_000));
+    assert_eq!(result, Err(Ok(EscrowError::InvalidBatch)));
+    let result = vault.try_create_escrows_batch(&depositor, &7, &token, &vec![&env], &Vec::new(&env));
+    assert_eq!(result, Err(Ok(EscrowError::InvalidBatch)));
+
+    let ids = vault.create_escrows_batch(&depositor, &7, &token, &vec![&env], &batch_entries(&env, 20, 1_000));
+    assert_eq!(ids.len(), 20);
+}
+
+// ============================================================
+// Auto-release
+// ============================================================
+
+#[test]
+fn test_auto_release_on_performance_update() {
+    let env = Env::default();
+    let (vault, _, oracle, depositor, token) = setup_vault(&env);
+    let token_client = token::Client::new(&env, &token);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000, &100, &80, &1_000, &vec![&env, approver.clone()], &true);
+    let manual_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &5_000, &0, &80, &1_000, &vec![&env, approver.clone()], &false);
+    assert!(vault.is_auto_release(&escrow_id));
+    assert!(!vault.is_auto_release(&manual_id));
+
+    // Unmet conditions leave the performance update in place
+    vault.update_performance(&oracle, &escrow_id, &90, &1_000, &10);
+    assert_eq!(vault.get_performance(&escrow_id).unwrap().current_performance, 90);
+    assert_eq!(vault.get_escrow(&escrow_id).unwrap().locked_amount, 5_000);
+
+    vault.approve_release(&approver, &escrow_id);
+    vault.update_performance(&oracle, &escrow_id, &95, &1_000, &10);
+    assert_eq!(vault.get_escrow(&escrow_id).unwrap().locked_amount, 5_000);
+
+    env.ledger().set_timestamp(100);
+    vault.update_performance(&oracle, &escrow_id, &70, &1_500, &12);
+    assert_eq!(vault.get_escrow(&escrow_id).unwrap().locked_amount, 5_000);
+
+    vault.update_performance(&oracle, &escrow_id, &85, &2_000, &20);
+    let events = env.events().all();
+    assert!(vault.get_escrow(&escrow_id).unwrap().state == EscrowState::Released);
+    assert_eq!(token_client.balance(&beneficiary), 5_000);
+    assert_eq!(
+        events.slice(events.len() - 2..),
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("escrow"), symbol_short!("release")).into_val(&env),
+                (escrow_id, 5_000i128).into_val(&env),
+            ),
+            (
+                vault.address.clone(),
+                (symbol_short!("escrow"), symbol_short!("auto")).into_val(&env),
+                (escrow_id, 5_000i128).into_val(&env),
+            ),
+        ]
+    );
+
+    // Later updates on a released escrow are plain updates
+    vault.update_performance(&oracle, &escrow_id, &99, &2_500, &25);
+    assert_eq!(token_client.balance(&beneficiary), 5_000);
+
+    vault.approve_release(&approver, &manual_id);
+    vault.update_performance(&oracle, &manual_id, &90, &1_000, &10);
+    assert_eq!(vault.get_escrow(&manual_id).unwrap().locked_amount, 5_000);
+}
+
+// ============================================================
+// Upgrade and Admin Transfer
+// ============================================================
+
+/// Smallest module the host accepts as contract code: just the wasm header
+/// and a contractenvmetav0 section declaring interface protocol 22.
+const MINIMAL_CONTRACT_WASM: [u8; 40] = [
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x00, 0x1e, 0x11, b'c', b'o', b'n', b't', b'r', b'a', b'c', b't',
+    b'e', b'n', b'v', b'm', b'e', b't', b'a', b'v', b'0', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x16, 0x00, 0x00,
+    0x00, 0x00,
+];
+
+#[test]
+fn test_upgrade_swaps_code() {
+    let env = Env::default();
+    let (vault, admin, _, _, _) = setup_vault(&env);
+
+    let wasm_hash = env.deployer().upload_contract_wasm(Bytes::from_slice(&env, &MINIMAL_CONTRACT_WASM));
+    vault.upgrade(&admin, &wasm_hash);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("admin"), symbol_short!("upgraded")).into_val(&env),
+                wasm_hash.into_val(&env),
+            ),
+        ]
+    );
+
+    // The replacement module exports nothing, so the old entry points are gone
+    assert!(vault.try_get_admin().is_err());
+}
+
+#[test]
+fn test_upgrade_requires_admin() {
+    let env = Env::default();
+    let (vault, admin, _, depositor, token) = setup_vault(&env);
+    let beneficiary = Address::generate(&env);
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &0, &0, &1_000, &vec![&env], &false);
+
+    let wasm_hash = BytesN::from_array(&env, &[7; 32]);
+    assert_eq!(vault.try_upgrade(&depositor, &wasm_hash), Err(Ok(EscrowError::Unauthorized)));
+    assert_eq!(vault.try_upgrade(&beneficiary, &wasm_hash), Err(Ok(EscrowError::Unauthorized)));
+    vault.pause(&admin);
+    assert_eq!(vault.try_upgrade(&depositor, &wasm_hash), Err(Ok(EscrowError::Unauthorized)));
+    assert_eq!(vault.get_escrow(&escrow_id).unwrap().amount, 1_000);
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    let (vault, admin, _, _, _) = setup_vault(&env);
+    let new_admin = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    assert_eq!(vault.try_accept_admin(&new_admin), Err(Ok(EscrowError::NoPendingAdmin)));
+    assert_eq!(vault.try_propose_admin(&stranger, &new_admin), Err(Ok(EscrowError::Unauthorized)));
+
+    vault.propose_admin(&admin, &new_admin);
+    assert_eq!(vault.get_pending_admin(), Some(new_admin.clone()));
+    // Proposing alone changes nothing
+    assert_eq!(vault.get_admin(), admin);
+    vault.set_refund_grace(&admin, &60);
+
+    assert_eq!(vault.try_accept_admin(&stranger), Err(Ok(EscrowError::Unauthorized)));
+    assert_eq!(vault.get_admin(), admin);
+
+    vault.accept_admin(&new_admin);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                vault.address.clone(),
+                (symbol_short!("admin"), symbol_short!("accepted")).into_val(&env),
+                new_admin.into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(vault.get_admin(), new_admin);
+    assert_eq!(vault.get_pending_admin(), None);
+    assert_eq!(vault.try_set_refund_grace(&admin, &60), Err(Ok(EscrowError::Unauthorized)));
+    vault.set_refund_grace(&new_admin, &120);
+}
+
+// ============================================================
+// Storage TTL
+// ============================================================
+
+fn escrow_ttl(env: &Env, vault: &EscrowVaultContractClient, escrow_id: u64) -> u32 {
+    env.as_contract(&vault.address, || env.storage().persistent().get_ttl(&DataKey::Escrow(escrow_id)))
+}
+
+/// Move the ledger forward in steps, touching the contracts along the way the
+/// way regular traffic would keep their instances alive
+fn advance_to(env: &Env, vault: &EscrowVaultContractClient, token: &token::Client, sequence: u32) {
+    let mut current = env.ledger().sequence();
+    while current < sequence {
+        current = sequence.min(current + 15_000);
+        env.ledger().with_mut(|li| {
+            li.sequence_number = current;
+            li.timestamp = current as u64 * 5;
+        });
+        vault.get_refund_grace();
+        token.balance(&vault.address);
+    }
+}
+
+#[test]
+fn test_escrow_ttl_tracks_expiry_and_bump() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    token::StellarAssetClient::new(&env, &token).mint(&depositor, &10_000);
+    let token_client = token::Client::new(&env, &token);
+    let vault = EscrowVaultContractClient::new(&env, &env.register(EscrowVaultContract, ()));
+    assert_eq!(
+        vault.try_initialize(&admin, &token, &oracle, &Some(2_000), &Some(1_000), &None),
+        Err(Ok(EscrowError::InvalidAmount))
+    );
+    vault.initialize(&admin, &token, &oracle, &Some(100), &Some(1_000), &None);
+
+    // 90 days at 5 seconds per ledger
+    let expires_in = 90 * 86_400;
+    let expiry_ledgers = expires_in as u32 / 5;
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token, &1_000, &0, &0, &expires_in, &vec![&env], &false);
+    assert_eq!(escrow_ttl(&env, &vault, escrow_id), expiry_ledgers + 1_000);
+    let approvals_ttl = env.as_contract(&vault.address, || env.storage().persistent().get_ttl(&DataKey::ApprovalCount(escrow_id)));
+    assert_eq!(approvals_ttl, expiry_ledgers + 1_000);
+
+    // Past expiry, still inside the bump window
+    advance_to(&env, &vault, &token_client, expiry_ledgers + 800);
+    assert_eq!(vault.get_escrow(&escrow_id).unwrap().amount, 1_000);
+    vault.bump_escrow(&escrow_id);
+    assert_eq!(escrow_ttl(&env, &vault, escrow_id), 200);
+
+    advance_to(&env, &vault, &token_client, expiry_ledgers + 950);
+    vault.bump_escrow(&escrow_id);
+    assert_eq!(escrow_ttl(&env, &vault, escrow_id), 1_000);
+
+    // Beyond the original lifetime the escrow is still readable
+    advance_to(&env, &vault, &token_client, expiry_ledgers + 1_500);
+    assert_eq!(vault.get_escrow(&escrow_id).unwrap().locked_amount, 1_000);
+    assert_eq!(vault.get_approval_count(&escrow_id), 0);
+    assert_eq!(vault.try_bump_escrow(&99), Err(Ok(EscrowError::NotFound)));
+}
+
+// ============================================================
+// Re-entrancy
+// ============================================================
+
+mod reentrant_token {
+    use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+    use crate::EscrowVaultContractClient;
+
+    #[contracttype]
+    pub enum MockKey {
+        Target,
+        EscrowId,
+        Caller,
+        Transfers,
+        NestedSucceeded,
+    }
+
+    /// Token whose transfer tries to release the same escrow again once armed
+    #[contract]
+    pub struct ReentrantToken;
+
+    #[contractimpl]
+    impl ReentrantToken {
+        pub fn arm(env: Env, target: Address, escrow_id: u64, caller: Address) {
+            env.storage().instance().set(&MockKey::Target, &target);
+            env.storage().instance().set(&MockKey::EscrowId, &escrow_id);
+            env.storage().instance().set(&MockKey::Caller, &caller);
+        }
+
+        pub fn transfer(env: Env, _from: Address, _to: Address, _amount: i128) {
+            let Some(target) = env.storage().instance().get::<MockKey, Address>(&MockKey::Target) else {
+                return;
+            };
+            let transfers: u32 = env.storage().instance().get(&MockKey::Transfers).unwrap_or(0);
+            env.storage().instance().set(&MockKey::Transfers, &(transfers + 1));
+
+            let escrow_id: u64 = env.storage().instance().get(&MockKey::EscrowId).unwrap();
+            let caller: Address = env.storage().instance().get(&MockKey::Caller).unwrap();
+            let nested = EscrowVaultContractClient::new(&env, &target).try_release_escrow(&caller, &escrow_id);
+            if matches!(nested, Ok(Ok(()))) {
+                env.storage().instance().set(&MockKey::NestedSucceeded, &true);
+            }
+        }
+
+        pub fn transfers(env: Env) -> u32 {
+            env.storage().instance().get(&MockKey::Transfers).unwrap_or(0)
+        }
+
+        pub fn nested_succeeded(env: Env) -> bool {
+            env.storage().instance().get(&MockKey::NestedSucceeded).unwrap_or(false)
+        }
+    }
+}
+
+#[test]
+fn test_reentrant_token_cannot_double_release() {
+    use reentrant_token::{ReentrantToken, ReentrantTokenClient};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let token_id = env.register(ReentrantToken, ());
+    let token = ReentrantTokenClient::new(&env, &token_id);
+
+    let vault_id = env.register(EscrowVaultContract, ());
+    let vault = EscrowVaultContractClient::new(&env, &vault_id);
+    vault.initialize(&admin, &token_id, &Address::generate(&env), &None, &None, &None);
+
+    let escrow_id = vault.create_escrow(&depositor, &1, &beneficiary, &token_id, &5_000, &0, &0, &1_000, &vec![&env, approver.clone()], &false);
+    vault.approve_release(&approver, &escrow_id);
+    token.arm(&vault_id, &escrow_id, &depositor);
+    vault.release_escrow(&depositor, &escrow_id);
+
+    assert_eq!(token.transfers(), 1);
+    assert!(!token.nested_succeeded());
+    let escrow = vault.get_escrow(&escrow_id).unwrap();
+    assert!(escrow.state == EscrowState::Released);
+    assert_eq!(escrow.locked_amount, 0);
+    assert_eq!(escrow.released_amount, 5_000);
 }
@@ -4,13 +4,42 @@
 //! Events:
 //! - ("proposal", "created"): [proposal_id: u64, proposer: Address]
 //! - ("gov", "voted"): [proposal_id: u64, voter: Address, power: i128]
-//! - ("proposal", "finalized"): [proposal_id: u64, status: ProposalStatus]
+//! - ("proposal", "finalized"): [proposal_id: u64, status: ProposalStatus, votes_for: i128,
+//!   votes_against: i128, votes_abstain: i128, quorum_met: bool]
+//! - ("proposal", "executed"): [proposal_id: u64, result: Val]
+//! - ("proposal", "cancel"): [proposal_id: u64, cancelled_by: Address]
+//! - ("gov", "revote"): [proposal_id: u64, voter: Address, choice: VoteChoice]
+//! - ("gov", "locked"): [voter: Address, amount: i128, locked_total: i128]
+//! - ("gov", "unlocked"): [voter: Address, amount: i128, locked_total: i128]
+//! - ("proposal", "vetoed"): [proposal_id: u64, veto_count: u32]
+//!
+//! Tally rule: quorum is met when For + Against + Abstain reaches the
+//! proposal's quorum_votes. The pass threshold is the share of For among
+//! For + Against; abstentions only count towards quorum. Finalizing without
+//! quorum sets Expired; with quorum but below the threshold, Rejected.
+//! quorum_votes is the absolute QuorumAmount when set, otherwise QuorumBps of
+//! the governance tokens locked in the DAO when the proposal is created.
+//!
+//! Voting power is the amount of governance tokens a voter has locked in the
+//! DAO. Voting on a proposal keeps those tokens locked until the proposal's
+//! end ledger, so counted power stays backed for the whole voting period.
+//!
+//! Once the DAO holds the admin role on an operational contract (ad registry,
+//! escrow vault, auction engine, payout automation), propose_action proposals
+//! call that contract's admin functions with the DAO's own address when
+//! executed.
+//!
+//! Guardians set at initialize act as an emergency brake: once
+//! veto_threshold distinct guardians veto an Active or Passed proposal it
+//! becomes Vetoed and can no longer be executed. An empty guardian set
+//! disables vetoes.
 
 
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    Address, Env, String,
+    token, vec, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Val, Vec,
 };
 
 // ============================================================
@@ -26,6 +55,7 @@ pub enum ProposalStatus {
     Executed,
     Cancelled,
     Expired,
+    Vetoed,
 }
 
 #[contracttype]
@@ -36,6 +66,46 @@ pub enum VoteChoice {
     Abstain,
 }
 
+/// Contract call made on target_contract when a passed proposal is executed
+#[contracttype]
+#[derive(Clone)]
+pub struct ProposalCall {
+    pub function: Symbol,
+    pub args: Vec<Val>,
+}
+
+/// DAO setting changed by executing a passed parameter proposal
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub enum ParameterChange {
+    VotingPeriod(u32),
+    QuorumBps(u32),
+    QuorumAmount(i128),
+    PassThreshold(u32),
+    ProposerMinTokens(i128),
+}
+
+/// Admin call on an operational contract, made with the DAO's own address as
+/// admin when a passed action proposal is executed. The DAO must already hold
+/// the admin role on the target contract.
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub enum ProposalAction {
+    /// Ad registry: flags needed to auto-suspend content
+    SetFlagThreshold(Address, u32),
+    /// Escrow vault: replace the primary oracle
+    SetOracle(Address, Address),
+    /// Auction engine: protocol fee in basis points
+    SetFeeBps(Address, u32),
+    /// Payout automation: minimum payout amount
+    SetMinPayout(Address, i128),
+    /// Ad registry, auction engine or payout automation: hand the admin role on
+    /// to another address
+    SetAdmin(Address, Address),
+    /// Escrow vault: accept an admin handover proposed to the DAO
+    AcceptAdmin(Address),
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct Proposal {
@@ -48,11 +118,20 @@ pub struct Proposal {
     pub votes_against: i128,
     pub votes_abstain: i128,
     pub quorum_bps: u32,
+    /// Votes needed for quorum, resolved when the proposal is created
+    pub quorum_votes: i128,
     pub threshold_pct: u32, // percentage to pass
     pub start_ledger: u32,
     pub end_ledger: u32,
     pub created_at: u64,
     pub executed_at: Option<u64>,
+    /// Set when the proposal passes: earliest execution time, and the time
+    /// after which it can no longer be executed
+    pub executable_after: u64,
+    pub execution_deadline: u64,
+    /// SHA-256 of the full proposal document hosted off-chain; `None` for
+    /// proposals created with a title and description
+    pub content_hash: Option<BytesN<32>>,
 }
 
 #[contracttype]
@@ -79,6 +158,25 @@ pub enum DataKey {
     Proposal(u64),
     Vote(u64, Address),
     HasVoted(u64, Address),
+    PowerSnapshot(u64, Address),
+    LockedBalance(Address),
+    LockedUntil(Address),
+    ProposalCall(u64),
+    ExecutionDelay,
+    ActiveProposals,
+    ProposerProposals(Address),
+    QuorumAmount,
+    MinVotingPeriod,
+    MaxVotingPeriod,
+    ParameterChange(u64),
+    VoterAt(u64, u32),
+    VoterCount(u64),
+    ProposalAction(u64),
+    Guardian(Address),
+    GuardianThreshold,
+    Veto(u64, Address),
+    VetoCount(u64),
+    TotalLocked,
 }
 
 // ============================================================
@@ -89,6 +187,10 @@ const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 34_560;
 const PERSISTENT_BUMP_AMOUNT: u32 = 259_200;
+// How long a passed proposal stays executable once its delay has elapsed
+const EXECUTION_GRACE_PERIOD: u64 = 1_209_600; // 14 days
+const MAX_ACTIVE_PROPOSALS: u32 = 50;
+const MAX_LIST_LIMIT: u32 = 100;
 
 #[contract]
 pub struct GovernanceDaoContract;
@@ -104,12 +206,35 @@ impl GovernanceDaoContract {
         quorum_bps: u32,        // basis points (e.g., 1000 = 10%)
         pass_threshold: u32,    // percentage (e.g., 51)
         proposer_min: i128,     // min tokens to create proposal
+        execution_delay: u64,   // seconds between passing and execution
+        guardians: Vec<Address>,
+        veto_threshold: u32,    // distinct guardian vetoes needed
     ) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         if env.storage().instance().has(&DataKey::Admin) {
             panic!("already initialized");
         }
         admin.require_auth();
+        if (guardians.is_empty() && veto_threshold != 0)
+            || (!guardians.is_empty() && (veto_threshold == 0 || veto_threshold > guardians.len()))
+        {
+            panic!("invalid veto threshold");
+        }
+        for guardian in guardians.iter() {
+            let _ttl_key = DataKey::Guardian(guardian);
+            if env.storage().persistent().has(&_ttl_key) {
+                panic!("duplicate guardian");
+            }
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &true);
+            env.storage()
+                .persistent()
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::GuardianThreshold, &veto_threshold);
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage()
             .instance()
@@ -129,81 +254,100 @@ impl GovernanceDaoContract {
         env.storage()
             .instance()
             .set(&DataKey::ProposerMinTokens, &proposer_min);
+        env.storage()
+            .instance()
+            .set(&DataKey::ExecutionDelay, &execution_delay);
     }
 
-    /// Create a new governance proposal
+    /// Create a new governance proposal. A `call` is invoked on
+    /// `target_contract` when the proposal is executed; the governance token
+    /// and the DAO itself cannot be call targets, since the DAO holds every
+    /// voter's locked tokens. `voting_period` overrides the default within
+    /// the admin-set bounds.
     pub fn create_proposal(
         env: Env,
         proposer: Address,
         title: String,
         description: String,
         target_contract: Option<Address>,
+        call: Option<ProposalCall>,
+        voting_period: Option<u32>,
     ) -> u64 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         proposer.require_auth();
+        Self::_create_call_proposal(&env, proposer, title, description, None, target_contract, call, voting_period)
+    }
 
-        let counter: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::ProposalCounter)
-            .unwrap_or(0);
-        let proposal_id = counter + 1;
-
-        let voting_period: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::VotingPeriod)
-            .unwrap_or(1_000);
-        let quorum_bps: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::QuorumBps)
-            .unwrap_or(0);
-        let threshold: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::PassThreshold)
-            .unwrap_or(51);
+    /// Create a proposal that stores only the SHA-256 `content_hash` of its
+    /// full document, hosted off-chain (e.g. on IPFS). Title and description
+    /// are left empty; use verify_content to check a copy of the document.
+    pub fn create_proposal_with_hash(
+        env: Env,
+        proposer: Address,
+        content_hash: BytesN<32>,
+        target_contract: Option<Address>,
+        call: Option<ProposalCall>,
+        voting_period: Option<u32>,
+    ) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        proposer.require_auth();
+        let empty = String::from_str(&env, "");
+        Self::_create_call_proposal(&env, proposer, empty.clone(), empty, Some(content_hash), target_contract, call, voting_period)
+    }
 
-        let start = env.ledger().sequence();
-        let proposal = Proposal {
-            proposer: proposer.clone(),
-            title,
-            description,
-            target_contract,
-            status: ProposalStatus::Active,
-            votes_for: 0,
-            votes_against: 0,
-            votes_abstain: 0,
-            quorum_bps,
-            threshold_pct: threshold,
-            start_ledger: start,
-            end_ledger: start + voting_period,
-            created_at: env.ledger().timestamp(),
-            executed_at: None,
-        };
+    /// Propose changing one of the DAO's own settings; executing the passed
+    /// proposal applies it to proposals created afterwards.
+    pub fn propose_parameter_change(
+        env: Env,
+        proposer: Address,
+        title: String,
+        description: String,
+        change: ParameterChange,
+        voting_period: Option<u32>,
+    ) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        proposer.require_auth();
+        Self::_validate_parameter(&change);
 
-        let _ttl_key = DataKey::Proposal(proposal_id);
+        let proposal_id = Self::_create_proposal(&env, proposer, title, description, None, None, voting_period);
+        let _ttl_key = DataKey::ParameterChange(proposal_id);
         env.storage()
             .persistent()
-            .set(&_ttl_key, &proposal);
+            .set(&_ttl_key, &change);
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        env.storage()
-            .instance()
-            .set(&DataKey::ProposalCounter, &proposal_id);
+        proposal_id
+    }
 
-        env.events().publish(
-            (symbol_short!("proposal"), symbol_short!("created")),
-            (proposal_id, proposer),
-        );
+    /// Propose an admin action on one of the operational contracts; executing
+    /// the passed proposal makes the call as the DAO.
+    pub fn propose_action(
+        env: Env,
+        proposer: Address,
+        title: String,
+        description: String,
+        action: ProposalAction,
+        voting_period: Option<u32>,
+    ) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        proposer.require_auth();
 
+        let target = Self::_action_call(&env, &action).0;
+        let proposal_id = Self::_create_proposal(&env, proposer, title, description, None, Some(target), voting_period);
+        let _ttl_key = DataKey::ProposalAction(proposal_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &action);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
         proposal_id
     }
 
-    /// Cast a vote on a proposal
-    pub fn cast_vote(env: Env, voter: Address, proposal_id: u64, choice: VoteChoice, power: i128) {
+    /// Cast a vote on a proposal, weighted by the voter's locked tokens. The
+    /// lock is extended to the proposal's end ledger.
+    pub fn cast_vote(env: Env, voter: Address, proposal_id: u64, choice: VoteChoice) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         voter.require_auth();
 
@@ -230,17 +374,32 @@ impl GovernanceDaoContract {
             panic!("voting period ended");
         }
 
+        let power = Self::_locked_balance(&env, &voter);
         if power <= 0 {
-            panic!("invalid voting power");
+            panic!("no voting power");
         }
 
-        // Record vote
-        match choice {
-            VoteChoice::For => proposal.votes_for += power,
-            VoteChoice::Against => proposal.votes_against += power,
-            VoteChoice::Abstain => proposal.votes_abstain += power,
+        let _ttl_key = DataKey::PowerSnapshot(proposal_id, voter.clone());
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &power);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::LockedUntil(voter.clone());
+        let locked_until: u32 = env.storage().persistent().get(&_ttl_key).unwrap_or(0);
+        if proposal.end_ledger > locked_until {
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &proposal.end_ledger);
+            env.storage()
+                .persistent()
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
         }
 
+        // Record vote
+        Self::_tally(&mut proposal, &choice, power);
+
         let vote = Vote {
             choice,
             power,
@@ -261,6 +420,21 @@ impl GovernanceDaoContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let voter_count = Self::get_voter_count(env.clone(), proposal_id);
+        let _ttl_key = DataKey::VoterAt(proposal_id, voter_count);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &voter);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::VoterCount(proposal_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &(voter_count + 1));
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
         let _ttl_key = DataKey::Proposal(proposal_id);
         env.storage()
             .persistent()
@@ -275,6 +449,117 @@ impl GovernanceDaoContract {
         );
     }
 
+    /// Move an existing vote to another choice while voting is open. The
+    /// power recorded with the original vote is reused.
+    pub fn change_vote(env: Env, voter: Address, proposal_id: u64, new_choice: VoteChoice) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        voter.require_auth();
+
+        let vote_key = DataKey::Vote(proposal_id, voter.clone());
+        let mut vote: Vote = env
+            .storage()
+            .persistent()
+            .get(&vote_key)
+            .expect("vote not found");
+        let mut proposal: Proposal = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .expect("proposal not found");
+
+        if proposal.status != ProposalStatus::Active {
+            panic!("proposal not active");
+        }
+        if env.ledger().sequence() > proposal.end_ledger {
+            panic!("voting period ended");
+        }
+
+        Self::_tally(&mut proposal, &vote.choice, -vote.power);
+        Self::_tally(&mut proposal, &new_choice, vote.power);
+        vote.choice = new_choice.clone();
+        vote.voted_at = env.ledger().timestamp();
+
+        env.storage().persistent().set(&vote_key, &vote);
+        env.storage()
+            .persistent()
+            .extend_ttl(&vote_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::Proposal(proposal_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &proposal);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("revote")),
+            (proposal_id, voter, new_choice),
+        );
+    }
+
+    /// Lock governance tokens in the DAO as voting power
+    pub fn lock_tokens(env: Env, voter: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        voter.require_auth();
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+
+        let token_address: Address = env.storage().instance().get(&DataKey::GovernanceToken).unwrap();
+        token::Client::new(&env, &token_address).transfer(&voter, &env.current_contract_address(), &amount);
+
+        let locked = Self::_locked_balance(&env, &voter) + amount;
+        Self::_add_total_locked(&env, amount);
+        let _ttl_key = DataKey::LockedBalance(voter.clone());
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &locked);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("locked")),
+            (voter, amount, locked),
+        );
+    }
+
+    /// Withdraw locked tokens once every proposal the voter voted on has ended
+    pub fn unlock_tokens(env: Env, voter: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        voter.require_auth();
+        let locked_until: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::LockedUntil(voter.clone()))
+            .unwrap_or(0);
+        if env.ledger().sequence() <= locked_until {
+            panic!("tokens locked");
+        }
+        let locked = Self::_locked_balance(&env, &voter);
+        if amount <= 0 || amount > locked {
+            panic!("invalid amount");
+        }
+
+        let remaining = locked - amount;
+        Self::_add_total_locked(&env, -amount);
+        let _ttl_key = DataKey::LockedBalance(voter.clone());
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &remaining);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let token_address: Address = env.storage().instance().get(&DataKey::GovernanceToken).unwrap();
+        token::Client::new(&env, &token_address).transfer(&env.current_contract_address(), &voter, &amount);
+
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("unlocked")),
+            (voter, amount, remaining),
+        );
+    }
+
     /// Finalize a proposal after voting period
     pub fn finalize_proposal(env: Env, proposal_id: u64) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
@@ -292,26 +577,33 @@ impl GovernanceDaoContract {
             panic!("voting period not ended");
         }
 
-        let token_address: Address = env.storage().instance().get(&DataKey::GovernanceToken).unwrap();
-        let total_supply: i128 = env.invoke_contract(&token_address, &soroban_sdk::Symbol::new(&env, "total_supply"), soroban_sdk::vec![&env]);
+        let total_votes = proposal.votes_for + proposal.votes_against + proposal.votes_abstain;
+        let quorum_met = total_votes >= proposal.quorum_votes;
 
-        let total_votes = proposal.votes_for + proposal.votes_against;
-        
-        let quorum_met = (total_votes * 10_000) >= (total_supply * (proposal.quorum_bps as i128));
-
-        let for_pct = if total_votes > 0 {
-            (proposal.votes_for * 100) / total_votes
+        let decisive_votes = proposal.votes_for + proposal.votes_against;
+        let for_pct = if decisive_votes > 0 {
+            (proposal.votes_for * 100) / decisive_votes
         } else {
             0
         };
 
-        proposal.status = if quorum_met && for_pct as u32 >= proposal.threshold_pct {
+        proposal.status = if !quorum_met {
+            ProposalStatus::Expired
+        } else if for_pct as u32 >= proposal.threshold_pct {
             ProposalStatus::Passed
-        } else if !quorum_met {
-            ProposalStatus::Rejected
         } else {
             ProposalStatus::Rejected
         };
+        if proposal.status == ProposalStatus::Passed {
+            let delay: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::ExecutionDelay)
+                .unwrap_or(0);
+            proposal.executable_after = env.ledger().timestamp() + delay;
+            proposal.execution_deadline = proposal.executable_after + EXECUTION_GRACE_PERIOD;
+        }
+        Self::_remove_active(&env, proposal_id);
 
         let _ttl_key = DataKey::Proposal(proposal_id);
         env.storage()
@@ -323,11 +615,13 @@ impl GovernanceDaoContract {
 
         env.events().publish(
             (symbol_short!("proposal"), symbol_short!("finalized")),
-            (proposal_id, proposal.status),
+            (proposal_id, proposal.status, proposal.votes_for, proposal.votes_against, proposal.votes_abstain, quorum_met),
         );
     }
 
-    /// Mark proposal as executed (admin only)
+    /// Execute a passed proposal (admin only), invoking its call if it has
+    /// one. A failing call leaves the proposal Passed. Past the execution
+    /// deadline the proposal is marked Expired instead.
     pub fn execute_proposal(env: Env, admin: Address, proposal_id: u64) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         admin.require_auth();
@@ -345,9 +639,44 @@ impl GovernanceDaoContract {
         if proposal.status != ProposalStatus::Passed {
             panic!("proposal not passed");
         }
+        let now = env.ledger().timestamp();
+        if now < proposal.executable_after {
+            panic!("execution delay not elapsed");
+        }
+        if now > proposal.execution_deadline {
+            proposal.status = ProposalStatus::Expired;
+            let _ttl_key = DataKey::Proposal(proposal_id);
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &proposal);
+            env.storage()
+                .persistent()
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            return;
+        }
+
+        let mut result: Val = ().into();
+        let call: Option<ProposalCall> = env.storage().persistent().get(&DataKey::ProposalCall(proposal_id));
+        if let (Some(target), Some(call)) = (proposal.target_contract.clone(), call) {
+            result = match env.try_invoke_contract::<Val, soroban_sdk::Error>(&target, &call.function, call.args) {
+                Ok(Ok(value)) => value,
+                _ => panic!("proposal execution failed"),
+            };
+        }
+
+        if let Some(change) = env.storage().persistent().get(&DataKey::ParameterChange(proposal_id)) {
+            Self::_apply_parameter(&env, change);
+        }
+
+        if let Some(action) = env.storage().persistent().get(&DataKey::ProposalAction(proposal_id)) {
+            let (target, function, args) = Self::_action_call(&env, &action);
+            if !matches!(env.try_invoke_contract::<Val, soroban_sdk::Error>(&target, &function, args), Ok(Ok(_))) {
+                panic!("proposal execution failed");
+            }
+        }
 
         proposal.status = ProposalStatus::Executed;
-        proposal.executed_at = Some(env.ledger().timestamp());
+        proposal.executed_at = Some(now);
 
         let _ttl_key = DataKey::Proposal(proposal_id);
         env.storage()
@@ -356,9 +685,15 @@ impl GovernanceDaoContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("proposal"), symbol_short!("executed")),
+            (proposal_id, result),
+        );
     }
 
-    /// Cancel a proposal (proposer or admin)
+    /// Cancel an Active proposal. The proposer may cancel only before any vote
+    /// is cast; the admin may cancel at any point while it is Active.
     pub fn cancel_proposal(env: Env, caller: Address, proposal_id: u64) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         caller.require_auth();
@@ -373,8 +708,85 @@ impl GovernanceDaoContract {
         if caller != proposal.proposer && caller != admin {
             panic!("unauthorized");
         }
+        if proposal.status != ProposalStatus::Active {
+            panic!("proposal not active");
+        }
+        let has_votes = proposal.votes_for + proposal.votes_against + proposal.votes_abstain > 0;
+        if caller != admin && has_votes {
+            panic!("voting already started");
+        }
 
         proposal.status = ProposalStatus::Cancelled;
+        Self::_remove_active(&env, proposal_id);
+        let _ttl_key = DataKey::Proposal(proposal_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &proposal);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("proposal"), symbol_short!("cancel")),
+            (proposal_id, caller),
+        );
+    }
+
+    /// Record a guardian's veto. Reaching the veto threshold moves an Active
+    /// or Passed proposal to Vetoed; executed proposals can't be vetoed.
+    pub fn veto_proposal(env: Env, guardian: Address, proposal_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        guardian.require_auth();
+        if !env.storage().persistent().has(&DataKey::Guardian(guardian.clone())) {
+            panic!("unauthorized");
+        }
+
+        let mut proposal: Proposal = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .expect("proposal not found");
+        if proposal.status != ProposalStatus::Active && proposal.status != ProposalStatus::Passed {
+            panic!("proposal not vetoable");
+        }
+        let veto_key = DataKey::Veto(proposal_id, guardian);
+        if env.storage().persistent().has(&veto_key) {
+            panic!("already vetoed");
+        }
+        env.storage()
+            .persistent()
+            .set(&veto_key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&veto_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::VetoCount(proposal_id))
+            .unwrap_or(0)
+            + 1;
+        let _ttl_key = DataKey::VetoCount(proposal_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &count);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let threshold: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::GuardianThreshold)
+            .unwrap_or(0);
+        if count < threshold {
+            return;
+        }
+
+        if proposal.status == ProposalStatus::Active {
+            Self::_remove_active(&env, proposal_id);
+        }
+        proposal.status = ProposalStatus::Vetoed;
         let _ttl_key = DataKey::Proposal(proposal_id);
         env.storage()
             .persistent()
@@ -382,6 +794,45 @@ impl GovernanceDaoContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("proposal"), symbol_short!("vetoed")),
+            (proposal_id, count),
+        );
+    }
+
+    /// Quorum as basis points of the tokens locked in the DAO at proposal
+    /// creation (admin only)
+    pub fn set_quorum_bps(env: Env, admin: Address, quorum_bps: u32) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if quorum_bps > 10_000 {
+            panic!("invalid quorum");
+        }
+        env.storage().instance().set(&DataKey::QuorumBps, &quorum_bps);
+        env.storage().instance().remove(&DataKey::QuorumAmount);
+    }
+
+    /// Range a proposer may pick for a proposal's voting period (admin only).
+    /// Until set, only the default period is accepted.
+    pub fn set_voting_period_bounds(env: Env, admin: Address, min: u32, max: u32) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if min == 0 || min > max {
+            panic!("invalid bounds");
+        }
+        env.storage().instance().set(&DataKey::MinVotingPeriod, &min);
+        env.storage().instance().set(&DataKey::MaxVotingPeriod, &max);
+    }
+
+    /// Quorum as a fixed number of votes (admin only)
+    pub fn set_quorum_amount(env: Env, admin: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if amount < 0 {
+            panic!("invalid quorum");
+        }
+        env.storage().instance().set(&DataKey::QuorumAmount, &amount);
     }
 
     // ============================================================
@@ -395,6 +846,53 @@ impl GovernanceDaoContract {
             .get(&DataKey::Proposal(proposal_id))
     }
 
+    /// Whether `content` hashes to the proposal's stored content_hash; false
+    /// for unknown proposals and proposals without a hash
+    pub fn verify_content(env: Env, proposal_id: u64, content: Bytes) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let hash = env
+            .storage()
+            .persistent()
+            .get::<DataKey, Proposal>(&DataKey::Proposal(proposal_id))
+            .and_then(|proposal| proposal.content_hash);
+        match hash {
+            Some(hash) => BytesN::from(env.crypto().sha256(&content)) == hash,
+            None => false,
+        }
+    }
+
+    /// (executable_after, execution_deadline) of a passed proposal; zeros until it passes
+    pub fn get_execution_window(env: Env, proposal_id: u64) -> (u64, u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let proposal: Proposal = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .expect("proposal not found");
+        (proposal.executable_after, proposal.execution_deadline)
+    }
+
+    pub fn get_proposal_call(env: Env, proposal_id: u64) -> Option<ProposalCall> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::ProposalCall(proposal_id))
+    }
+
+    pub fn get_proposal_action(env: Env, proposal_id: u64) -> Option<ProposalAction> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::ProposalAction(proposal_id))
+    }
+
+    pub fn get_parameter_change(env: Env, proposal_id: u64) -> Option<ParameterChange> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::ParameterChange(proposal_id))
+    }
+
     pub fn get_vote(env: Env, proposal_id: u64, voter: Address) -> Option<Vote> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage()
@@ -410,6 +908,111 @@ impl GovernanceDaoContract {
             .unwrap_or(false)
     }
 
+    /// Power recorded for the voter when they voted on the proposal
+    pub fn get_power_snapshot(env: Env, proposal_id: u64, voter: Address) -> Option<i128> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::PowerSnapshot(proposal_id, voter))
+    }
+
+    pub fn get_locked_balance(env: Env, voter: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_locked_balance(&env, &voter)
+    }
+
+    /// Governance tokens locked across all voters; the base for bps quorum
+    pub fn get_total_locked(env: Env) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_total_locked(&env)
+    }
+
+    /// Last ledger at which the voter's locked tokens are still committed
+    pub fn get_locked_until(env: Env, voter: Address) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::LockedUntil(voter))
+            .unwrap_or(0)
+    }
+
+    /// Proposal ids in creation order; `limit` is capped at 100
+    pub fn list_proposals(env: Env, offset: u32, limit: u32) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposalCounter)
+            .unwrap_or(0);
+        let mut ids = Vec::new(&env);
+        let start = offset as u64 + 1;
+        let end = (offset as u64 + limit.min(MAX_LIST_LIMIT) as u64).min(count);
+        for id in start..=end {
+            ids.push_back(id);
+        }
+        ids
+    }
+
+    /// Addresses that voted on a proposal, in the order they first voted;
+    /// `limit` is capped at 100
+    pub fn get_voters(env: Env, proposal_id: u64, offset: u32, limit: u32) -> Vec<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let count = Self::get_voter_count(env.clone(), proposal_id);
+        let end = offset.saturating_add(limit.min(MAX_LIST_LIMIT)).min(count);
+        let mut voters = Vec::new(&env);
+        for i in offset..end {
+            if let Some(voter) = env.storage().persistent().get(&DataKey::VoterAt(proposal_id, i)) {
+                voters.push_back(voter);
+            }
+        }
+        voters
+    }
+
+    pub fn get_voter_count(env: Env, proposal_id: u64) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::VoterCount(proposal_id))
+            .unwrap_or(0)
+    }
+
+    /// Proposals still open for voting or awaiting finalization
+    pub fn get_active_proposals(env: Env) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .get(&DataKey::ActiveProposals)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// A proposer's proposal ids, oldest first; `limit` is capped at 100
+    pub fn get_proposals_by_proposer(env: Env, proposer: Address, offset: u32, limit: u32) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ProposerProposals(proposer))
+            .unwrap_or(Vec::new(&env));
+        if offset >= ids.len() {
+            return Vec::new(&env);
+        }
+        let end = offset.saturating_add(limit.min(MAX_LIST_LIMIT)).min(ids.len());
+        ids.slice(offset..end)
+    }
+
+    pub fn get_veto_count(env: Env, proposal_id: u64) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::VetoCount(proposal_id))
+            .unwrap_or(0)
+    }
+
+    pub fn is_guardian(env: Env, address: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().has(&DataKey::Guardian(address))
+    }
+
     pub fn get_proposal_count(env: Env) -> u64 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage()
@@ -417,6 +1020,272 @@ impl GovernanceDaoContract {
             .get(&DataKey::ProposalCounter)
             .unwrap_or(0)
     }
+
+    // ============================================================
+    // Internal Helpers
+    // ============================================================
+
+    fn _token_balance(env: &Env, account: &Address) -> i128 {
+        let token_address: Address = env.storage().instance().get(&DataKey::GovernanceToken).unwrap();
+        token::Client::new(env, &token_address).balance(account)
+    }
+
+    fn _remove_active(env: &Env, proposal_id: u64) {
+        let mut active: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ActiveProposals)
+            .unwrap_or(Vec::new(env));
+        if let Some(index) = active.first_index_of(proposal_id) {
+            active.remove(index);
+            env.storage().instance().set(&DataKey::ActiveProposals, &active);
+        }
+    }
+
+    fn _tally(proposal: &mut Proposal, choice: &VoteChoice, power: i128) {
+        match choice {
+            VoteChoice::For => proposal.votes_for += power,
+            VoteChoice::Against => proposal.votes_against += power,
+            VoteChoice::Abstain => proposal.votes_abstain += power,
+        }
+    }
+
+    /// Validate and store a new Active proposal, indexing it as active and
+    /// under its proposer
+    fn _create_call_proposal(
+        env: &Env,
+        proposer: Address,
+        title: String,
+        description: String,
+        content_hash: Option<BytesN<32>>,
+        target_contract: Option<Address>,
+        call: Option<ProposalCall>,
+        voting_period: Option<u32>,
+    ) -> u64 {
+        if call.is_some() {
+            let target = target_contract.as_ref().expect("call requires a target contract");
+            let token_address: Address = env.storage().instance().get(&DataKey::GovernanceToken).unwrap();
+            if *target == token_address || *target == env.current_contract_address() {
+                panic!("call target not allowed");
+            }
+        }
+
+        let proposal_id = Self::_create_proposal(env, proposer, title, description, content_hash, target_contract, voting_period);
+        if let Some(call) = call {
+            let _ttl_key = DataKey::ProposalCall(proposal_id);
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &call);
+            env.storage()
+                .persistent()
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+        proposal_id
+    }
+
+    fn _create_proposal(
+        env: &Env,
+        proposer: Address,
+        title: String,
+        description: String,
+        content_hash: Option<BytesN<32>>,
+        target_contract: Option<Address>,
+        voting_period: Option<u32>,
+    ) -> u64 {
+        let proposer_min: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposerMinTokens)
+            .unwrap_or(0);
+        if Self::_token_balance(env, &proposer) + Self::_locked_balance(env, &proposer) < proposer_min {
+            panic!("insufficient tokens to propose");
+        }
+
+        let mut active: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ActiveProposals)
+            .unwrap_or(Vec::new(env));
+        if active.len() >= MAX_ACTIVE_PROPOSALS {
+            panic!("too many active proposals");
+        }
+
+        let counter: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposalCounter)
+            .unwrap_or(0);
+        let proposal_id = counter + 1;
+
+        let default_period: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::VotingPeriod)
+            .unwrap_or(1_000);
+        let voting_period = match voting_period {
+            Some(period) => {
+                let min: u32 = env.storage().instance().get(&DataKey::MinVotingPeriod).unwrap_or(default_period);
+                let max: u32 = env.storage().instance().get(&DataKey::MaxVotingPeriod).unwrap_or(default_period);
+                if period < min || period > max {
+                    panic!("voting period out of bounds");
+                }
+                period
+            }
+            None => default_period,
+        };
+        // Absolute mode when QuorumAmount is set, otherwise bps of the tokens
+        // locked in the DAO, which the DAO tracks itself (token contracts such
+        // as the Stellar Asset Contract expose no total supply)
+        let (quorum_bps, quorum_votes) = match env.storage().instance().get::<DataKey, i128>(&DataKey::QuorumAmount) {
+            Some(amount) => (0, amount),
+            None => {
+                let bps: u32 = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::QuorumBps)
+                    .unwrap_or(0);
+                (bps, Self::_total_locked(env) * bps as i128 / 10_000)
+            }
+        };
+        let threshold: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PassThreshold)
+            .unwrap_or(51);
+
+        let start = env.ledger().sequence();
+        let proposal = Proposal {
+            proposer: proposer.clone(),
+            title,
+            description,
+            target_contract,
+            status: ProposalStatus::Active,
+            votes_for: 0,
+            votes_against: 0,
+            votes_abstain: 0,
+            quorum_bps,
+            quorum_votes,
+            threshold_pct: threshold,
+            start_ledger: start,
+            end_ledger: start + voting_period,
+            created_at: env.ledger().timestamp(),
+            executed_at: None,
+            executable_after: 0,
+            execution_deadline: 0,
+            content_hash,
+        };
+
+        let _ttl_key = DataKey::Proposal(proposal_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &proposal);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .set(&DataKey::ProposalCounter, &proposal_id);
+        active.push_back(proposal_id);
+        env.storage().instance().set(&DataKey::ActiveProposals, &active);
+        let _ttl_key = DataKey::ProposerProposals(proposer.clone());
+        let mut by_proposer: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&_ttl_key)
+            .unwrap_or(Vec::new(env));
+        by_proposer.push_back(proposal_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &by_proposer);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("proposal"), symbol_short!("created")),
+            (proposal_id, proposer),
+        );
+
+        proposal_id
+    }
+
+    fn _validate_parameter(change: &ParameterChange) {
+        let valid = match change {
+            ParameterChange::VotingPeriod(period) => *period > 0,
+            ParameterChange::QuorumBps(bps) => *bps <= 10_000,
+            ParameterChange::QuorumAmount(amount) => *amount >= 0,
+            ParameterChange::PassThreshold(pct) => *pct > 0 && *pct <= 100,
+            ParameterChange::ProposerMinTokens(amount) => *amount >= 0,
+        };
+        if !valid {
+            panic!("invalid parameter");
+        }
+    }
+
+    fn _apply_parameter(env: &Env, change: ParameterChange) {
+        let storage = env.storage().instance();
+        match change {
+            ParameterChange::VotingPeriod(period) => storage.set(&DataKey::VotingPeriod, &period),
+            ParameterChange::QuorumBps(bps) => {
+                storage.set(&DataKey::QuorumBps, &bps);
+                storage.remove(&DataKey::QuorumAmount);
+            }
+            ParameterChange::QuorumAmount(amount) => storage.set(&DataKey::QuorumAmount, &amount),
+            ParameterChange::PassThreshold(pct) => storage.set(&DataKey::PassThreshold, &pct),
+            ParameterChange::ProposerMinTokens(amount) => storage.set(&DataKey::ProposerMinTokens, &amount),
+        }
+    }
+
+    /// Target contract, function and arguments for an action, with the DAO
+    /// passed as the admin argument
+    fn _action_call(env: &Env, action: &ProposalAction) -> (Address, Symbol, Vec<Val>) {
+        let dao = env.current_contract_address();
+        match action.clone() {
+            ProposalAction::SetFlagThreshold(registry, threshold) => {
+                (registry, Symbol::new(env, "set_flag_threshold"), vec![env, dao.into_val(env), threshold.into_val(env)])
+            }
+            ProposalAction::SetOracle(vault, oracle) => {
+                (vault, Symbol::new(env, "set_oracle"), vec![env, dao.into_val(env), oracle.into_val(env)])
+            }
+            ProposalAction::SetFeeBps(auction, fee_bps) => {
+                (auction, Symbol::new(env, "set_fee_bps"), vec![env, dao.into_val(env), fee_bps.into_val(env)])
+            }
+            ProposalAction::SetMinPayout(payout, amount) => {
+                (payout, Symbol::new(env, "set_min_payout"), vec![env, dao.into_val(env), amount.into_val(env)])
+            }
+            ProposalAction::SetAdmin(target, new_admin) => {
+                (target, Symbol::new(env, "set_admin"), vec![env, dao.into_val(env), new_admin.into_val(env)])
+            }
+            ProposalAction::AcceptAdmin(vault) => {
+                (vault, Symbol::new(env, "accept_admin"), vec![env, dao.into_val(env)])
+            }
+        }
+    }
+
+    fn _total_locked(env: &Env) -> i128 {
+        env.storage().instance().get(&DataKey::TotalLocked).unwrap_or(0)
+    }
+
+    fn _add_total_locked(env: &Env, delta: i128) {
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalLocked, &(Self::_total_locked(env) + delta));
+    }
+
+    fn _require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *admin != stored_admin {
+            panic!("unauthorized");
+        }
+    }
+
+    fn _locked_balance(env: &Env, voter: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::LockedBalance(voter.clone()))
+            .unwrap_or(0)
+    }
 }
 
 mod test;
@@ -1,6 +1,7 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, IntoVal};
+use pulsar_ad_registry::{AdRegistryContract, AdRegistryContractClient};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, token, vec, Address, Bytes, BytesN, Env, IntoVal};
 
 #[test]
 fn test_initialize() {
@@ -13,7 +14,7 @@ fn test_initialize() {
     let admin = Address::generate(&env);
     let token = Address::generate(&env);
 
-    client.initialize(&admin, &token, &3600u32, &1000u32, &5000u32, &100i128);
+    client.initialize(&admin, &token, &3600u32, &1000u32, &5000u32, &100i128, &0u64, &vec![&env], &0u32);
 }
 
 #[test]
@@ -28,8 +29,8 @@ fn test_initialize_twice() {
     let admin = Address::generate(&env);
     let token = Address::generate(&env);
 
-    client.initialize(&admin, &token, &3600u32, &1000u32, &5000u32, &100i128);
-    client.initialize(&admin, &token, &3600u32, &1000u32, &5000u32, &100i128);
+    client.initialize(&admin, &token, &3600u32, &1000u32, &5000u32, &100i128, &0u64, &vec![&env], &0u32);
+    client.initialize(&admin, &token, &3600u32, &1000u32, &5000u32, &100i128, &0u64, &vec![&env], &0u32);
 }
 
 #[test]
@@ -44,5 +45,793 @@ fn test_initialize_non_admin_fails() {
     let token = Address::generate(&env);
 
     // This should panic because admin didn't authorize it and we haven't mocked it
-    client.initialize(&admin, &token, &3600u32, &1000u32, &5000u32, &100i128);
+    client.initialize(&admin, &token, &3600u32, &1000u32, &5000u32, &100i128, &0u64, &vec![&env], &0u32);
+}
+
+// ============================================================
+// Voting Power
+// ============================================================
+
+fn setup(env: &Env) -> (GovernanceDaoContractClient<'_>, Address, Address) {
+    env.mock_all_auths();
+    let client = GovernanceDaoContractClient::new(env, &env.register(GovernanceDaoContract, ()));
+    let admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    client.initialize(&admin, &token, &100u32, &1000u32, &51u32, &100i128, &3_600u64, &vec![env], &0u32);
+    (client, admin, token)
+}
+
+fn holder(env: &Env, token: &Address, amount: i128) -> Address {
+    let account = Address::generate(env);
+    if amount > 0 {
+        token::StellarAssetClient::new(env, token).mint(&account, &amount);
+    }
+    account
+}
+
+/// A holder with `amount` tokens locked as voting power
+fn voter(env: &Env, client: &GovernanceDaoContractClient, token: &Address, amount: i128) -> Address {
+    let account = holder(env, token, amount);
+    client.lock_tokens(&account, &amount);
+    account
+}
+
+fn propose(env: &Env, client: &GovernanceDaoContractClient, proposer: &Address) -> u64 {
+    client.create_proposal(
+        proposer,
+        &String::from_str(env, "Raise flag threshold"),
+        &String::from_str(env, "Five flags is too few"),
+        &None,
+        &None,
+        &None,
+    )
+}
+
+#[test]
+fn test_vote_power_is_locked_balance() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    let proposer = holder(&env, &token, 100);
+    let proposal_id = propose(&env, &client, &proposer);
+    let whale = voter(&env, &client, &token, 5_000);
+    let minnow = voter(&env, &client, &token, 7);
+
+    client.cast_vote(&whale, &proposal_id, &VoteChoice::For);
+    client.cast_vote(&minnow, &proposal_id, &VoteChoice::Against);
+
+    assert_eq!(client.get_vote(&proposal_id, &whale).unwrap().power, 5_000);
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.votes_for, 5_000);
+    assert_eq!(proposal.votes_against, 7);
+}
+
+#[test]
+#[should_panic(expected = "no voting power")]
+fn test_vote_without_tokens_rejected() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    let proposal_id = propose(&env, &client, &holder(&env, &token, 100));
+
+    // Unlocked wallet tokens carry no weight
+    client.cast_vote(&holder(&env, &token, 1_000), &proposal_id, &VoteChoice::For);
+}
+
+#[test]
+#[should_panic(expected = "insufficient tokens to propose")]
+fn test_proposer_needs_min_tokens() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+
+    propose(&env, &client, &holder(&env, &token, 99));
+}
+
+#[test]
+fn test_transfer_after_vote_keeps_recorded_power() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    let token_client = token::Client::new(&env, &token);
+    let proposal_id = propose(&env, &client, &holder(&env, &token, 100));
+    let whale = voter(&env, &client, &token, 1_000);
+    token::StellarAssetClient::new(&env, &token).mint(&whale, &500);
+
+    client.cast_vote(&whale, &proposal_id, &VoteChoice::For);
+    assert_eq!(client.get_power_snapshot(&proposal_id, &whale), Some(1_000));
+    assert_eq!(client.get_locked_until(&whale), 100);
+    assert!(client.try_unlock_tokens(&whale, &1_000).is_err());
+
+    // Moving wallet tokens to a second wallet only adds that wallet's own lock
+    let second = Address::generate(&env);
+    token_client.transfer(&whale, &second, &500);
+    client.lock_tokens(&second, &500);
+    client.cast_vote(&second, &proposal_id, &VoteChoice::For);
+
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.votes_for, 1_500);
+    assert_eq!(client.get_vote(&proposal_id, &whale).unwrap().power, 1_000);
+    assert_eq!(client.get_power_snapshot(&proposal_id, &second), Some(500));
+    assert!(client.try_cast_vote(&whale, &proposal_id, &VoteChoice::Against).is_err());
+
+    env.ledger().set_sequence_number(101);
+    assert_eq!(client.get_total_locked(), 1_500);
+    client.unlock_tokens(&whale, &1_000);
+    assert_eq!(client.get_locked_balance(&whale), 0);
+    assert_eq!(client.get_total_locked(), 500);
+    assert_eq!(token_client.balance(&whale), 1_000);
+    assert_eq!(client.get_power_snapshot(&proposal_id, &whale), Some(1_000));
+}
+
+// ============================================================
+// Changing Votes
+// ============================================================
+
+#[test]
+fn test_change_vote_moves_power_between_tallies() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    let proposal_id = propose(&env, &client, &holder(&env, &token, 100));
+    let fickle = voter(&env, &client, &token, 300);
+    let steady = voter(&env, &client, &token, 50);
+    client.cast_vote(&fickle, &proposal_id, &VoteChoice::For);
+    client.cast_vote(&steady, &proposal_id, &VoteChoice::Against);
+
+    // Extra tokens locked after voting don't change the recorded power
+    token::StellarAssetClient::new(&env, &token).mint(&fickle, &1_000);
+    client.lock_tokens(&fickle, &1_000);
+
+    client.change_vote(&fickle, &proposal_id, &VoteChoice::Abstain);
+    let tallies = |id: u64| {
+        let p = client.get_proposal(&id).unwrap();
+        (p.votes_for, p.votes_against, p.votes_abstain)
+    };
+    assert_eq!(tallies(proposal_id), (0, 50, 300));
+
+    client.change_vote(&fickle, &proposal_id, &VoteChoice::Against);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("gov"), symbol_short!("revote")).into_val(&env),
+                (proposal_id, fickle.clone(), VoteChoice::Against).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(tallies(proposal_id), (0, 350, 0));
+    let vote = client.get_vote(&proposal_id, &fickle).unwrap();
+    assert!(matches!(vote.choice, VoteChoice::Against));
+    assert_eq!(vote.power, 300);
+}
+
+#[test]
+#[should_panic(expected = "voting period ended")]
+fn test_change_vote_after_end_fails() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    let proposal_id = propose(&env, &client, &holder(&env, &token, 100));
+    let account = voter(&env, &client, &token, 300);
+    client.cast_vote(&account, &proposal_id, &VoteChoice::For);
+
+    env.ledger().set_sequence_number(101);
+    client.change_vote(&account, &proposal_id, &VoteChoice::Against);
+}
+
+#[test]
+#[should_panic(expected = "vote not found")]
+fn test_change_vote_requires_existing_vote() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    let proposal_id = propose(&env, &client, &holder(&env, &token, 100));
+
+    client.change_vote(&voter(&env, &client, &token, 300), &proposal_id, &VoteChoice::Against);
+}
+
+// ============================================================
+// Execution
+// ============================================================
+
+/// A proposal that called `call` on `target`, voted through and finalized
+fn passed_proposal(env: &Env, client: &GovernanceDaoContractClient, token: &Address, target: &Address, call: ProposalCall) -> u64 {
+    let proposal_id = client.create_proposal(
+        &holder(env, token, 100),
+        &String::from_str(env, "Add tracker"),
+        &String::from_str(env, "Onboard the new analytics tracker"),
+        &Some(target.clone()),
+        &Some(call),
+        &None,
+    );
+    client.cast_vote(&voter(env, client, token, 1_000), &proposal_id, &VoteChoice::For);
+    env.ledger().set_sequence_number(env.ledger().sequence() + 101);
+    client.finalize_proposal(&proposal_id);
+    proposal_id
+}
+
+#[test]
+fn test_execute_invokes_target_contract() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let registry = AdRegistryContractClient::new(&env, &env.register(AdRegistryContract, ()));
+    registry.initialize(&client.address);
+    let tracker = Address::generate(&env);
+
+    let call = ProposalCall {
+        function: Symbol::new(&env, "add_tracker"),
+        args: vec![&env, client.address.into_val(&env), tracker.into_val(&env)],
+    };
+    let proposal_id = passed_proposal(&env, &client, &token, &registry.address, call);
+    assert!(client.get_proposal(&proposal_id).unwrap().status == ProposalStatus::Passed);
+    env.ledger().set_timestamp(3_600);
+
+    client.execute_proposal(&admin, &proposal_id);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(event.1, (symbol_short!("proposal"), symbol_short!("executed")).into_val(&env));
+    assert!(registry.is_tracker(&tracker));
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert!(proposal.status == ProposalStatus::Executed);
+
+    // A proposal executes once
+    assert!(client.try_execute_proposal(&admin, &proposal_id).is_err());
+}
+
+#[test]
+fn test_call_cannot_target_governance_token_or_dao() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    let proposer = holder(&env, &token, 100);
+    voter(&env, &client, &token, 5_000);
+
+    let drain = ProposalCall {
+        function: Symbol::new(&env, "transfer"),
+        args: vec![&env, client.address.into_val(&env), proposer.into_val(&env), 5_000i128.into_val(&env)],
+    };
+    let title = String::from_str(&env, "Drain");
+    for target in [token.clone(), client.address.clone()] {
+        let result = client.try_create_proposal(&proposer, &title, &title, &Some(target.clone()), &Some(drain.clone()), &None);
+        assert!(result.is_err());
+        let result = client.try_create_proposal_with_hash(&proposer, &BytesN::from_array(&env, &[1; 32]), &Some(target), &Some(drain.clone()), &None);
+        assert!(result.is_err());
+    }
+    assert_eq!(client.get_proposal_count(), 0);
+
+    // Any other target is accepted
+    client.create_proposal(&proposer, &title, &title, &Some(Address::generate(&env)), &Some(drain), &None);
+    assert_eq!(client.get_proposal_count(), 1);
+}
+
+#[test]
+fn test_failed_call_leaves_proposal_passed() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let registry = AdRegistryContractClient::new(&env, &env.register(AdRegistryContract, ()));
+    registry.initialize(&Address::generate(&env));
+
+    // The DAO isn't the registry admin, so the call is rejected
+    let call = ProposalCall {
+        function: Symbol::new(&env, "add_tracker"),
+        args: vec![&env, client.address.into_val(&env), Address::generate(&env).into_val(&env)],
+    };
+    let proposal_id = passed_proposal(&env, &client, &token, &registry.address, call);
+    env.ledger().set_timestamp(3_600);
+
+    assert!(client.try_execute_proposal(&admin, &proposal_id).is_err());
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert!(proposal.status == ProposalStatus::Passed);
+    assert!(proposal.executed_at.is_none());
+}
+
+#[test]
+fn test_execution_window() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let registry = AdRegistryContractClient::new(&env, &env.register(AdRegistryContract, ()));
+    registry.initialize(&client.address);
+    let call = |tracker: &Address| ProposalCall {
+        function: Symbol::new(&env, "add_tracker"),
+        args: vec![&env, client.address.into_val(&env), tracker.into_val(&env)],
+    };
+
+    env.ledger().set_timestamp(1_000);
+    let tracker = Address::generate(&env);
+    let on_time = passed_proposal(&env, &client, &token, &registry.address, call(&tracker));
+    assert_eq!(client.get_execution_window(&on_time), (4_600, 4_600 + 1_209_600));
+
+    // Too early
+    env.ledger().set_timestamp(4_599);
+    assert!(client.try_execute_proposal(&admin, &on_time).is_err());
+    assert!(!registry.is_tracker(&tracker));
+
+    // In the window
+    env.ledger().set_timestamp(4_600);
+    let late_tracker = Address::generate(&env);
+    let late = passed_proposal(&env, &client, &token, &registry.address, call(&late_tracker));
+    client.execute_proposal(&admin, &on_time);
+    assert!(registry.is_tracker(&tracker));
+
+    // Too late: the proposal expires without running its call
+    let (_, deadline) = client.get_execution_window(&late);
+    env.ledger().set_timestamp(deadline + 1);
+    client.execute_proposal(&admin, &late);
+    let proposal = client.get_proposal(&late).unwrap();
+    assert!(proposal.status == ProposalStatus::Expired);
+    assert!(proposal.executed_at.is_none());
+    assert!(!registry.is_tracker(&late_tracker));
+    assert!(client.try_execute_proposal(&admin, &late).is_err());
+}
+
+// ============================================================
+// Listing
+// ============================================================
+
+#[test]
+fn test_active_index_shrinks_after_finalization() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let alice = holder(&env, &token, 100);
+    let bob = holder(&env, &token, 100);
+    let first = propose(&env, &client, &alice);
+    let second = propose(&env, &client, &bob);
+    let third = propose(&env, &client, &alice);
+
+    assert_eq!(client.list_proposals(&0, &10), vec![&env, first, second, third]);
+    assert_eq!(client.list_proposals(&1, &1), vec![&env, second]);
+    assert_eq!(client.list_proposals(&3, &10).len(), 0);
+    assert_eq!(client.get_proposals_by_proposer(&alice, &0, &10), vec![&env, first, third]);
+    assert_eq!(client.get_proposals_by_proposer(&alice, &1, &10), vec![&env, third]);
+    assert_eq!(client.get_proposals_by_proposer(&bob, &0, &10), vec![&env, second]);
+    assert_eq!(client.get_active_proposals(), vec![&env, first, second, third]);
+
+    client.cancel_proposal(&admin, &second);
+    assert_eq!(client.get_active_proposals(), vec![&env, first, third]);
+
+    env.ledger().set_sequence_number(101);
+    client.finalize_proposal(&first);
+    assert_eq!(client.get_active_proposals(), vec![&env, third]);
+    client.finalize_proposal(&third);
+    assert_eq!(client.get_active_proposals().len(), 0);
+    assert_eq!(client.list_proposals(&0, &10).len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "too many active proposals")]
+fn test_active_proposals_capped() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    let proposer = holder(&env, &token, 100);
+    for _ in 0..51 {
+        propose(&env, &client, &proposer);
+    }
+}
+
+// ============================================================
+// Quorum
+// ============================================================
+
+#[test]
+fn test_quorum_resolved_from_locked_total_at_creation() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    // 10% of 10_000 locked; unlocked holdings don't count
+    let abstainer = voter(&env, &client, &token, 600);
+    let supporter = voter(&env, &client, &token, 400);
+    voter(&env, &client, &token, 9_000);
+    holder(&env, &token, 50_000);
+    assert_eq!(client.get_total_locked(), 10_000);
+    let proposal_id = propose(&env, &client, &holder(&env, &token, 100));
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.quorum_bps, 1_000);
+    assert_eq!(proposal.quorum_votes, 1_000);
+
+    // Later locking doesn't move the quorum of an existing proposal
+    voter(&env, &client, &token, 1_000_000);
+    client.cast_vote(&abstainer, &proposal_id, &VoteChoice::Abstain);
+    client.cast_vote(&supporter, &proposal_id, &VoteChoice::For);
+
+    // Abstentions count towards quorum but not the pass threshold
+    env.ledger().set_sequence_number(101);
+    client.finalize_proposal(&proposal_id);
+    assert!(client.get_proposal(&proposal_id).unwrap().status == ProposalStatus::Passed);
+}
+
+#[test]
+fn test_absolute_quorum_mode() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    client.set_quorum_amount(&admin, &5_000);
+    let supporter = voter(&env, &client, &token, 1_000);
+    let proposal_id = propose(&env, &client, &holder(&env, &token, 100));
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.quorum_bps, 0);
+    assert_eq!(proposal.quorum_votes, 5_000);
+
+    client.cast_vote(&supporter, &proposal_id, &VoteChoice::For);
+    env.ledger().set_sequence_number(101);
+    client.finalize_proposal(&proposal_id);
+    assert!(client.get_proposal(&proposal_id).unwrap().status == ProposalStatus::Expired);
+
+    // Switching back to bps resolves against the locked total again
+    client.set_quorum_bps(&admin, &500);
+    let next = propose(&env, &client, &holder(&env, &token, 100));
+    assert_eq!(client.get_proposal(&next).unwrap().quorum_votes, 50);
+}
+
+// ============================================================
+// Status Transitions
+// ============================================================
+
+fn status(client: &GovernanceDaoContractClient, proposal_id: u64) -> ProposalStatus {
+    client.get_proposal(&proposal_id).unwrap().status
+}
+
+#[test]
+fn test_finalize_outcomes() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    let big = voter(&env, &client, &token, 600);
+    let small = voter(&env, &client, &token, 400);
+    let passed = propose(&env, &client, &holder(&env, &token, 100));
+    let rejected = propose(&env, &client, &holder(&env, &token, 100));
+    let expired = propose(&env, &client, &holder(&env, &token, 100));
+
+    client.cast_vote(&big, &passed, &VoteChoice::For);
+    client.cast_vote(&small, &passed, &VoteChoice::Against);
+    client.cast_vote(&big, &rejected, &VoteChoice::Against);
+    client.cast_vote(&small, &rejected, &VoteChoice::For);
+    // 1_000 locked at creation: 10% quorum is 100 votes
+    client.cast_vote(&voter(&env, &client, &token, 1), &expired, &VoteChoice::For);
+
+    assert!(client.try_finalize_proposal(&passed).is_err());
+    env.ledger().set_sequence_number(101);
+    client.finalize_proposal(&passed);
+    client.finalize_proposal(&rejected);
+    client.finalize_proposal(&expired);
+    assert!(status(&client, passed) == ProposalStatus::Passed);
+    assert!(status(&client, rejected) == ProposalStatus::Rejected);
+    assert!(status(&client, expired) == ProposalStatus::Expired);
+    assert!(client.try_finalize_proposal(&passed).is_err());
+}
+
+#[test]
+fn test_cancel_rules() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let proposer = holder(&env, &token, 100);
+    let supporter = voter(&env, &client, &token, 500);
+
+    // Proposer cancels before any vote
+    let untouched = propose(&env, &client, &proposer);
+    client.cancel_proposal(&proposer, &untouched);
+    assert!(status(&client, untouched) == ProposalStatus::Cancelled);
+    assert!(client.try_cancel_proposal(&admin, &untouched).is_err());
+
+    // Once voting starts only the admin can cancel
+    let voted = propose(&env, &client, &proposer);
+    client.cast_vote(&supporter, &voted, &VoteChoice::For);
+    assert!(client.try_cancel_proposal(&proposer, &voted).is_err());
+    assert!(client.try_cancel_proposal(&Address::generate(&env), &voted).is_err());
+    client.cancel_proposal(&admin, &voted);
+    assert!(status(&client, voted) == ProposalStatus::Cancelled);
+
+    // Nobody cancels a finalized proposal
+    let finished = propose(&env, &client, &proposer);
+    client.cast_vote(&supporter, &finished, &VoteChoice::For);
+    env.ledger().set_sequence_number(101);
+    client.finalize_proposal(&finished);
+    assert!(status(&client, finished) == ProposalStatus::Passed);
+    assert!(client.try_cancel_proposal(&admin, &finished).is_err());
+    assert!(client.try_cancel_proposal(&proposer, &finished).is_err());
+    assert!(status(&client, finished) == ProposalStatus::Passed);
+}
+
+#[test]
+fn test_voting_period_override_bounds() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let proposer = holder(&env, &token, 100);
+    let title = String::from_str(&env, "Quick fix");
+    let description = String::from_str(&env, "Urgent tracker rotation");
+
+    // Without bounds only the default period is allowed
+    assert!(client.try_create_proposal(&proposer, &title, &description, &None, &None, &Some(50)).is_err());
+    let id = client.create_proposal(&proposer, &title, &description, &None, &None, &Some(100));
+    assert_eq!(client.get_proposal(&id).unwrap().end_ledger, 100);
+
+    assert!(client.try_set_voting_period_bounds(&admin, &0, &10).is_err());
+    assert!(client.try_set_voting_period_bounds(&admin, &200, &100).is_err());
+    client.set_voting_period_bounds(&admin, &20, &500);
+
+    let short = client.create_proposal(&proposer, &title, &description, &None, &None, &Some(20));
+    assert_eq!(client.get_proposal(&short).unwrap().end_ledger, 20);
+    let default = client.create_proposal(&proposer, &title, &description, &None, &None, &None);
+    assert_eq!(client.get_proposal(&default).unwrap().end_ledger, 100);
+    assert!(client.try_create_proposal(&proposer, &title, &description, &None, &None, &Some(19)).is_err());
+    assert!(client.try_create_proposal(&proposer, &title, &description, &None, &None, &Some(501)).is_err());
+}
+
+#[test]
+fn test_parameter_change_proposal() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let proposer = holder(&env, &token, 100);
+    let title = String::from_str(&env, "Longer votes");
+    let description = String::from_str(&env, "Give holders more time");
+
+    assert!(client
+        .try_propose_parameter_change(&proposer, &title, &description, &ParameterChange::PassThreshold(101), &None)
+        .is_err());
+    assert!(client
+        .try_propose_parameter_change(&proposer, &title, &description, &ParameterChange::VotingPeriod(0), &None)
+        .is_err());
+
+    let change = ParameterChange::VotingPeriod(300);
+    let id = client.propose_parameter_change(&proposer, &title, &description, &change, &None);
+    assert!(client.get_parameter_change(&id) == Some(change));
+    client.cast_vote(&voter(&env, &client, &token, 1_000), &id, &VoteChoice::For);
+    env.ledger().set_sequence_number(101);
+    client.finalize_proposal(&id);
+    assert!(status(&client, id) == ProposalStatus::Passed);
+
+    // Nothing changes until the proposal is executed
+    let before = propose(&env, &client, &proposer);
+    assert_eq!(client.get_proposal(&before).unwrap().end_ledger, 201);
+
+    env.ledger().set_timestamp(3_600);
+    client.execute_proposal(&admin, &id);
+    assert!(status(&client, id) == ProposalStatus::Executed);
+
+    let after = propose(&env, &client, &proposer);
+    assert_eq!(client.get_proposal(&after).unwrap().end_ledger, 401);
+}
+
+#[test]
+fn test_voter_enumeration() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let id = propose(&env, &client, &holder(&env, &token, 100));
+
+    let mut voters = Vec::new(&env);
+    for _ in 0..5 {
+        let account = voter(&env, &client, &token, 100);
+        client.cast_vote(&account, &id, &VoteChoice::For);
+        voters.push_back(account);
+    }
+    client.change_vote(&voters.get(1).unwrap(), &id, &VoteChoice::Against);
+    assert_eq!(client.get_voter_count(&id), 5);
+
+    assert_eq!(client.get_voters(&id, &0, &2), voters.slice(0..2));
+    assert_eq!(client.get_voters(&id, &2, &10), voters.slice(2..5));
+    assert_eq!(client.get_voters(&id, &5, &10).len(), 0);
+    assert_eq!(client.get_voter_count(&(id + 1)), 0);
+}
+
+#[test]
+fn test_finalize_and_cancel_events() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let proposer = holder(&env, &token, 100);
+
+    let id = propose(&env, &client, &proposer);
+    client.cast_vote(&voter(&env, &client, &token, 600), &id, &VoteChoice::For);
+    client.cast_vote(&voter(&env, &client, &token, 300), &id, &VoteChoice::Against);
+    client.cast_vote(&voter(&env, &client, &token, 100), &id, &VoteChoice::Abstain);
+    env.ledger().set_sequence_number(101);
+    client.finalize_proposal(&id);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("proposal"), symbol_short!("finalized")).into_val(&env),
+                (id, ProposalStatus::Passed, 600i128, 300i128, 100i128, true).into_val(&env),
+            ),
+        ]
+    );
+
+    let cancelled = propose(&env, &client, &proposer);
+    client.cancel_proposal(&admin, &cancelled);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, event],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("proposal"), symbol_short!("cancel")).into_val(&env),
+                (cancelled, admin.clone()).into_val(&env),
+            ),
+        ]
+    );
+}
+
+// ============================================================
+// Off-chain Content
+// ============================================================
+
+#[test]
+fn test_hashed_proposal_verifies_content() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let proposer = voter(&env, &client, &token, 500);
+
+    let document = Bytes::from_slice(&env, b"# PIP-7\nRaise the flag threshold to ten.");
+    let hash: BytesN<32> = env.crypto().sha256(&document).into();
+    let proposal_id = client.create_proposal_with_hash(&proposer, &hash, &None, &None, &None);
+
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.content_hash, Some(hash));
+    assert_eq!(proposal.title, String::from_str(&env, ""));
+    assert!(proposal.status == ProposalStatus::Active);
+    assert!(client.verify_content(&proposal_id, &document));
+    assert!(!client.verify_content(&proposal_id, &Bytes::from_slice(&env, b"# PIP-7\nRaise the flag threshold to 100.")));
+    assert!(!client.verify_content(&99, &document));
+}
+
+#[test]
+fn test_text_proposal_has_no_content_hash() {
+    let env = Env::default();
+    let (client, _admin, token) = setup(&env);
+    let proposer = voter(&env, &client, &token, 500);
+
+    let proposal_id = propose(&env, &client, &proposer);
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.title, String::from_str(&env, "Raise flag threshold"));
+    assert_eq!(proposal.content_hash, None);
+    assert!(!client.verify_content(&proposal_id, &Bytes::from_slice(&env, b"Five flags is too few")));
+}
+
+// ============================================================
+// Guardian Veto
+// ============================================================
+
+fn guarded_setup(env: &Env) -> (GovernanceDaoContractClient<'_>, Address, Address, Vec<Address>) {
+    env.mock_all_auths();
+    let client = GovernanceDaoContractClient::new(env, &env.register(GovernanceDaoContract, ()));
+    let admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    let guardians = vec![env, Address::generate(env), Address::generate(env), Address::generate(env)];
+    client.initialize(&admin, &token, &100u32, &1000u32, &51u32, &100i128, &3_600u64, &guardians, &2u32);
+    (client, admin, token, guardians)
+}
+
+#[test]
+fn test_initialize_rejects_bad_veto_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let guardians = vec![&env, Address::generate(&env), Address::generate(&env)];
+    for threshold in [0u32, 3u32] {
+        let client = GovernanceDaoContractClient::new(&env, &env.register(GovernanceDaoContract, ()));
+        let result = client.try_initialize(&Address::generate(&env), &token, &100u32, &1000u32, &51u32, &100i128, &0u64, &guardians, &threshold);
+        assert!(result.is_err());
+    }
+    let client = GovernanceDaoContractClient::new(&env, &env.register(GovernanceDaoContract, ()));
+    let result = client.try_initialize(&Address::generate(&env), &token, &100u32, &1000u32, &51u32, &100i128, &0u64, &vec![&env], &1u32);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_veto_below_threshold_keeps_proposal_executable() {
+    let env = Env::default();
+    let (client, admin, token, guardians) = guarded_setup(&env);
+    let registry = AdRegistryContractClient::new(&env, &env.register(AdRegistryContract, ()));
+    registry.initialize(&client.address);
+    let tracker = Address::generate(&env);
+    let call = ProposalCall {
+        function: Symbol::new(&env, "add_tracker"),
+        args: vec![&env, client.address.into_val(&env), tracker.into_val(&env)],
+    };
+    let proposal_id = passed_proposal(&env, &client, &token, &registry.address, call);
+    assert!(client.is_guardian(&guardians.get(0).unwrap()));
+
+    client.veto_proposal(&guardians.get(0).unwrap(), &proposal_id);
+    assert_eq!(client.get_veto_count(&proposal_id), 1);
+    assert!(status(&client, proposal_id) == ProposalStatus::Passed);
+
+    // The same guardian can't veto twice, and outsiders can't veto at all
+    assert!(client.try_veto_proposal(&guardians.get(0).unwrap(), &proposal_id).is_err());
+    assert!(client.try_veto_proposal(&Address::generate(&env), &proposal_id).is_err());
+    assert_eq!(client.get_veto_count(&proposal_id), 1);
+
+    env.ledger().set_timestamp(3_600);
+    client.execute_proposal(&admin, &proposal_id);
+    assert!(registry.is_tracker(&tracker));
+
+    // Executed proposals are out of reach
+    assert!(client.try_veto_proposal(&guardians.get(1).unwrap(), &proposal_id).is_err());
+    assert!(status(&client, proposal_id) == ProposalStatus::Executed);
+}
+
+#[test]
+fn test_veto_at_threshold_blocks_proposal() {
+    let env = Env::default();
+    let (client, admin, token, guardians) = guarded_setup(&env);
+    let target = Address::generate(&env);
+    let call = ProposalCall { function: Symbol::new(&env, "add_tracker"), args: vec![&env] };
+    let passed = passed_proposal(&env, &client, &token, &target, call);
+    let active = propose(&env, &client, &holder(&env, &token, 100));
+
+    client.veto_proposal(&guardians.get(0).unwrap(), &passed);
+    client.veto_proposal(&guardians.get(2).unwrap(), &passed);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(event.1, (symbol_short!("proposal"), symbol_short!("vetoed")).into_val(&env));
+    let (id, count): (u64, u32) = event.2.into_val(&env);
+    assert_eq!((id, count), (passed, 2));
+    assert_eq!(client.get_veto_count(&passed), 2);
+    assert!(status(&client, passed) == ProposalStatus::Vetoed);
+
+    env.ledger().set_timestamp(3_600);
+    assert!(client.try_execute_proposal(&admin, &passed).is_err());
+    assert!(client.try_veto_proposal(&guardians.get(1).unwrap(), &passed).is_err());
+
+    // Vetoing an open proposal closes voting on it
+    client.veto_proposal(&guardians.get(1).unwrap(), &active);
+    client.veto_proposal(&guardians.get(2).unwrap(), &active);
+    assert!(status(&client, active) == ProposalStatus::Vetoed);
+    assert!(client.try_cast_vote(&voter(&env, &client, &token, 10), &active, &VoteChoice::For).is_err());
+    assert!(!client.get_active_proposals().contains(active));
+}
+
+// ============================================================
+// Admin Actions
+// ============================================================
+
+#[test]
+fn test_action_proposal_sets_registry_flag_threshold() {
+
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let registry = AdRegistryContractClient::new(&env, &env.register(AdRegistryContract, ()));
+    let registry_admin = Address::generate(&env);
+    registry.initialize(&registry_admin);
+
+    // Install the DAO as the registry's admin
+    registry.set_admin(&registry_admin, &client.address);
+    assert_eq!(registry.get_admin(), client.address);
+    assert!(registry.try_set_flag_threshold(&registry_admin, &9).is_err());
+
+    let action = ProposalAction::SetFlagThreshold(registry.address.clone(), 9);
+    let id = client.propose_action(
+        &holder(&env, &token, 100),
+        &String::from_str(&env, "Raise flag threshold"),
+        &String::from_str(&env, "Five flags is too few"),
+        &action,
+        &None,
+    );
+    assert!(client.get_proposal_action(&id) == Some(action));
+    assert_eq!(client.get_proposal(&id).unwrap().target_contract, Some(registry.address.clone()));
+
+    client.cast_vote(&voter(&env, &client, &token, 1_000), &id, &VoteChoice::For);
+    env.ledger().set_sequence_number(101);
+    client.finalize_proposal(&id);
+    assert_eq!(registry.get_flag_threshold(), 5);
+
+    env.ledger().set_timestamp(3_600);
+    client.execute_proposal(&admin, &id);
+    assert!(status(&client, id) == ProposalStatus::Executed);
+    assert_eq!(registry.get_flag_threshold(), 9);
+}
+
+#[test]
+#[should_panic(expected = "proposal execution failed")]
+fn test_action_proposal_fails_without_admin_role() {
+
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let registry = AdRegistryContractClient::new(&env, &env.register(AdRegistryContract, ()));
+    registry.initialize(&Address::generate(&env));
+
+    let id = client.propose_action(
+        &holder(&env, &token, 100),
+        &String::from_str(&env, "Raise flag threshold"),
+        &String::from_str(&env, "Five flags is too few"),
+        &ProposalAction::SetFlagThreshold(registry.address.clone(), 9),
+        &None,
+    );
+    client.cast_vote(&voter(&env, &client, &token, 1_000), &id, &VoteChoice::For);
+    env.ledger().set_sequence_number(101);
+    client.finalize_proposal(&id);
+    env.ledger().set_timestamp(3_600);
+    client.execute_proposal(&admin, &id);
 }
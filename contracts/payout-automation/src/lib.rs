@@ -2,18 +2,57 @@
 //! Automated publisher payouts and scheduled payments on Stellar.
 //!
 //! Events:
-//! - ("payout", "schedule"): [payout_id: u64, recipient: Address, amount: i128]
-//! - ("payout", "execute"): [payout_id: u64, amount: i128]
+//! - ("payout", "schedule"): [payout_id: u64, recipient: Address, amount: i128, memo: Option<String>]
+//! - ("payout", "execute"): [payout_id: u64, amount: i128, withheld: i128, memo: Option<String>]
+//! - ("payout", "approved"): [payout_id: u64, approver: Address]
+//! - ("payout", "rejected"): [payout_id: u64, approver: Address]
+//! - ("payout", "capped"): [payout_id: u64, amount: i128, remaining: i128]
+//! - ("payout", "failed"): [payout_id: u64, recipient: Address]
+//! - ("payout", "retry"): [payout_id: u64]
+//! - ("payout", "expired"): [payout_id: u64, amount: i128, recredited: bool]
+//! - ("payout", "merged"): [merge_id: u64, recipient: Address, payout_ids: Vec<u64>, amount: i128, net_amount: i128]
+//! - ("payout", "amended"): [payout_id: u64, old_amount: i128, new_amount: i128, old_execute_after: u64, new_execute_after: u64]
+//! - ("payout", "resched"): [payout_id: u64, new_execute_after: u64, amount: i128]
+//! - ("payout", "cancelled"): [payout_id: u64, amount: i128]
+//! - ("payout", "recur"): [recurring_id: u64, payout_id: u64, amount: i128, remaining: u32]
+//! - ("payout", "claim"): [publisher: Address, amount: i128]
+//! - ("recur", "capped"): [recurring_id: u64, amount: i128, remaining: i128]
+//! - ("claim", "capped"): [publisher: Address, amount: i128, remaining: i128]
+//! - ("earnings", "added"): [publisher: Address, amount: i128, new_pending: i128]
+//! - ("earnings", "threshold"): [publisher: Address, pending: i128, threshold: i128]
+//! - ("earnings", "escrow"): [publisher: Address, amount: i128, escrow_id: u64]
+//! - ("earnings", "credit"): [from_contract: Address, publisher: Address, amount: i128]
+//! - ("earnings", "batch"): [count: u32, total: i128]
+//! - ("earnings", "adjusted"): [publisher: Address, delta: i128, index: u32]
+//! - ("publisher", "register"): [publisher: Address, kyc_ref: BytesN<32>]
+//! - ("publisher", "suspend"): [publisher: Address, reason: String]
+//! - ("treasury", "sweep"): [token: Address, destination: Address, amount: i128]
+//! - ("treasury", "deposit"): [from: Address, amount: i128, total_deposited: i128]
+//! - ("stream", "withdraw"): [stream_id: u64, amount: i128, withdrawn: i128]
+//! - ("stream", "capped"): [stream_id: u64, amount: i128, remaining: i128]
+//! - ("stream", "approved"): [stream_id: u64, approver: Address]
+//! - ("stream", "rejected"): [stream_id: u64, approver: Address]
+//! - ("dest", "set"): [publisher: Address, destination: Address, active_at: u64]
+//! - ("dest", "cleared"): [publisher: Address]
+//! - ("admin", "paused"): [caller: Address]
+//! - ("admin", "unpaused"): [caller: Address]
+//! - ("admin", "upgraded"): [new_wasm_hash: BytesN<32>]
+//! - ("admin", "changed"): [old_admin: Address, new_admin: Address]
+//! - ("admin", "migrated"): [version: u32]
+//! - ("operator", "added"): [operator: Address]
+//! - ("operator", "removed"): [operator: Address]
 
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env,
+    token, Address, BytesN, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
 };
 
 #[contracttype]
 #[derive(Clone, PartialEq)]
 pub enum PayoutStatus {
+    PendingApproval,
     Scheduled,
     Processing,
     Completed,
@@ -21,6 +60,23 @@ pub enum PayoutStatus {
     Cancelled,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum CancelReason {
+    None,
+    Rejected,
+    Expired,
+    Admin,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum FailureReason {
+    None,
+    PublisherSuspended,
+    InsufficientBalance,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct ScheduledPayout {
@@ -33,32 +89,358 @@ pub struct ScheduledPayout {
     pub status: PayoutStatus,
     pub campaign_id: Option<u64>,
     pub executed_at: Option<u64>,
+    pub withheld: i128,
+    pub priority: u32,
+    pub reserved: bool,
+    pub amended_count: u32,
+    pub expires_at: Option<u64>,
+    pub cancel_reason: CancelReason,
+    pub memo: Option<String>,
+    pub failure_reason: FailureReason,
+    pub failed_at: Option<u64>,
+}
+
+/// Payout record layout from before withholding, priorities and memos
+#[contracttype]
+#[derive(Clone)]
+struct LegacyScheduledPayout {
+    pub payout_id: u64,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub scheduled_at: u64,
+    pub execute_after: u64,
+    pub status: PayoutStatus,
+    pub campaign_id: Option<u64>,
+    pub executed_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum PublisherState {
+    Active,
+    Suspended,
+}
+
+/// Onboarding record; only Active publishers can accrue earnings or be paid
+#[contracttype]
+#[derive(Clone)]
+pub struct PublisherStatus {
+    pub publisher: Address,
+    pub kyc_ref: BytesN<32>,
+    pub state: PublisherState,
+    pub registered_at: u64,
+    pub suspension_reason: Option<String>,
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub struct PublisherEarnings {
+    pub publisher: Address,
+    pub token: Address,
+    pub pending_amount: i128,
+    pub reserved_amount: i128,
+    pub total_paid: i128,
+    pub last_payout: u64,
+    pub payout_count: u32,
+    pub debt: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PublisherSummary {
     pub publisher: Address,
     pub pending_amount: i128,
+    pub reserved_amount: i128,
     pub total_paid: i128,
+    pub total_withheld: i128,
+    pub payout_count: u32,
     pub last_payout: u64,
 }
 
+/// Due-queue entry. Entries are ordered by execute_after, then higher priority
+/// first, then payout_id, so the queue order is deterministic.
+#[contracttype]
+#[derive(Clone)]
+pub struct DueEntry {
+    pub execute_after: u64,
+    pub priority: u32,
+    pub payout_id: u64,
+    pub expires_at: Option<u64>,
+}
+
+/// Contract-wide payout totals. Amounts and counts describe payouts by their
+/// current status; "scheduled" covers both Scheduled and PendingApproval.
+/// `total_reclaimed` is value from cancelled/expired payouts that went back to
+/// the contract rather than to a publisher; `total_swept` is how much of it
+/// has been moved out again. Earnings claims and stream withdrawals are not
+/// payouts but still count toward `total_executed_amount` and
+/// `total_withheld`; `total_claimed`, `claim_count` and `total_streamed` break
+/// them out.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PayoutStats {
+    pub total_scheduled_amount: i128,
+    pub total_executed_amount: i128,
+    pub total_cancelled_amount: i128,
+    pub total_failed_amount: i128,
+    pub total_withheld: i128,
+    pub pending_approval_count: u32,
+    pub scheduled_count: u32,
+    pub completed_count: u32,
+    pub failed_count: u32,
+    pub cancelled_count: u32,
+    pub total_reclaimed: i128,
+    pub total_swept: i128,
+    pub total_claimed: i128,
+    pub claim_count: u32,
+    pub total_streamed: i128,
+}
+
+/// Where a publisher's payouts are sent. A new destination only takes effect at
+/// `active_at`; until then payouts go to `previous` (or the publisher itself).
+#[contracttype]
+#[derive(Clone)]
+pub struct PayoutDestination {
+    pub destination: Address,
+    pub active_at: u64,
+    pub previous: Option<Address>,
+}
+
+/// Proof of which execution paid a payout, written alongside the transfer
+#[contracttype]
+#[derive(Clone)]
+pub struct ExecutionReceipt {
+    pub payout_id: u64,
+    pub executor: Address,
+    pub paid_to: Address,
+    pub ledger: u32,
+    pub timestamp: u64,
+    pub amount: i128,
+    pub withheld: i128,
+    pub net_amount: i128,
+    /// Set when the payout was paid as part of a merged transfer
+    pub merge_id: Option<u64>,
+}
+
+/// `total_amount` vesting linearly from `start_at` to `end_at`; the recipient
+/// can withdraw whatever has vested and not been withdrawn yet.
+#[contracttype]
+#[derive(Clone)]
+pub struct PayoutStream {
+    pub stream_id: u64,
+    pub recipient: Address,
+    pub token: Address,
+    pub total_amount: i128,
+    pub withdrawn: i128,
+    pub start_at: u64,
+    pub end_at: u64,
+    pub created_at: u64,
+}
+
+/// Payout amounts for one campaign by current status; payouts without a
+/// campaign are reported under campaign 0
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CampaignPayoutTotals {
+    pub scheduled: i128,
+    pub executed: i128,
+    pub cancelled: i128,
+    pub failed: i128,
+    pub payout_count: u32,
+}
+
+/// Funding position in one token. `surplus` is the spendable balance (excluding
+/// reclaimed funds) minus what scheduled payouts still owe; negative means underfunded.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreasuryStatus {
+    pub token: Address,
+    pub balance: i128,
+    pub liabilities: i128,
+    pub surplus: i128,
+    pub total_deposited: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingDailyCap {
+    pub cap: Option<i128>,
+    pub effective_at: u64,
+}
+
+/// Pending earnings at or above `threshold` are scheduled automatically,
+/// executable `delay` seconds later.
+#[contracttype]
+#[derive(Clone)]
+pub struct AutoPayoutConfig {
+    pub threshold: i128,
+    pub delay: u64,
+}
+
+/// Audit record of a manual earnings correction
+#[contracttype]
+#[derive(Clone)]
+pub struct EarningsAdjustment {
+    pub publisher: Address,
+    pub token: Address,
+    pub delta: i128,
+    pub reason: String,
+    pub admin: Address,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct EarningsAccrual {
+    pub publisher: Address,
+    pub token: Address,
+    pub escrow_id: u64,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// A fixed amount paid every `interval` seconds, `remaining` more times.
+/// Each installment is recorded as a completed payout, as a failed one
+/// (retryable like any other) when the contract was underfunded, or as a
+/// payout pending approval when it reaches the large-payout threshold.
+#[contracttype]
+#[derive(Clone)]
+pub struct RecurringPayout {
+    pub recurring_id: u64,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub interval: u64,
+    pub next_due: u64,
+    pub remaining: u32,
+    pub executed_count: u32,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RecurringInstallment {
+    pub recurring_id: u64,
+    pub index: u32,
+    pub payout_id: u64,
+    pub due_at: u64,
+    pub executed_at: u64,
+    pub amount: i128,
+    pub withheld: i128,
+}
+
 #[contracttype]
 pub enum DataKey {
     Admin,
     TokenAddress,
     PayoutCounter,
     MinPayoutAmount,
+    LargePayoutThreshold,
+    PayoutApprover,
+    WithholdingAccount,
     Payout(u64),
+    PublisherEarnings(Address, Address),
+    PublisherTokens(Address),
+    Withholding(Address),
+    TotalWithheld(Address),
+    PublisherPaidBucket(Address, u32),
+    EarningsTtl,
+    Paused,
+    Guardian,
+    EscrowSource,
+    AccrualCount,
+    Accrual(u64),
+    DueDays,
+    DueBucket(u64),
+    DailyCap,
+    PendingDailyCap,
+    DailyOutflow(u64),
+    AutoPayoutConfig,
+    AutoPayoutOptOut(Address),
+    AdjustmentCount(Address),
+    Adjustment(Address, u32),
+    PayoutTtl,
+    ExpireToTreasury,
+    ExecutionReceipt(u64),
+    PublisherStatus(Address),
+    PayoutStats,
+    PayoutDestination(Address),
+    ReclaimedBalance(Address),
+    ThresholdNotified(Address, Address),
+    MergeCounter,
+    MigratedVersion,
+    MinPayoutInterval,
+    IntervalOverride(u64),
+    RecurringCounter,
+    Recurring(u64),
+    RecurringInstallment(u64, u32),
+    PayoutTokens,
+    TotalDeposited(Address),
+    TotalScheduledLiability(Address),
+    StrictFunding,
+    CampaignPayouts(u64),
+    CampaignTotals(u64),
+    CreditingContract(Address),
+}
+
+/// Stream storage, kept out of DataKey, which is close to the 50-variant limit
+/// of a contract type
+#[contracttype]
+pub enum StreamKey {
+    Counter,
+    Stream(u64),
+    PendingApproval(u64),
+}
+
+/// Operator role storage, kept out of DataKey for the same reason as StreamKey
+#[contracttype]
+pub enum RoleKey {
+    Operator(Address),
+    Operators,
+}
+
+/// Storage layout from before earnings were tracked per token. Balances stored
+/// under it are read as earnings in the contract's default token.
+#[contracttype]
+enum LegacyDataKey {
     PublisherEarnings(Address),
 }
 
+#[contracttype]
+#[derive(Clone)]
+struct LegacyPublisherEarnings {
+    pub publisher: Address,
+    pub pending_amount: i128,
+    pub total_paid: i128,
+    pub last_payout: u64,
+}
+
+/// Bumped whenever a release needs `migrate` to run
+const CONTRACT_VERSION: u32 = 1;
+
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
 
+/// Average ledger close time, used to size TTL extensions from timestamps
+const LEDGER_SECONDS: u64 = 5;
+
+const BPS_DENOMINATOR: i128 = 10_000;
+const MAX_WITHHOLDING_BPS: u32 = 5_000;
+
+const SECONDS_PER_BUCKET: u64 = 86_400;
+const SECONDS_PER_DUE_BUCKET: u64 = 86_400;
+const MAX_DUE_BATCH: u32 = 50;
+const SECONDS_PER_DAY: u64 = 86_400;
+const DAILY_CAP_DELAY: u64 = 86_400;
+const MAX_BUCKET_SPAN: u32 = 366;
+const MAX_PAGE_SIZE: u32 = 50;
+const MAX_EARNINGS_BATCH: u32 = 25;
+const MAX_MEMO_LEN: u32 = 64;
+const DESTINATION_DELAY: u64 = 86_400;
+
 #[contract]
 pub struct PayoutAutomationContract;
 
@@ -76,6 +458,7 @@ impl PayoutAutomationContract {
         env.storage().instance().set(&DataKey::MinPayoutAmount, &1_000_000i128);
     }
 
+    /// Schedule a payout in the contract's default token
     pub fn schedule_payout(
         env: Env,
         admin: Address,
@@ -83,52 +466,153 @@ impl PayoutAutomationContract {
         amount: i128,
         execute_after: u64,
         campaign_id: Option<u64>,
+        priority: Option<u32>,
+        expires_at: Option<u64>,
+        memo: Option<String>,
+    ) -> u64 {
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        Self::schedule_payout_for(env, admin, recipient, token_addr, amount, execute_after, campaign_id, priority, expires_at, memo)
+    }
+
+    /// Schedule a payout in an allowlisted token
+    pub fn schedule_payout_for(
+        env: Env,
+        admin: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        execute_after: u64,
+        campaign_id: Option<u64>,
+        priority: Option<u32>,
+        expires_at: Option<u64>,
+        memo: Option<String>,
     ) -> u64 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        admin.require_auth();
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if admin != stored_admin {
-            panic!("unauthorized");
+        Self::_require_admin(&env, &admin);
+        Self::_require_not_paused(&env);
+        Self::_require_payout_token(&env, &token);
+        Self::_require_active_publisher(&env, &recipient);
+        if let Some(expiry) = expires_at {
+            if expiry <= execute_after {
+                panic!("invalid expiry");
+            }
+        }
+        if let Some(text) = &memo {
+            if text.len() > MAX_MEMO_LEN {
+                panic!("memo too long");
+            }
+        }
+        if env.storage().instance().get(&DataKey::StrictFunding).unwrap_or(false)
+            && Self::_liabilities(&env, &token) + amount > Self::_available_balance(&env, &token)
+        {
+            panic!("insufficient funds");
         }
 
-        let counter: u64 = env.storage().instance().get(&DataKey::PayoutCounter).unwrap_or(0);
-        let payout_id = counter + 1;
+        let mut payout = Self::_new_payout(&env, &recipient, &token, amount, execute_after);
+        payout.campaign_id = campaign_id;
+        payout.priority = priority.unwrap_or(0);
+        payout.expires_at = expires_at;
+        payout.memo = memo;
+        Self::_create_payout(&env, payout)
+    }
 
-        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+    /// Correct the amount and/or execution time of a payout that has not run yet.
+    /// Raising the amount to the large-payout threshold sends it back for approval.
+    pub fn update_payout(
+        env: Env,
+        admin: Address,
+        payout_id: u64,
+        new_amount: Option<i128>,
+        new_execute_after: Option<u64>,
+    ) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
 
-        let payout = ScheduledPayout {
-            payout_id,
-            recipient: recipient.clone(),
-            token: token_addr,
-            amount,
-            scheduled_at: env.ledger().timestamp(),
-            execute_after,
-            status: PayoutStatus::Scheduled,
-            campaign_id,
-            executed_at: None,
-        };
+        let mut payout = Self::_load_payout(&env, payout_id).expect("payout not found");
+        if payout.status != PayoutStatus::Scheduled {
+            panic!("payout not scheduled");
+        }
 
-        let _ttl_key = DataKey::Payout(payout_id);
-        env.storage().persistent().set(&_ttl_key, &payout);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        env.storage().instance().set(&DataKey::PayoutCounter, &payout_id);
+        let old_amount = payout.amount;
+        let old_execute_after = payout.execute_after;
+        let amount = new_amount.unwrap_or(old_amount);
+        let execute_after = new_execute_after.unwrap_or(old_execute_after);
+        Self::_amend_payout(&env, &mut payout, amount, execute_after);
 
         env.events().publish(
-            (symbol_short!("payout"), symbol_short!("schedule")),
-            (payout_id, recipient, amount),
+            (symbol_short!("payout"), symbol_short!("amended")),
+            (payout_id, old_amount, amount, old_execute_after, execute_after),
+        );
+    }
+
+    /// Move a scheduled payout to a new execution time, optionally changing its
+    /// amount. A time in the past makes the payout due immediately.
+    pub fn reschedule_payout(
+        env: Env,
+        admin: Address,
+        payout_id: u64,
+        new_execute_after: u64,
+        new_amount: Option<i128>,
+    ) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        let mut payout = Self::_load_payout(&env, payout_id).expect("payout not found");
+        if payout.status != PayoutStatus::Scheduled {
+            panic!("payout not scheduled");
+        }
+
+        let amount = new_amount.unwrap_or(payout.amount);
+        Self::_amend_payout(&env, &mut payout, amount, new_execute_after);
+
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("resched")),
+            (payout_id, new_execute_after, amount),
         );
+    }
+
+    /// Cancel a scheduled payout before it runs. Reserved auto-payout amounts go
+    /// back to pending earnings; a manual payout's value is reclaimed as far as
+    /// the contract actually held funds for it.
+    pub fn cancel_payout(env: Env, admin: Address, payout_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        let mut payout = Self::_load_payout(&env, payout_id).expect("payout not found");
+        if payout.status != PayoutStatus::Scheduled {
+            panic!("payout not scheduled");
+        }
+
+        payout.status = PayoutStatus::Cancelled;
+        payout.cancel_reason = CancelReason::Admin;
+        Self::_save_payout(&env, &payout);
+        Self::_dequeue_due(&env, &payout);
+        if payout.reserved {
+            Self::_release_reservation(&env, &payout);
+        } else {
+            Self::_reclaim(&env, &payout.token, payout.amount);
+        }
 
-        payout_id
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("cancelled")),
+            (payout_id, payout.amount),
+        );
     }
 
-    pub fn execute_payout(env: Env, payout_id: u64) {
+    /// Pay a due payout (admin or operator). A payout the daily cap blocks stays
+    /// scheduled for a later day and a ("payout","capped") event is emitted.
+    pub fn execute_payout(env: Env, executor: Address, payout_id: u64) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        let mut payout: ScheduledPayout = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Payout(payout_id))
-            .expect("payout not found");
+        Self::_require_executor(&env, &executor);
+        let payout = Self::_load_payout(&env, payout_id).expect("payout not found");
 
+        Self::_require_not_paused(&env);
+        if payout.status == PayoutStatus::PendingApproval {
+            panic!("payout awaiting approval");
+        }
+        if payout.status == PayoutStatus::Cancelled {
+            panic!("payout cancelled");
+        }
         if payout.status != PayoutStatus::Scheduled {
             panic!("payout not scheduled");
         }
@@ -136,78 +620,2683 @@ impl PayoutAutomationContract {
         if env.ledger().timestamp() < payout.execute_after {
             panic!("too early to execute");
         }
+        if Self::_is_expired(&env, &payout) {
+            panic!("payout expired");
+        }
+        // A publisher suspended after scheduling is not paid; the payout fails instead
+        if !Self::_is_active_publisher(&env, &payout.recipient) {
+            Self::_fail_payout(&env, payout, FailureReason::PublisherSuspended);
+            return;
+        }
+        if Self::_too_frequent(&env, &payout) {
+            panic!("payout too frequent");
+        }
+        if !Self::_within_daily_cap(&env, payout.amount) {
+            Self::_emit_capped(&env, symbol_short!("payout"), payout.payout_id, payout.amount);
+            return;
+        }
+        // An underfunded contract fails the payout instead of reverting, so the attempt is recorded
+        if Self::_available_balance(&env, &payout.token) < payout.amount {
+            Self::_fail_payout(&env, payout, FailureReason::InsufficientBalance);
+            return;
+        }
 
-        let token_client = token::Client::new(&env, &payout.token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &payout.recipient,
-            &payout.amount,
-        );
+        Self::_execute(&env, &executor, payout);
+    }
 
-        payout.status = PayoutStatus::Completed;
-        payout.executed_at = Some(env.ledger().timestamp());
-        let _ttl_key = DataKey::Payout(payout_id);
-        env.storage().persistent().set(&_ttl_key, &payout);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    /// Cancel a payout whose expiry has passed (callable by anyone). Reserved
+    /// auto-payout amounts go back to pending earnings, or are reclaimed when
+    /// expiry-to-treasury is enabled; a manual payout's value is reclaimed as
+    /// far as the contract actually held funds for it.
+    pub fn expire_payout(env: Env, payout_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut payout = Self::_load_payout(&env, payout_id).expect("payout not found");
+        if payout.status != PayoutStatus::Scheduled && payout.status != PayoutStatus::PendingApproval {
+            panic!("payout not scheduled");
+        }
+        if !Self::_is_expired(&env, &payout) {
+            panic!("payout not expired");
+        }
 
-        // Update publisher earnings
-        let key = DataKey::PublisherEarnings(payout.recipient.clone());
-        let mut earnings: PublisherEarnings = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or(PublisherEarnings {
-                publisher: payout.recipient.clone(),
-                pending_amount: 0,
-                total_paid: 0,
-                last_payout: 0,
-            });
+        payout.status = PayoutStatus::Cancelled;
+        payout.cancel_reason = CancelReason::Expired;
+        Self::_save_payout(&env, &payout);
+        Self::_dequeue_due(&env, &payout);
 
-        earnings.total_paid += payout.amount;
-        earnings.pending_amount = earnings.pending_amount.saturating_sub(payout.amount);
-        earnings.last_payout = env.ledger().timestamp();
-        env.storage().persistent().set(&key, &earnings);
-        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let to_treasury: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::ExpireToTreasury)
+            .unwrap_or(false);
+        let recredited = payout.reserved && !to_treasury;
+        if payout.reserved {
+            let mut earnings = Self::_load_earnings(&env, &payout.recipient, &payout.token);
+            earnings.reserved_amount -= payout.amount;
+            if recredited {
+                earnings.pending_amount += payout.amount;
+            }
+            Self::_save_earnings(&env, &earnings);
+        }
+        if !recredited {
+            Self::_reclaim(&env, &payout.token, payout.amount);
+        }
 
         env.events().publish(
-            (symbol_short!("payout"), symbol_short!("execute")),
-            (payout_id, payout.amount),
+            (symbol_short!("payout"), symbol_short!("expired")),
+            (payout_id, payout.amount, recredited),
         );
     }
 
-    pub fn add_publisher_earnings(env: Env, admin: Address, publisher: Address, amount: i128) {
+    /// Put a failed payout back in the due queue. Auto payouts take their amount
+    /// out of pending earnings again.
+    pub fn retry_payout(env: Env, admin: Address, payout_id: u64) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        admin.require_auth();
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if admin != stored_admin {
-            panic!("unauthorized");
+        Self::_require_admin(&env, &admin);
+
+        let mut payout = Self::_load_payout(&env, payout_id).expect("payout not found");
+        if payout.status != PayoutStatus::Failed {
+            panic!("payout not failed");
         }
+        Self::_require_active_publisher(&env, &payout.recipient);
 
-        let key = DataKey::PublisherEarnings(publisher.clone());
-        let mut earnings: PublisherEarnings = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or(PublisherEarnings {
-                publisher: publisher.clone(),
-                pending_amount: 0,
-                total_paid: 0,
-                last_payout: 0,
-            });
+        if payout.reserved {
+            let mut earnings = Self::_load_earnings(&env, &payout.recipient, &payout.token);
+            if payout.amount > earnings.pending_amount {
+                panic!("insufficient pending earnings");
+            }
+            earnings.pending_amount -= payout.amount;
+            earnings.reserved_amount += payout.amount;
+            Self::_save_earnings(&env, &earnings);
+        }
 
-        earnings.pending_amount += amount;
-        env.storage().persistent().set(&key, &earnings);
-        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        payout.status = PayoutStatus::Scheduled;
+        payout.failure_reason = FailureReason::None;
+        payout.failed_at = None;
+        Self::_save_payout(&env, &payout);
+        Self::_enqueue_due(&env, &payout);
+
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("retry")),
+            payout_id,
+        );
     }
 
-    pub fn get_payout(env: Env, payout_id: u64) -> Option<ScheduledPayout> {
+    /// Execute up to `n` due payouts in due-queue order (admin or operator);
+    /// returns how many were paid
+    pub fn execute_next_due(env: Env, executor: Address, n: u32) -> u32 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage().persistent().get(&DataKey::Payout(payout_id))
+        Self::_require_executor(&env, &executor);
+        Self::_require_not_paused(&env);
+
+        let due = Self::_due_payouts(&env, env.ledger().timestamp(), n.min(MAX_DUE_BATCH));
+        let mut executed = 0u32;
+        for payout_id in due.iter() {
+            let payout = Self::_load_payout(&env, payout_id).unwrap();
+            if !Self::_is_active_publisher(&env, &payout.recipient) {
+                Self::_fail_payout(&env, payout, FailureReason::PublisherSuspended);
+                continue;
+            }
+            if Self::_too_frequent(&env, &payout) {
+                continue;
+            }
+            if !Self::_within_daily_cap(&env, payout.amount) {
+                Self::_emit_capped(&env, symbol_short!("payout"), payout.payout_id, payout.amount);
+                continue;
+            }
+            if Self::_available_balance(&env, &payout.token) < payout.amount {
+                Self::_fail_payout(&env, payout, FailureReason::InsufficientBalance);
+                continue;
+            }
+            Self::_execute(&env, &executor, payout);
+            executed += 1;
+        }
+        executed
     }
 
-    pub fn get_publisher_earnings(env: Env, publisher: Address) -> Option<PublisherEarnings> {
+    /// Execute the listed payouts (admin or operator, e.g. a keeper working from
+    /// `get_due_payouts`). Ids that are unknown, not Scheduled, not yet due or
+    /// expired are skipped rather than aborting the batch. Returns how many were paid.
+    pub fn execute_due_payouts(env: Env, executor: Address, payout_ids: Vec<u64>) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_executor(&env, &executor);
+        Self::_require_not_paused(&env);
+        if payout_ids.len() > MAX_DUE_BATCH {
+            panic!("invalid batch size");
+        }
+
+        let mut executed = 0u32;
+        for payout_id in payout_ids.iter() {
+            let payout = match Self::_load_payout(&env, payout_id) {
+                Some(payout) => payout,
+                None => continue,
+            };
+            if payout.status != PayoutStatus::Scheduled
+                || env.ledger().timestamp() < payout.execute_after
+                || Self::_is_expired(&env, &payout)
+            {
+                continue;
+            }
+            if !Self::_is_active_publisher(&env, &payout.recipient) {
+                Self::_fail_payout(&env, payout, FailureReason::PublisherSuspended);
+                continue;
+            }
+            if Self::_too_frequent(&env, &payout) {
+                continue;
+            }
+            if !Self::_within_daily_cap(&env, payout.amount) {
+                Self::_emit_capped(&env, symbol_short!("payout"), payout.payout_id, payout.amount);
+                continue;
+            }
+            if Self::_available_balance(&env, &payout.token) < payout.amount {
+                Self::_fail_payout(&env, payout, FailureReason::InsufficientBalance);
+                continue;
+            }
+            Self::_execute(&env, &executor, payout);
+            executed += 1;
+        }
+        executed
+    }
+
+    /// Pay all of a recipient's due payouts in `token` with a single transfer.
+    /// Every payout is settled and receipted individually under a shared merge
+    /// id; if the transfer fails, none of them are marked Completed. Only the
+    /// first MAX_DUE_BATCH due entries are considered. Returns the merge id, or
+    /// 0 when the daily cap defers the whole set (each payout is reported capped).
+    pub fn execute_merged(env: Env, executor: Address, recipient: Address, token: Address) -> u64 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage().persistent().get(&DataKey::PublisherEarnings(publisher))
+        Self::_require_executor(&env, &executor);
+        Self::_require_not_paused(&env);
+        Self::_require_active_publisher(&env, &recipient);
+
+        let mut payouts: Vec<ScheduledPayout> = Vec::new(&env);
+        let mut payout_ids: Vec<u64> = Vec::new(&env);
+        let mut total: i128 = 0;
+        for payout_id in Self::_due_payouts(&env, env.ledger().timestamp(), MAX_DUE_BATCH).iter() {
+            let payout = Self::_load_payout(&env, payout_id).unwrap();
+            if payout.recipient == recipient && payout.token == token && !Self::_too_frequent(&env, &payout) {
+                total += payout.amount;
+                payout_ids.push_back(payout_id);
+                payouts.push_back(payout);
+            }
+        }
+        if payouts.is_empty() {
+            panic!("no due payouts");
+        }
+        if !Self::_within_daily_cap(&env, total) {
+            for payout in payouts.iter() {
+                Self::_emit_capped(&env, symbol_short!("payout"), payout.payout_id, payout.amount);
+            }
+            return 0;
+        }
+
+        let merge_id: u64 = env.storage().instance().get(&DataKey::MergeCounter).unwrap_or(0) + 1;
+        env.storage().instance().set(&DataKey::MergeCounter, &merge_id);
+
+        let payee = Self::_payee(&env, &recipient);
+        let mut withheld: i128 = 0;
+        let mut settled: Vec<ScheduledPayout> = Vec::new(&env);
+        for mut payout in payouts.iter() {
+            Self::_settle(&env, &executor, &mut payout, &payee, Some(merge_id));
+            withheld += payout.withheld;
+            settled.push_back(payout);
+        }
+
+        Self::_disburse(&env, &token, &payee, total, withheld);
+
+        for payout in settled.iter() {
+            env.events().publish(
+                (symbol_short!("payout"), symbol_short!("execute")),
+                (payout.payout_id, payout.amount, payout.withheld, payout.memo),
+            );
+        }
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("merged")),
+            (merge_id, recipient, payout_ids, total, total - withheld),
+        );
+        merge_id
+    }
+
+    /// Pay `recipient` a fixed amount every `interval_secs`, `max_occurrences`
+    /// times, starting once `start_after` is reached.
+    pub fn schedule_recurring_payout(
+        env: Env,
+        admin: Address,
+        recipient: Address,
+        amount: i128,
+        interval_secs: u64,
+        max_occurrences: u32,
+        start_after: u64,
+    ) -> u64 {
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        Self::schedule_recurring_payout_for(env, admin, recipient, token_addr, amount, interval_secs, max_occurrences, start_after)
+    }
+
+    /// Recurring payout in an allowlisted token
+    pub fn schedule_recurring_payout_for(
+        env: Env,
+        admin: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        interval_secs: u64,
+        max_occurrences: u32,
+        start_after: u64,
+    ) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        Self::_require_not_paused(&env);
+        Self::_require_payout_token(&env, &token);
+        Self::_require_active_publisher(&env, &recipient);
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        if interval_secs == 0 || max_occurrences == 0 {
+            panic!("invalid interval");
+        }
+
+        let recurring_id: u64 = env.storage().instance().get(&DataKey::RecurringCounter).unwrap_or(0) + 1;
+        let recurring = RecurringPayout {
+            recurring_id,
+            recipient,
+            token,
+            amount,
+            interval: interval_secs,
+            next_due: start_after,
+            remaining: max_occurrences,
+            executed_count: 0,
+            created_at: env.ledger().timestamp(),
+        };
+        Self::_save_recurring(&env, &recurring);
+        env.storage().instance().set(&DataKey::RecurringCounter, &recurring_id);
+        recurring_id
+    }
+
+    /// Pay the next due installment of a recurring payout (admin or operator).
+    /// Returns the id of the payout that records the installment, or 0 when the
+    /// daily cap defers it (reported as a ("recur","capped") event).
+    pub fn execute_recurring(env: Env, executor: Address, recurring_id: u64) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_executor(&env, &executor);
+        Self::_require_not_paused(&env);
+        let mut recurring: RecurringPayout = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Recurring(recurring_id))
+            .expect("recurring payout not found");
+        if recurring.remaining == 0 {
+            panic!("recurring payout finished");
+        }
+        if env.ledger().timestamp() < recurring.next_due {
+            panic!("too early to execute");
+        }
+        Self::_require_active_publisher(&env, &recurring.recipient);
+
+        let mut payout = Self::_new_payout(&env, &recurring.recipient, &recurring.token, recurring.amount, recurring.next_due);
+        // Large installments wait for the approver like any other large payout
+        if Self::_needs_approval(&env, payout.amount) {
+            let payout_id = Self::_create_payout(&env, payout);
+            Self::_advance_recurring(&env, &mut recurring);
+            return payout_id;
+        }
+
+        let counter: u64 = env.storage().instance().get(&DataKey::PayoutCounter).unwrap_or(0);
+        payout.payout_id = counter + 1;
+        if Self::_too_frequent(&env, &payout) {
+            panic!("payout too frequent");
+        }
+        if !Self::_within_daily_cap(&env, recurring.amount) {
+            Self::_emit_capped(&env, symbol_short!("recur"), recurring_id, recurring.amount);
+            return 0;
+        }
+        env.storage().instance().set(&DataKey::PayoutCounter, &payout.payout_id);
+        Self::_save_payout(&env, &payout);
+
+        // An underfunded contract records the installment as a failed payout and
+        // moves on; retry_payout pays it once funds arrive
+        if Self::_available_balance(&env, &payout.token) < payout.amount {
+            let payout_id = payout.payout_id;
+            Self::_fail_payout(&env, payout, FailureReason::InsufficientBalance);
+            Self::_advance_recurring(&env, &mut recurring);
+            return payout_id;
+        }
+
+        let payee = Self::_payee(&env, &payout.recipient);
+        Self::_settle(&env, &executor, &mut payout, &payee, None);
+
+        let installment = RecurringInstallment {
+            recurring_id,
+            index: recurring.executed_count,
+            payout_id: payout.payout_id,
+            due_at: recurring.next_due,
+            executed_at: env.ledger().timestamp(),
+            amount: payout.amount,
+            withheld: payout.withheld,
+        };
+        let _ttl_key = DataKey::RecurringInstallment(recurring_id, installment.index);
+        env.storage().persistent().set(&_ttl_key, &installment);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        recurring.next_due += recurring.interval;
+        recurring.remaining -= 1;
+        recurring.executed_count += 1;
+        Self::_save_recurring(&env, &recurring);
+
+        Self::_disburse(&env, &payout.token, &payee, payout.amount, payout.withheld);
+
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("recur")),
+            (recurring_id, payout.payout_id, payout.amount, recurring.remaining),
+        );
+        payout.payout_id
+    }
+
+    /// Let a publisher withdraw pending earnings in the default token, in full
+    /// or in part. The claim must meet the minimum payout amount and is subject
+    /// to withholding, the payout interval and the daily cap. Returns the amount
+    /// claimed, or 0 when the daily cap defers the claim (reported as a
+    /// ("claim","capped") event).
+    pub fn claim_earnings(env: Env, publisher: Address, amount: Option<i128>) -> i128 {
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        Self::claim_earnings_for(env, publisher, token_addr, amount)
+    }
+
+    /// Claim pending earnings in an allowlisted token
+    pub fn claim_earnings_for(env: Env, publisher: Address, token_addr: Address, amount: Option<i128>) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        publisher.require_auth();
+        Self::_require_not_paused(&env);
+        Self::_require_payout_token(&env, &token_addr);
+        Self::_require_active_publisher(&env, &publisher);
+
+        let mut earnings = Self::_get_earnings(&env, &publisher, &token_addr).expect("earnings not found");
+        let amount = amount.unwrap_or(earnings.pending_amount);
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        let min_amount: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MinPayoutAmount)
+            .unwrap_or(0);
+        if amount < min_amount {
+            panic!("amount below minimum");
+        }
+        if amount > earnings.pending_amount {
+            panic!("insufficient pending earnings");
+        }
+        if env.ledger().timestamp() < Self::_next_payout_allowed_at(&env, &publisher) {
+            panic!("payout too frequent");
+        }
+        if !Self::_within_daily_cap(&env, amount) {
+            let remaining = Self::_remaining_daily_capacity(&env);
+            env.events().publish(
+                (symbol_short!("claim"), symbol_short!("capped")),
+                (publisher, amount, remaining),
+            );
+            return 0;
+        }
+
+        Self::_promote_daily_cap(&env);
+        Self::_record_outflow(&env, amount);
+        let withheld = Self::_apply_withholding(&env, &publisher, amount);
+        Self::_update_stats(&env, |stats| {
+            stats.total_executed_amount += amount;
+            stats.total_withheld += withheld;
+            stats.total_claimed += amount;
+            stats.claim_count += 1;
+        });
+        earnings.pending_amount -= amount;
+        earnings.total_paid += amount;
+        earnings.last_payout = env.ledger().timestamp();
+        earnings.payout_count += 1;
+        Self::_save_earnings(&env, &earnings);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ThresholdNotified(publisher.clone(), token_addr.clone()));
+        Self::_record_paid_bucket(&env, &publisher, amount);
+
+        let payee = Self::_payee(&env, &publisher);
+        Self::_disburse(&env, &token_addr, &payee, amount, withheld);
+
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("claim")),
+            (publisher, amount),
+        );
+        amount
+    }
+
+    /// Stream `total_amount` of the default token to `recipient`, vesting
+    /// linearly between `start_at` and `end_at`. A total at or above the
+    /// large-payout threshold cannot be withdrawn until the approver approves it.
+    pub fn create_stream(
+        env: Env,
+        admin: Address,
+        recipient: Address,
+        total_amount: i128,
+        start_at: u64,
+        end_at: u64,
+    ) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        Self::_require_not_paused(&env);
+        Self::_require_active_publisher(&env, &recipient);
+        if total_amount <= 0 {
+            panic!("invalid amount");
+        }
+        if end_at <= start_at {
+            panic!("invalid range");
+        }
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        if env.storage().instance().get(&DataKey::StrictFunding).unwrap_or(false)
+            && Self::_liabilities(&env, &token_addr) + total_amount > Self::_available_balance(&env, &token_addr)
+        {
+            panic!("insufficient funds");
+        }
+
+        let stream_id: u64 = env.storage().instance().get(&StreamKey::Counter).unwrap_or(0) + 1;
+        let stream = PayoutStream {
+            stream_id,
+            recipient,
+            token: token_addr,
+            total_amount,
+            withdrawn: 0,
+            start_at,
+            end_at,
+            created_at: env.ledger().timestamp(),
+        };
+        Self::_save_stream(&env, &stream);
+        if Self::_needs_approval(&env, total_amount) {
+            let key = StreamKey::PendingApproval(stream_id);
+            env.storage().persistent().set(&key, &true);
+            Self::_extend_until(&env, &key, stream.end_at);
+        }
+        Self::_add_liability(&env, &stream.token, total_amount);
+        env.storage().instance().set(&StreamKey::Counter, &stream_id);
+        stream_id
+    }
+
+    /// Approve a large stream so its recipient can withdraw
+    pub fn approve_stream(env: Env, approver: Address, stream_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_approver(&env, &approver);
+        Self::_require_pending_stream(&env, stream_id);
+        env.storage().persistent().remove(&StreamKey::PendingApproval(stream_id));
+
+        env.events().publish(
+            (symbol_short!("stream"), symbol_short!("approved")),
+            (stream_id, approver),
+        );
+    }
+
+    /// Reject a large stream, dropping it before anything is paid
+    pub fn reject_stream(env: Env, approver: Address, stream_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_approver(&env, &approver);
+        let stream = Self::_require_pending_stream(&env, stream_id);
+        env.storage().persistent().remove(&StreamKey::PendingApproval(stream_id));
+        env.storage().persistent().remove(&StreamKey::Stream(stream_id));
+        Self::_add_liability(&env, &stream.token, -stream.total_amount);
+        Self::_reclaim(&env, &stream.token, stream.total_amount);
+
+        env.events().publish(
+            (symbol_short!("stream"), symbol_short!("rejected")),
+            (stream_id, approver),
+        );
+    }
+
+    /// Pay out everything vested on a stream since the last withdrawal (callable
+    /// by anyone). Returns the amount paid, which is zero before the start or
+    /// when the daily cap defers the withdrawal.
+    pub fn withdraw_stream(env: Env, stream_id: u64) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_not_paused(&env);
+        let mut stream: PayoutStream = env
+            .storage()
+            .persistent()
+            .get(&StreamKey::Stream(stream_id))
+            .expect("stream not found");
+        Self::_require_active_publisher(&env, &stream.recipient);
+        if env.storage().persistent().has(&StreamKey::PendingApproval(stream_id)) {
+            panic!("stream awaiting approval");
+        }
+
+        let amount = Self::_vested(&env, &stream) - stream.withdrawn;
+        if amount == 0 {
+            return 0;
+        }
+        if !Self::_within_daily_cap(&env, amount) {
+            Self::_emit_capped(&env, symbol_short!("stream"), stream_id, amount);
+            return 0;
+        }
+
+        Self::_promote_daily_cap(&env);
+        Self::_record_outflow(&env, amount);
+        let withheld = Self::_apply_withholding(&env, &stream.recipient, amount);
+        Self::_update_stats(&env, |stats| {
+            stats.total_executed_amount += amount;
+            stats.total_withheld += withheld;
+            stats.total_streamed += amount;
+        });
+        stream.withdrawn += amount;
+        Self::_save_stream(&env, &stream);
+        Self::_add_liability(&env, &stream.token, -amount);
+
+        let mut earnings = Self::_load_earnings(&env, &stream.recipient, &stream.token);
+        earnings.total_paid += amount;
+        earnings.last_payout = env.ledger().timestamp();
+        earnings.payout_count += 1;
+        Self::_save_earnings(&env, &earnings);
+        Self::_record_paid_bucket(&env, &stream.recipient, amount);
+
+        let payee = Self::_payee(&env, &stream.recipient);
+        Self::_disburse(&env, &stream.token, &payee, amount, withheld);
+
+        env.events().publish(
+            (symbol_short!("stream"), symbol_short!("withdraw")),
+            (stream_id, amount, stream.withdrawn),
+        );
+        amount
+    }
+
+    /// Credit earnings in the contract's default token
+    pub fn add_publisher_earnings(env: Env, admin: Address, publisher: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        Self::_require_active_publisher(&env, &publisher);
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let mut earnings = Self::_load_earnings(&env, &publisher, &token_addr);
+        Self::_credit(&env, &mut earnings, amount);
+        Self::_save_earnings(&env, &earnings);
+    }
+
+    /// Credit default-token earnings to many publishers at once. Any invalid entry
+    /// fails the whole batch. Returns the number of entries applied.
+    pub fn add_earnings_batch(env: Env, admin: Address, entries: Vec<(Address, i128)>) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        if entries.is_empty() || entries.len() > MAX_EARNINGS_BATCH {
+            panic!("invalid batch size");
+        }
+        let mut total: i128 = 0;
+        for (publisher, amount) in entries.iter() {
+            Self::_require_active_publisher(&env, &publisher);
+            if amount <= 0 {
+                panic!("invalid amount");
+            }
+            total = total.checked_add(amount).expect("overflow");
+        }
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        for (publisher, amount) in entries.iter() {
+            let mut earnings = Self::_load_earnings(&env, &publisher, &token_addr);
+            Self::_credit(&env, &mut earnings, amount);
+            Self::_save_earnings(&env, &earnings);
+        }
+
+        env.events().publish(
+            (symbol_short!("earnings"), symbol_short!("batch")),
+            (entries.len(), total),
+        );
+        entries.len()
+    }
+
+    /// Credit earnings in a specific token
+    pub fn add_publisher_earnings_for(env: Env, admin: Address, publisher: Address, token: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        Self::_require_active_publisher(&env, &publisher);
+
+        let mut earnings = Self::_load_earnings(&env, &publisher, &token);
+        Self::_credit(&env, &mut earnings, amount);
+        Self::_save_earnings(&env, &earnings);
+    }
+
+    /// Correct a publisher's pending earnings. Positive deltas require an active
+    /// publisher; negative deltas claw back pending earnings and whatever cannot
+    /// be recovered is recorded as debt.
+    pub fn adjust_earnings(
+        env: Env,
+        admin: Address,
+        publisher: Address,
+        token: Address,
+        delta: i128,
+        reason: String,
+    ) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if delta == 0 {
+            panic!("invalid amount");
+        }
+
+        let mut earnings = Self::_load_earnings(&env, &publisher, &token);
+        if delta > 0 {
+            Self::_require_active_publisher(&env, &publisher);
+            Self::_credit(&env, &mut earnings, delta);
+        } else {
+            let clawback = -delta;
+            let recovered = clawback.min(earnings.pending_amount);
+            earnings.pending_amount -= recovered;
+            earnings.debt += clawback - recovered;
+        }
+        Self::_save_earnings(&env, &earnings);
+
+        let count_key = DataKey::AdjustmentCount(publisher.clone());
+        let index: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        let adjustment = EarningsAdjustment {
+            publisher: publisher.clone(),
+            token,
+            delta,
+            reason,
+            admin,
+            timestamp: env.ledger().timestamp(),
+        };
+        let _ttl_key = DataKey::Adjustment(publisher.clone(), index);
+        env.storage().persistent().set(&_ttl_key, &adjustment);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage().persistent().set(&count_key, &(index + 1));
+        env.storage().persistent().extend_ttl(&count_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("earnings"), symbol_short!("adjusted")),
+            (publisher, delta, index),
+        );
+    }
+
+    /// Configure automatic scheduling of pending earnings (None disables)
+    pub fn set_auto_payout_config(env: Env, admin: Address, config: Option<AutoPayoutConfig>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        match config {
+            Some(config) => {
+                if config.threshold <= 0 {
+                    panic!("invalid threshold");
+                }
+                env.storage().instance().set(&DataKey::AutoPayoutConfig, &config);
+            }
+            None => env.storage().instance().remove(&DataKey::AutoPayoutConfig),
+        }
+    }
+
+    /// Publisher opt-in/opt-out of automatic payouts (enabled by default)
+    pub fn set_auto_payout(env: Env, publisher: Address, enabled: bool) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        publisher.require_auth();
+
+        let key = DataKey::AutoPayoutOptOut(publisher);
+        if enabled {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+    }
+
+    /// Record a publisher as onboarded (or reinstate a suspended one)
+    pub fn register_publisher(env: Env, admin: Address, publisher: Address, kyc_ref: BytesN<32>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        let status = PublisherStatus {
+            publisher: publisher.clone(),
+            kyc_ref: kyc_ref.clone(),
+            state: PublisherState::Active,
+            registered_at: env.ledger().timestamp(),
+            suspension_reason: None,
+        };
+        let _ttl_key = DataKey::PublisherStatus(publisher.clone());
+        env.storage().persistent().set(&_ttl_key, &status);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("publisher"), symbol_short!("register")),
+            (publisher, kyc_ref),
+        );
+    }
+
+    /// Block a publisher from accruing earnings and from being paid
+    pub fn suspend_publisher(env: Env, admin: Address, publisher: Address, reason: String) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        let _ttl_key = DataKey::PublisherStatus(publisher.clone());
+        let mut status: PublisherStatus = env
+            .storage()
+            .persistent()
+            .get(&_ttl_key)
+            .expect("publisher not registered");
+        status.state = PublisherState::Suspended;
+        status.suspension_reason = Some(reason.clone());
+        env.storage().persistent().set(&_ttl_key, &status);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("publisher"), symbol_short!("suspend")),
+            (publisher, reason),
+        );
+    }
+
+    /// Fund the contract with the default token
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        from.require_auth();
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let key = DataKey::TotalDeposited(token_addr.clone());
+        let total: i128 = env.storage().instance().get(&key).unwrap_or(0) + amount;
+        env.storage().instance().set(&key, &total);
+
+        token::Client::new(&env, &token_addr).transfer(&from, &env.current_contract_address(), &amount);
+
+        env.events().publish(
+            (symbol_short!("treasury"), symbol_short!("deposit")),
+            (from, amount, total),
+        );
+    }
+
+    /// When enabled, payouts cannot be scheduled beyond what the contract can cover
+    pub fn set_strict_funding(env: Env, admin: Address, enabled: bool) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::StrictFunding, &enabled);
+    }
+
+    /// Move reclaimed funds (from cancelled/expired payouts) to a treasury address
+    pub fn sweep_reclaimed(env: Env, admin: Address, token: Address, destination: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        let key = DataKey::ReclaimedBalance(token.clone());
+        let reclaimed: i128 = env.storage().instance().get(&key).unwrap_or(0);
+        if amount <= 0 || amount > reclaimed {
+            panic!("invalid amount");
+        }
+        env.storage().instance().set(&key, &(reclaimed - amount));
+
+        Self::_update_stats(&env, |stats| stats.total_swept += amount);
+
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &destination, &amount);
+
+        env.events().publish(
+            (symbol_short!("treasury"), symbol_short!("sweep")),
+            (token, destination, amount),
+        );
+    }
+
+    /// Send this publisher's payouts to another address, effective after 24h.
+    /// Earnings and payout records stay keyed on the publisher.
+    pub fn set_payout_destination(env: Env, publisher: Address, destination: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        publisher.require_auth();
+
+        let key = DataKey::PayoutDestination(publisher.clone());
+        let previous = env
+            .storage()
+            .persistent()
+            .get::<DataKey, PayoutDestination>(&key)
+            .and_then(|current| Self::_active_destination(&env, &current));
+        let record = PayoutDestination {
+            destination: destination.clone(),
+            active_at: env.ledger().timestamp() + DESTINATION_DELAY,
+            previous,
+        };
+        env.storage().persistent().set(&key, &record);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("dest"), symbol_short!("set")),
+            (publisher, destination, record.active_at),
+        );
+    }
+
+    /// Pay the publisher directly again (takes effect immediately)
+    pub fn clear_payout_destination(env: Env, publisher: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        publisher.require_auth();
+
+        env.storage().persistent().remove(&DataKey::PayoutDestination(publisher.clone()));
+        env.events().publish((symbol_short!("dest"), symbol_short!("cleared")), publisher);
+    }
+
+    /// Propose a new daily outflow cap (None removes it); takes effect after 24h
+    pub fn set_daily_cap(env: Env, admin: Address, cap: Option<i128>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        if let Some(value) = cap {
+            if value <= 0 {
+                panic!("invalid cap");
+            }
+        }
+        Self::_promote_daily_cap(&env);
+        let pending = PendingDailyCap {
+            cap,
+            effective_at: env.ledger().timestamp() + DAILY_CAP_DELAY,
+        };
+        env.storage().instance().set(&DataKey::PendingDailyCap, &pending);
+    }
+
+    /// Register the escrow vault allowed to credit publisher earnings
+    pub fn set_escrow_source(env: Env, admin: Address, source: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::EscrowSource, &source);
+    }
+
+    /// Credit pending earnings from an escrow release (registered escrow vault only).
+    /// The vault transfers the released funds to this contract before calling.
+    pub fn credit_from_escrow(
+        env: Env,
+        source: Address,
+        publisher: Address,
+        token: Address,
+        amount: i128,
+        escrow_id: u64,
+    ) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        source.require_auth();
+        let stored_source: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::EscrowSource)
+            .expect("escrow source not set");
+        if source != stored_source {
+            panic!("unauthorized");
+        }
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        Self::_require_active_publisher(&env, &publisher);
+
+        let mut earnings = Self::_load_earnings(&env, &publisher, &token);
+        Self::_credit(&env, &mut earnings, amount);
+        Self::_save_earnings(&env, &earnings);
+
+        // Accrual log for reconciliation against the vault
+        let index: u64 = env.storage().instance().get(&DataKey::AccrualCount).unwrap_or(0);
+        let accrual = EarningsAccrual {
+            publisher: publisher.clone(),
+            token,
+            escrow_id,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        };
+        let _ttl_key = DataKey::Accrual(index);
+        env.storage().persistent().set(&_ttl_key, &accrual);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage().instance().set(&DataKey::AccrualCount, &(index + 1));
+
+        env.events().publish(
+            (symbol_short!("earnings"), symbol_short!("escrow")),
+            (publisher, amount, escrow_id),
+        );
+    }
+
+    /// Allow a contract (such as the auction engine) to credit publisher earnings
+    /// through `credit_earnings`
+    pub fn add_crediting_contract(env: Env, admin: Address, addr: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::CreditingContract(addr), &true);
+    }
+
+    pub fn remove_crediting_contract(env: Env, admin: Address, addr: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().remove(&DataKey::CreditingContract(addr));
+    }
+
+    /// Credit default-token pending earnings on behalf of a registered crediting
+    /// contract, which transfers the funds to this contract before calling.
+    pub fn credit_earnings(env: Env, from_contract: Address, publisher: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        from_contract.require_auth();
+        if !env.storage().instance().has(&DataKey::CreditingContract(from_contract.clone())) {
+            panic!("unauthorized");
+        }
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        Self::_require_active_publisher(&env, &publisher);
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let mut earnings = Self::_load_earnings(&env, &publisher, &token_addr);
+        Self::_credit(&env, &mut earnings, amount);
+        Self::_save_earnings(&env, &earnings);
+
+        env.events().publish(
+            (symbol_short!("earnings"), symbol_short!("credit")),
+            (from_contract, publisher, amount),
+        );
+    }
+
+    /// Freeze or resume scheduling and outflows; cancelling payouts stays open.
+    /// The guardian may pause but only the admin may unpause.
+    pub fn set_paused(env: Env, caller: Address, paused: bool) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        caller.require_auth();
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        let guardian: Option<Address> = env.storage().instance().get(&DataKey::Guardian);
+        let is_guardian = guardian == Some(caller.clone());
+        if caller != admin && !(paused && is_guardian) {
+            panic!("unauthorized");
+        }
+
+        env.storage().instance().set(&DataKey::Paused, &paused);
+
+        let action = if paused { symbol_short!("paused") } else { symbol_short!("unpaused") };
+        env.events().publish((symbol_short!("admin"), action), caller);
+    }
+
+    /// Same as `set_paused(caller, true)`
+    pub fn pause(env: Env, caller: Address) {
+        Self::set_paused(env, caller, true)
+    }
+
+    /// Same as `set_paused(admin, false)`
+    pub fn unpause(env: Env, admin: Address) {
+        Self::set_paused(env, admin, false)
+    }
+
+    /// Replace the contract code. Needs the admin and, when one is configured,
+    /// the large-payout approver as a second signer.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if let Some(approver) = env.storage().instance().get::<DataKey, Address>(&DataKey::PayoutApprover) {
+            approver.require_auth();
+        }
+
+        env.events().publish(
+            (symbol_short!("admin"), symbol_short!("upgraded")),
+            new_wasm_hash.clone(),
+        );
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    /// Run storage migrations for the current code version, at most once per version
+    pub fn migrate(env: Env, admin: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        let migrated: u32 = env.storage().instance().get(&DataKey::MigratedVersion).unwrap_or(0);
+        if migrated >= CONTRACT_VERSION {
+            panic!("already migrated");
+        }
+
+        // v1: pre-multi-token earnings records are still read through the
+        // legacy fallback and rewritten per token on their next update, so
+        // there is nothing to rewrite eagerly here.
+
+        env.storage().instance().set(&DataKey::MigratedVersion, &CONTRACT_VERSION);
+        env.events().publish(
+            (symbol_short!("admin"), symbol_short!("migrated")),
+            CONTRACT_VERSION,
+        );
+    }
+
+    pub fn version(env: Env) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        CONTRACT_VERSION
+    }
+
+    /// Hand the admin role to another address, such as the governance DAO
+    pub fn set_admin(env: Env, admin: Address, new_admin: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.events().publish(
+            (symbol_short!("admin"), symbol_short!("changed")),
+            (admin, new_admin),
+        );
+    }
+
+    /// Smallest amount a payout or claim may move
+    pub fn set_min_payout(env: Env, admin: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        env.storage().instance().set(&DataKey::MinPayoutAmount, &amount);
+    }
+
+    /// Grant the operator role: operators can execute due payouts but not
+    /// schedule, cancel or credit them
+    pub fn add_operator(env: Env, admin: Address, operator: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if Self::_is_operator(&env, &operator) {
+            return;
+        }
+
+        let _ttl_key = RoleKey::Operator(operator.clone());
+        env.storage().persistent().set(&_ttl_key, &true);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let mut operators: Vec<Address> = env.storage().instance().get(&RoleKey::Operators).unwrap_or(Vec::new(&env));
+        operators.push_back(operator.clone());
+        env.storage().instance().set(&RoleKey::Operators, &operators);
+        env.events().publish((symbol_short!("operator"), symbol_short!("added")), operator);
+    }
+
+    pub fn remove_operator(env: Env, admin: Address, operator: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if !Self::_is_operator(&env, &operator) {
+            panic!("operator not found");
+        }
+
+        env.storage().persistent().remove(&RoleKey::Operator(operator.clone()));
+        let mut operators: Vec<Address> = env.storage().instance().get(&RoleKey::Operators).unwrap_or(Vec::new(&env));
+        if let Some(index) = operators.first_index_of(&operator) {
+            operators.remove(index);
+        }
+        env.storage().instance().set(&RoleKey::Operators, &operators);
+        env.events().publish((symbol_short!("operator"), symbol_short!("removed")), operator);
+    }
+
+    /// Set the guardian address that can pause outflows
+    pub fn set_guardian(env: Env, admin: Address, guardian: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::Guardian, &guardian);
+    }
+
+    /// Set the amount at or above which payouts need approver sign-off (None disables)
+    pub fn set_large_payout_threshold(env: Env, admin: Address, threshold: Option<i128>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        match threshold {
+            Some(value) => {
+                if value <= 0 {
+                    panic!("invalid threshold");
+                }
+                env.storage().instance().set(&DataKey::LargePayoutThreshold, &value);
+            }
+            None => env.storage().instance().remove(&DataKey::LargePayoutThreshold),
+        }
+    }
+
+    /// Set the second signer for large payouts; must not be the admin
+    pub fn set_payout_approver(env: Env, admin: Address, approver: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        if approver == admin {
+            panic!("approver must differ from admin");
+        }
+        env.storage().instance().set(&DataKey::PayoutApprover, &approver);
+    }
+
+    /// Approve a large payout so it becomes executable
+    pub fn approve_large_payout(env: Env, approver: Address, payout_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut payout = Self::_load_pending_approval(&env, &approver, payout_id);
+
+        payout.status = PayoutStatus::Scheduled;
+        Self::_save_payout(&env, &payout);
+        Self::_enqueue_due(&env, &payout);
+
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("approved")),
+            (payout_id, approver),
+        );
+    }
+
+    /// Reject a large payout, cancelling it
+    pub fn reject_large_payout(env: Env, approver: Address, payout_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut payout = Self::_load_pending_approval(&env, &approver, payout_id);
+
+        payout.status = PayoutStatus::Cancelled;
+        payout.cancel_reason = CancelReason::Rejected;
+        Self::_save_payout(&env, &payout);
+        if payout.reserved {
+            Self::_release_reservation(&env, &payout);
+        } else {
+            Self::_reclaim(&env, &payout.token, payout.amount);
+        }
+
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("rejected")),
+            (payout_id, approver),
+        );
+    }
+
+    /// Allow payouts in `token`. The default token is always allowed.
+    pub fn add_payout_token(env: Env, admin: Address, token: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        let mut tokens: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::PayoutTokens)
+            .unwrap_or(Vec::new(&env));
+        let default_token: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        if token != default_token && !tokens.contains(&token) {
+            tokens.push_back(token);
+            env.storage().instance().set(&DataKey::PayoutTokens, &tokens);
+        }
+    }
+
+    /// Stop new payouts in `token`. Payouts already scheduled in it still execute.
+    pub fn remove_payout_token(env: Env, admin: Address, token: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        let mut tokens: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::PayoutTokens)
+            .unwrap_or(Vec::new(&env));
+        if let Some(i) = tokens.first_index_of(&token) {
+            tokens.remove(i);
+            env.storage().instance().set(&DataKey::PayoutTokens, &tokens);
+        }
+    }
+
+    /// Minimum time between payouts to the same publisher (None disables)
+    pub fn set_min_payout_interval(env: Env, admin: Address, seconds: Option<u64>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        match seconds {
+            Some(value) => {
+                if value == 0 {
+                    panic!("invalid interval");
+                }
+                env.storage().instance().set(&DataKey::MinPayoutInterval, &value);
+            }
+            None => env.storage().instance().remove(&DataKey::MinPayoutInterval),
+        }
+    }
+
+    /// Let one payout execute inside the minimum payout interval (emergencies)
+    pub fn override_payout_interval(env: Env, admin: Address, payout_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        let payout = Self::_load_payout(&env, payout_id).expect("payout not found");
+        if payout.status != PayoutStatus::Scheduled && payout.status != PayoutStatus::PendingApproval {
+            panic!("payout not scheduled");
+        }
+        let _ttl_key = DataKey::IntervalOverride(payout_id);
+        env.storage().persistent().set(&_ttl_key, &true);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Default lifetime of a payout, in seconds after its execute_after (None disables)
+    pub fn set_payout_ttl(env: Env, admin: Address, seconds: Option<u64>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        match seconds {
+            Some(value) => {
+                if value == 0 {
+                    panic!("invalid ttl");
+                }
+                env.storage().instance().set(&DataKey::PayoutTtl, &value);
+            }
+            None => env.storage().instance().remove(&DataKey::PayoutTtl),
+        }
+    }
+
+    /// Whether expired auto-payout amounts stay in the contract instead of
+    /// returning to the publisher's pending earnings
+    pub fn set_expire_to_treasury(env: Env, admin: Address, enabled: bool) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::ExpireToTreasury, &enabled);
+    }
+
+    /// Set how many ledgers publisher earnings records are kept alive on each touch
+    pub fn set_earnings_ttl(env: Env, admin: Address, ledgers: u32) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        if ledgers < PERSISTENT_LIFETIME_THRESHOLD || ledgers > env.storage().max_ttl() {
+            panic!("invalid ttl");
+        }
+        env.storage().instance().set(&DataKey::EarningsTtl, &ledgers);
+    }
+
+    /// Re-extend a payout record so it lives until after it becomes executable
+    pub fn bump_payout(env: Env, payout_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let payout = Self::_load_payout(&env, payout_id).expect("payout not found");
+        Self::_bump_payout(&env, &payout);
+
+        if payout.status == PayoutStatus::Scheduled {
+            let bucket_key = DataKey::DueBucket(payout.execute_after / SECONDS_PER_DUE_BUCKET);
+            if env.storage().persistent().has(&bucket_key) {
+                Self::_extend_until(&env, &bucket_key, payout.execute_after);
+                Self::_extend_until(&env, &DataKey::DueDays, payout.execute_after);
+            }
+        }
+    }
+
+    /// Re-extend a publisher's earnings records (every token) by the configured earnings TTL
+    pub fn bump_earnings(env: Env, publisher: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let tokens_key = DataKey::PublisherTokens(publisher.clone());
+        let tokens: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&tokens_key)
+            .expect("earnings not found");
+        let ttl = Self::_earnings_ttl(&env);
+        env.storage().persistent().extend_ttl(&tokens_key, ttl, ttl);
+        for token in tokens.iter() {
+            let key = DataKey::PublisherEarnings(publisher.clone(), token);
+            if env.storage().persistent().has(&key) {
+                env.storage().persistent().extend_ttl(&key, ttl, ttl);
+            }
+        }
+
+        let key = DataKey::TotalWithheld(publisher.clone());
+        if env.storage().persistent().has(&key) {
+            env.storage().persistent().extend_ttl(&key, ttl, ttl);
+        }
+        let key = DataKey::PublisherStatus(publisher);
+        if env.storage().persistent().has(&key) {
+            env.storage().persistent().extend_ttl(&key, ttl, ttl);
+        }
+    }
+
+    /// Set the percentage (in bps) withheld from every payment to a publisher
+    pub fn set_withholding(env: Env, admin: Address, publisher: Address, bps: u32) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+
+        if bps > MAX_WITHHOLDING_BPS {
+            panic!("withholding too high");
+        }
+
+        let _ttl_key = DataKey::Withholding(publisher);
+        env.storage().persistent().set(&_ttl_key, &bps);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Set the account that receives withheld amounts
+    pub fn set_withholding_account(env: Env, admin: Address, account: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::WithholdingAccount, &account);
+    }
+
+    /// Executable payout ids at `now`, in due-queue order (at most `limit`)
+    pub fn get_due_payouts(env: Env, now: u64, limit: u32) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_due_payouts(&env, now, limit.min(MAX_DUE_BATCH))
+    }
+
+    pub fn get_publisher_status(env: Env, publisher: Address) -> Option<PublisherStatus> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::PublisherStatus(publisher))
+    }
+
+    /// Whether `publisher` is registered and not suspended, i.e. whether
+    /// earnings can be credited to them right now
+    pub fn is_active_publisher(env: Env, publisher: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_is_active_publisher(&env, &publisher)
+    }
+
+    pub fn get_execution_receipt(env: Env, payout_id: u64) -> Option<ExecutionReceipt> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::ExecutionReceipt(payout_id))
+    }
+
+    /// Reclaimed funds in `token` not yet swept
+    pub fn get_reclaimed_balance(env: Env, token: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .get(&DataKey::ReclaimedBalance(token))
+            .unwrap_or(0)
+    }
+
+    pub fn get_payout_destination(env: Env, publisher: Address) -> Option<PayoutDestination> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::PayoutDestination(publisher))
+    }
+
+    /// Address a payout to this publisher would be sent to right now
+    pub fn get_effective_payee(env: Env, publisher: Address) -> Address {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_payee(&env, &publisher)
+    }
+
+    pub fn get_payout_stats(env: Env) -> PayoutStats {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::PayoutStats).unwrap_or_default()
+    }
+
+    pub fn get_recurring_payout(env: Env, recurring_id: u64) -> Option<RecurringPayout> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Recurring(recurring_id))
+    }
+
+    /// Installments paid so far, oldest first
+    pub fn get_recurring_history(env: Env, recurring_id: u64, offset: u32, limit: u32) -> Vec<RecurringInstallment> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let count = env
+            .storage()
+            .persistent()
+            .get::<DataKey, RecurringPayout>(&DataKey::Recurring(recurring_id))
+            .map_or(0, |recurring| recurring.executed_count);
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+        let mut result = Vec::new(&env);
+        for index in offset..end {
+            if let Some(installment) = env
+                .storage()
+                .persistent()
+                .get(&DataKey::RecurringInstallment(recurring_id, index))
+            {
+                result.push_back(installment);
+            }
+        }
+        result
+    }
+
+    /// Tokens payouts can be scheduled in, default token first
+    pub fn list_payout_tokens(env: Env) -> Vec<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut tokens = Vec::new(&env);
+        tokens.push_back(env.storage().instance().get::<DataKey, Address>(&DataKey::TokenAddress).unwrap());
+        tokens.append(
+            &env.storage()
+                .instance()
+                .get(&DataKey::PayoutTokens)
+                .unwrap_or(Vec::new(&env)),
+        );
+        tokens
+    }
+
+    /// Contract balance in the default token, including reclaimed funds
+    pub fn get_contract_balance(env: Env) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        token::Client::new(&env, &token_addr).balance(&env.current_contract_address())
+    }
+
+    pub fn get_payout(env: Env, payout_id: u64) -> Option<ScheduledPayout> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_load_payout(&env, payout_id)
+    }
+
+    /// Earnings in the contract's default token
+    pub fn get_publisher_earnings(env: Env, publisher: Address) -> Option<PublisherEarnings> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        Self::_get_earnings(&env, &publisher, &token_addr)
+    }
+
+    pub fn get_publisher_earnings_for(env: Env, publisher: Address, token: Address) -> Option<PublisherEarnings> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_get_earnings(&env, &publisher, &token)
+    }
+
+    /// Tokens a publisher has ever been credited in, in first-credit order
+    pub fn get_publisher_tokens(env: Env, publisher: Address) -> Vec<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut tokens: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PublisherTokens(publisher.clone()))
+            .unwrap_or(Vec::new(&env));
+        if env
+            .storage()
+            .persistent()
+            .has(&LegacyDataKey::PublisherEarnings(publisher))
+        {
+            let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+            if !tokens.contains(&token_addr) {
+                tokens.push_front(token_addr);
+            }
+        }
+        tokens
+    }
+
+    pub fn get_adjustment(env: Env, publisher: Address, index: u32) -> Option<EarningsAdjustment> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Adjustment(publisher, index))
+    }
+
+    pub fn get_adjustment_count(env: Env, publisher: Address) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::AdjustmentCount(publisher))
+            .unwrap_or(0)
+    }
+
+    /// Adjustment history for a publisher, oldest first (at most 50 per page)
+    pub fn get_adjustments(env: Env, publisher: Address, start: u32, limit: u32) -> Vec<EarningsAdjustment> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::AdjustmentCount(publisher.clone()))
+            .unwrap_or(0);
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+        let mut result = Vec::new(&env);
+        for index in start..end {
+            if let Some(adjustment) = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Adjustment(publisher.clone(), index))
+            {
+                result.push_back(adjustment);
+            }
+        }
+        result
+    }
+
+    /// Clawback that could not be recovered from pending earnings yet
+    pub fn get_earnings_debt(env: Env, publisher: Address, token: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_load_earnings(&env, &publisher, &token).debt
+    }
+
+    pub fn get_escrow_source(env: Env) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::EscrowSource)
+    }
+
+    pub fn is_crediting_contract(env: Env, addr: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().has(&DataKey::CreditingContract(addr))
+    }
+
+    pub fn get_accrual(env: Env, index: u64) -> Option<EarningsAccrual> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Accrual(index))
+    }
+
+    pub fn get_accrual_count(env: Env) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::AccrualCount).unwrap_or(0)
+    }
+
+    /// Cap currently in force (None when uncapped)
+    pub fn get_daily_cap(env: Env) -> Option<i128> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_daily_cap(&env)
+    }
+
+    pub fn get_auto_payout_config(env: Env) -> Option<AutoPayoutConfig> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::AutoPayoutConfig)
+    }
+
+    pub fn is_auto_payout_enabled(env: Env, publisher: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        !env.storage().persistent().has(&DataKey::AutoPayoutOptOut(publisher))
+    }
+
+    pub fn get_pending_daily_cap(env: Env) -> Option<PendingDailyCap> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::PendingDailyCap)
+    }
+
+    /// Amount that can still be paid out today (None when uncapped)
+    pub fn get_remaining_daily_capacity(env: Env) -> Option<i128> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_daily_cap(&env).map(|cap| (cap - Self::_daily_outflow(&env)).max(0))
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+    }
+
+    pub fn is_operator(env: Env, address: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_is_operator(&env, &address)
+    }
+
+    pub fn get_operators(env: Env) -> Vec<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&RoleKey::Operators).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_guardian(env: Env) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::Guardian)
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn get_min_payout(env: Env) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::MinPayoutAmount).unwrap_or(0)
+    }
+
+    pub fn get_min_payout_interval(env: Env) -> Option<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::MinPayoutInterval)
+    }
+
+    /// Earliest time the publisher can be paid again; 0 if there is no restriction
+    pub fn next_payout_allowed_at(env: Env, publisher: Address) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_next_payout_allowed_at(&env, &publisher)
+    }
+
+    pub fn get_payout_ttl(env: Env) -> Option<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::PayoutTtl)
+    }
+
+    pub fn get_stream(env: Env, stream_id: u64) -> Option<PayoutStream> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&StreamKey::Stream(stream_id))
+    }
+
+    /// Whether a stream is waiting for the approver
+    pub fn is_stream_pending(env: Env, stream_id: u64) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().has(&StreamKey::PendingApproval(stream_id))
+    }
+
+    /// Amount a stream's recipient could withdraw right now
+    pub fn get_stream_withdrawable(env: Env, stream_id: u64) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get::<StreamKey, PayoutStream>(&StreamKey::Stream(stream_id))
+            .map_or(0, |stream| Self::_vested(&env, &stream) - stream.withdrawn)
+    }
+
+    /// Payout ids recorded for a campaign (0 for payouts without one), oldest first
+    pub fn get_campaign_payouts(env: Env, campaign_id: u64, offset: u32, limit: u32) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CampaignPayouts(campaign_id))
+            .unwrap_or(Vec::new(&env));
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(ids.len());
+        if offset >= end {
+            return Vec::new(&env);
+        }
+        ids.slice(offset..end)
+    }
+
+    pub fn get_campaign_totals(env: Env, campaign_id: u64) -> CampaignPayoutTotals {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::CampaignTotals(campaign_id))
+            .unwrap_or_default()
+    }
+
+    /// Balance against scheduled liabilities in the default token
+    pub fn get_treasury_status(env: Env) -> TreasuryStatus {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let liabilities = Self::_liabilities(&env, &token_addr);
+        TreasuryStatus {
+            balance: token::Client::new(&env, &token_addr).balance(&env.current_contract_address()),
+            liabilities,
+            surplus: Self::_available_balance(&env, &token_addr) - liabilities,
+            total_deposited: env
+                .storage()
+                .instance()
+                .get(&DataKey::TotalDeposited(token_addr.clone()))
+                .unwrap_or(0),
+            token: token_addr,
+        }
+    }
+
+    pub fn is_strict_funding(env: Env) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::StrictFunding).unwrap_or(false)
+    }
+
+    pub fn is_expire_to_treasury(env: Env) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::ExpireToTreasury).unwrap_or(false)
+    }
+
+    pub fn get_large_payout_threshold(env: Env) -> Option<i128> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::LargePayoutThreshold)
+    }
+
+    pub fn get_payout_approver(env: Env) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::PayoutApprover)
+    }
+
+    /// Earnings plus lifetime payout aggregates for a publisher
+    pub fn get_publisher_summary(env: Env, publisher: Address) -> PublisherSummary {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let earnings = Self::_load_earnings(&env, &publisher, &token_addr);
+        let total_withheld: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TotalWithheld(publisher))
+            .unwrap_or(0);
+
+        PublisherSummary {
+            publisher: earnings.publisher,
+            pending_amount: earnings.pending_amount,
+            reserved_amount: earnings.reserved_amount,
+            total_paid: earnings.total_paid,
+            total_withheld,
+            payout_count: earnings.payout_count,
+            last_payout: earnings.last_payout,
+        }
+    }
+
+    /// Total paid to a publisher across day buckets `from_bucket..=to_bucket`
+    pub fn get_paid_in_range(env: Env, publisher: Address, from_bucket: u32, to_bucket: u32) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        if from_bucket > to_bucket {
+            panic!("invalid range");
+        }
+        if to_bucket - from_bucket >= MAX_BUCKET_SPAN {
+            panic!("range too large");
+        }
+
+        let mut total: i128 = 0;
+        for bucket in from_bucket..=to_bucket {
+            total += env
+                .storage()
+                .persistent()
+                .get::<DataKey, i128>(&DataKey::PublisherPaidBucket(publisher.clone(), bucket))
+                .unwrap_or(0);
+        }
+        total
+    }
+
+    /// Bucket index (days since epoch) for the current ledger timestamp
+    pub fn get_current_bucket(env: Env) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_bucket_index(&env)
+    }
+
+    pub fn get_withholding(env: Env, publisher: Address) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::Withholding(publisher))
+            .unwrap_or(0)
+    }
+
+    pub fn get_withheld_total(env: Env, publisher: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::TotalWithheld(publisher))
+            .unwrap_or(0)
+    }
+
+    pub fn get_withholding_account(env: Env) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::WithholdingAccount)
+    }
+
+    // ============================================================
+    // Internal Helpers
+    // ============================================================
+
+    fn _require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *admin != stored_admin {
+            panic!("unauthorized");
+        }
+    }
+
+    /// Payout execution is open to the admin and operators
+    fn _require_executor(env: &Env, executor: &Address) {
+        executor.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *executor != stored_admin && !Self::_is_operator(env, executor) {
+            panic!("unauthorized");
+        }
+    }
+
+    fn _is_operator(env: &Env, address: &Address) -> bool {
+        env.storage().persistent().has(&RoleKey::Operator(address.clone()))
+    }
+
+    /// A fresh payout with default options; `_create_payout` assigns its id and status
+    fn _new_payout(env: &Env, recipient: &Address, token: &Address, amount: i128, execute_after: u64) -> ScheduledPayout {
+        ScheduledPayout {
+            payout_id: 0,
+            recipient: recipient.clone(),
+            token: token.clone(),
+            amount,
+            scheduled_at: env.ledger().timestamp(),
+            execute_after,
+            status: PayoutStatus::Scheduled,
+            campaign_id: None,
+            executed_at: None,
+            withheld: 0,
+            priority: 0,
+            reserved: false,
+            amended_count: 0,
+            expires_at: None,
+            cancel_reason: CancelReason::None,
+            memo: None,
+            failure_reason: FailureReason::None,
+            failed_at: None,
+        }
+    }
+
+    fn _create_payout(env: &Env, mut payout: ScheduledPayout) -> u64 {
+        let counter: u64 = env.storage().instance().get(&DataKey::PayoutCounter).unwrap_or(0);
+        payout.payout_id = counter + 1;
+        if payout.expires_at.is_none() {
+            let ttl: Option<u64> = env.storage().instance().get(&DataKey::PayoutTtl);
+            payout.expires_at = ttl.map(|ttl| payout.execute_after + ttl);
+        }
+
+        // Payouts at or above the large-payout threshold need a second signature
+        payout.status = if Self::_needs_approval(env, payout.amount) {
+            PayoutStatus::PendingApproval
+        } else {
+            PayoutStatus::Scheduled
+        };
+
+        Self::_save_payout(env, &payout);
+        if payout.status == PayoutStatus::Scheduled {
+            Self::_enqueue_due(env, &payout);
+        }
+        env.storage().instance().set(&DataKey::PayoutCounter, &payout.payout_id);
+
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("schedule")),
+            (payout.payout_id, payout.recipient, payout.amount, payout.memo),
+        );
+
+        payout.payout_id
+    }
+
+    fn _needs_approval(env: &Env, amount: i128) -> bool {
+        env.storage()
+            .instance()
+            .get::<DataKey, i128>(&DataKey::LargePayoutThreshold)
+            .is_some_and(|threshold| amount >= threshold)
+    }
+
+    /// Apply a new amount and execution time to a scheduled payout, keeping the
+    /// reservation and due queue in step. Raising the amount to the
+    /// large-payout threshold sends it back for approval.
+    fn _amend_payout(env: &Env, payout: &mut ScheduledPayout, amount: i128, execute_after: u64) {
+        let old_amount = payout.amount;
+        let min_amount: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MinPayoutAmount)
+            .unwrap_or(0);
+        if amount < min_amount {
+            panic!("amount below minimum");
+        }
+
+        // Auto payouts hold their amount in reserve; move the difference to or from pending
+        if payout.reserved && amount != old_amount {
+            let mut earnings = Self::_load_earnings(env, &payout.recipient, &payout.token);
+            let increase = amount - old_amount;
+            if increase > earnings.pending_amount {
+                panic!("insufficient pending earnings");
+            }
+            earnings.pending_amount -= increase;
+            earnings.reserved_amount += increase;
+            Self::_save_earnings(env, &earnings);
+        }
+
+        Self::_dequeue_due(env, payout);
+        payout.amount = amount;
+        payout.execute_after = execute_after;
+        payout.amended_count += 1;
+        if amount > old_amount && Self::_needs_approval(env, amount) {
+            payout.status = PayoutStatus::PendingApproval;
+        }
+        Self::_save_payout(env, payout);
+        if payout.status == PayoutStatus::Scheduled {
+            Self::_enqueue_due(env, payout);
+        }
+    }
+
+    /// Schedule the full pending balance once it reaches the auto-payout threshold.
+    /// The amount moves from pending to reserved so it cannot be scheduled twice.
+    fn _maybe_auto_schedule(env: &Env, earnings: &mut PublisherEarnings) {
+        let config: AutoPayoutConfig = match env.storage().instance().get(&DataKey::AutoPayoutConfig) {
+            Some(config) => config,
+            None => return,
+        };
+        if earnings.pending_amount < config.threshold
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::AutoPayoutOptOut(earnings.publisher.clone()))
+        {
+            return;
+        }
+
+        let amount = earnings.pending_amount;
+        earnings.pending_amount = 0;
+        earnings.reserved_amount += amount;
+        let execute_after = env.ledger().timestamp() + config.delay;
+        let mut payout = Self::_new_payout(env, &earnings.publisher, &earnings.token, amount, execute_after);
+        payout.reserved = true;
+        Self::_create_payout(env, payout);
+    }
+
+    /// Credit new earnings, notify the publisher, and auto-schedule if due
+    fn _credit(env: &Env, earnings: &mut PublisherEarnings, amount: i128) {
+        Self::_accrue(earnings, amount);
+        env.events().publish(
+            (symbol_short!("earnings"), symbol_short!("added")),
+            (earnings.publisher.clone(), amount, earnings.pending_amount),
+        );
+        Self::_notify_threshold(env, earnings);
+        Self::_maybe_auto_schedule(env, earnings);
+    }
+
+    /// Emit a threshold event the first time pending earnings reach the payout
+    /// threshold (auto-payout threshold if configured, else the minimum payout)
+    /// since the publisher's last payout.
+    fn _notify_threshold(env: &Env, earnings: &PublisherEarnings) {
+        let threshold: i128 = match env
+            .storage()
+            .instance()
+            .get::<DataKey, AutoPayoutConfig>(&DataKey::AutoPayoutConfig)
+        {
+            Some(config) => config.threshold,
+            None => env.storage().instance().get(&DataKey::MinPayoutAmount).unwrap_or(0),
+        };
+        let key = DataKey::ThresholdNotified(earnings.publisher.clone(), earnings.token.clone());
+        if earnings.pending_amount < threshold || env.storage().persistent().has(&key) {
+            return;
+        }
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("earnings"), symbol_short!("threshold")),
+            (earnings.publisher.clone(), earnings.pending_amount, threshold),
+        );
+    }
+
+    /// Credit new earnings, paying down any outstanding debt first
+    fn _accrue(earnings: &mut PublisherEarnings, amount: i128) {
+        let repaid = amount.min(earnings.debt);
+        earnings.debt -= repaid;
+        earnings.pending_amount = earnings
+            .pending_amount
+            .checked_add(amount - repaid)
+            .expect("overflow");
+    }
+
+    /// Record value from a cancelled or expired payout that did not go back to
+    /// publisher earnings. Call after the payout's liability is dropped: only
+    /// balance not backing other scheduled payouts is reclaimed, so an
+    /// unfunded payout never locks up funds other payouts rely on.
+    fn _reclaim(env: &Env, token_addr: &Address, amount: i128) {
+        let key = DataKey::ReclaimedBalance(token_addr.clone());
+        let reclaimed: i128 = env.storage().instance().get(&key).unwrap_or(0);
+        let balance = token::Client::new(env, token_addr).balance(&env.current_contract_address());
+        let unreserved = balance - Self::_liabilities(env, token_addr);
+        let updated = (reclaimed + amount).min(unreserved).max(reclaimed);
+        env.storage().instance().set(&key, &updated);
+
+        Self::_update_stats(env, |stats| stats.total_reclaimed += updated - reclaimed);
+    }
+
+    /// Return a cancelled auto payout's reserved amount to pending earnings
+    fn _release_reservation(env: &Env, payout: &ScheduledPayout) {
+        if !payout.reserved {
+            return;
+        }
+        let mut earnings = Self::_load_earnings(env, &payout.recipient, &payout.token);
+        earnings.reserved_amount -= payout.amount;
+        earnings.pending_amount += payout.amount;
+        Self::_save_earnings(env, &earnings);
+    }
+
+    /// Pay out a validated, due payout. All state (status, receipt, earnings)
+    /// is persisted before any token transfer (checks-effects-interactions).
+    fn _execute(env: &Env, executor: &Address, mut payout: ScheduledPayout) {
+        let payee = Self::_payee(env, &payout.recipient);
+        Self::_settle(env, executor, &mut payout, &payee, None);
+
+        Self::_disburse(env, &payout.token, &payee, payout.amount, payout.withheld);
+
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("execute")),
+            (payout.payout_id, payout.amount, payout.withheld, payout.memo),
+        );
+    }
+
+    /// State changes for paying a payout: outflow, withholding, receipt,
+    /// Completed status and earnings. The caller performs the transfer.
+    fn _settle(env: &Env, executor: &Address, payout: &mut ScheduledPayout, payee: &Address, merge_id: Option<u64>) {
+        Self::_promote_daily_cap(env);
+        Self::_record_outflow(env, payout.amount);
+        payout.withheld = Self::_apply_withholding(env, &payout.recipient, payout.amount);
+
+        let receipt = ExecutionReceipt {
+            payout_id: payout.payout_id,
+            executor: executor.clone(),
+            paid_to: payee.clone(),
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+            amount: payout.amount,
+            withheld: payout.withheld,
+            net_amount: payout.amount - payout.withheld,
+            merge_id,
+        };
+        let _ttl_key = DataKey::ExecutionReceipt(payout.payout_id);
+        env.storage().persistent().set(&_ttl_key, &receipt);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        payout.status = PayoutStatus::Completed;
+        payout.executed_at = Some(env.ledger().timestamp());
+        Self::_save_payout(env, payout);
+        Self::_dequeue_due(env, payout);
+
+        // Update publisher earnings
+        let mut earnings = Self::_load_earnings(env, &payout.recipient, &payout.token);
+        earnings.total_paid += payout.amount;
+        if payout.reserved {
+            earnings.reserved_amount -= payout.amount;
+        } else {
+            earnings.pending_amount = (earnings.pending_amount - payout.amount).max(0);
+        }
+        earnings.last_payout = env.ledger().timestamp();
+        earnings.payout_count += 1;
+        Self::_save_earnings(env, &earnings);
+        env.storage().persistent().remove(&DataKey::IntervalOverride(payout.payout_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ThresholdNotified(payout.recipient.clone(), payout.token.clone()));
+        Self::_record_paid_bucket(env, &payout.recipient, payout.amount);
+    }
+
+    /// Active daily cap, taking a matured pending change into account
+    fn _daily_cap(env: &Env) -> Option<i128> {
+        if let Some(pending) = env
+            .storage()
+            .instance()
+            .get::<DataKey, PendingDailyCap>(&DataKey::PendingDailyCap)
+        {
+            if env.ledger().timestamp() >= pending.effective_at {
+                return pending.cap;
+            }
+        }
+        env.storage().instance().get(&DataKey::DailyCap)
+    }
+
+    fn _promote_daily_cap(env: &Env) {
+        if let Some(pending) = env
+            .storage()
+            .instance()
+            .get::<DataKey, PendingDailyCap>(&DataKey::PendingDailyCap)
+        {
+            if env.ledger().timestamp() >= pending.effective_at {
+                match pending.cap {
+                    Some(cap) => env.storage().instance().set(&DataKey::DailyCap, &cap),
+                    None => env.storage().instance().remove(&DataKey::DailyCap),
+                }
+                env.storage().instance().remove(&DataKey::PendingDailyCap);
+            }
+        }
+    }
+
+    fn _daily_outflow(env: &Env) -> i128 {
+        let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+        env.storage()
+            .persistent()
+            .get(&DataKey::DailyOutflow(day))
+            .unwrap_or(0)
+    }
+
+    fn _within_daily_cap(env: &Env, amount: i128) -> bool {
+        match Self::_daily_cap(env) {
+            Some(cap) => Self::_daily_outflow(env) + amount <= cap,
+            None => true,
+        }
+    }
+
+    fn _record_outflow(env: &Env, amount: i128) {
+        let key = DataKey::DailyOutflow(env.ledger().timestamp() / SECONDS_PER_DAY);
+        let spent: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(spent + amount));
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_LIFETIME_THRESHOLD);
+    }
+
+    /// Report an outflow the daily cap deferred: (topic,"capped") with the
+    /// payout, recurring or stream id, the amount and the capacity left today
+    fn _emit_capped(env: &Env, topic: Symbol, id: u64, amount: i128) {
+        env.events().publish(
+            (topic, symbol_short!("capped")),
+            (id, amount, Self::_remaining_daily_capacity(env)),
+        );
+    }
+
+    fn _remaining_daily_capacity(env: &Env) -> i128 {
+        Self::_daily_cap(env)
+            .map(|cap| (cap - Self::_daily_outflow(env)).max(0))
+            .unwrap_or(0)
+    }
+
+    fn _due_payouts(env: &Env, now: u64, limit: u32) -> Vec<u64> {
+        let mut result = Vec::new(env);
+        let days: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::DueDays)
+            .unwrap_or(Vec::new(env));
+
+        for day in days.iter() {
+            if day > now / SECONDS_PER_DUE_BUCKET || result.len() >= limit {
+                break;
+            }
+            let bucket: Vec<DueEntry> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::DueBucket(day))
+                .unwrap_or(Vec::new(env));
+            for entry in bucket.iter() {
+                if entry.execute_after > now || result.len() >= limit {
+                    break;
+                }
+                if entry.expires_at.is_some_and(|expiry| now >= expiry) {
+                    continue;
+                }
+                result.push_back(entry.payout_id);
+            }
+        }
+        result
+    }
+
+    fn _due_before(a: &DueEntry, b: &DueEntry) -> bool {
+        (a.execute_after, u32::MAX - a.priority, a.payout_id)
+            < (b.execute_after, u32::MAX - b.priority, b.payout_id)
+    }
+
+    fn _enqueue_due(env: &Env, payout: &ScheduledPayout) {
+        let day = payout.execute_after / SECONDS_PER_DUE_BUCKET;
+        let entry = DueEntry {
+            execute_after: payout.execute_after,
+            priority: payout.priority,
+            payout_id: payout.payout_id,
+            expires_at: payout.expires_at,
+        };
+
+        let bucket_key = DataKey::DueBucket(day);
+        let mut bucket: Vec<DueEntry> = env
+            .storage()
+            .persistent()
+            .get(&bucket_key)
+            .unwrap_or(Vec::new(env));
+        let mut pos = bucket.len();
+        for (i, existing) in bucket.iter().enumerate() {
+            if Self::_due_before(&entry, &existing) {
+                pos = i as u32;
+                break;
+            }
+        }
+        let was_empty = bucket.is_empty();
+        bucket.insert(pos, entry);
+        env.storage().persistent().set(&bucket_key, &bucket);
+        Self::_extend_until(env, &bucket_key, payout.execute_after);
+
+        let mut days: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::DueDays)
+            .unwrap_or(Vec::new(env));
+        if was_empty {
+            let mut pos = days.len();
+            for (i, existing) in days.iter().enumerate() {
+                if day < existing {
+                    pos = i as u32;
+                    break;
+                }
+            }
+            days.insert(pos, day);
+            env.storage().persistent().set(&DataKey::DueDays, &days);
+        }
+        Self::_extend_until(env, &DataKey::DueDays, payout.execute_after);
+    }
+
+    fn _dequeue_due(env: &Env, payout: &ScheduledPayout) {
+        let day = payout.execute_after / SECONDS_PER_DUE_BUCKET;
+        let bucket_key = DataKey::DueBucket(day);
+        let mut bucket: Vec<DueEntry> = match env.storage().persistent().get(&bucket_key) {
+            Some(bucket) => bucket,
+            None => return,
+        };
+        if let Some(i) = bucket.iter().position(|e| e.payout_id == payout.payout_id) {
+            bucket.remove(i as u32);
+        }
+
+        if !bucket.is_empty() {
+            env.storage().persistent().set(&bucket_key, &bucket);
+            return;
+        }
+        env.storage().persistent().remove(&bucket_key);
+
+        let mut days: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::DueDays)
+            .unwrap_or(Vec::new(env));
+        if let Some(i) = days.iter().position(|d| d == day) {
+            days.remove(i as u32);
+            env.storage().persistent().set(&DataKey::DueDays, &days);
+        }
+    }
+
+    fn _require_active_publisher(env: &Env, publisher: &Address) {
+        let status: PublisherStatus = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PublisherStatus(publisher.clone()))
+            .expect("publisher not registered");
+        if status.state != PublisherState::Active {
+            panic!("publisher suspended");
+        }
+    }
+
+    fn _is_active_publisher(env: &Env, publisher: &Address) -> bool {
+        env.storage()
+            .persistent()
+            .get::<DataKey, PublisherStatus>(&DataKey::PublisherStatus(publisher.clone()))
+            .is_some_and(|status| status.state == PublisherState::Active)
+    }
+
+    fn _next_payout_allowed_at(env: &Env, publisher: &Address) -> u64 {
+        let interval: u64 = match env.storage().instance().get(&DataKey::MinPayoutInterval) {
+            Some(interval) => interval,
+            None => return 0,
+        };
+        let mut last_payout: Option<u64> = None;
+        for token_addr in Self::get_publisher_tokens(env.clone(), publisher.clone()).iter() {
+            if let Some(earnings) = Self::_get_earnings(env, publisher, &token_addr) {
+                if earnings.payout_count > 0 {
+                    last_payout = Some(last_payout.unwrap_or(0).max(earnings.last_payout));
+                }
+            }
+        }
+        last_payout.map_or(0, |last| last + interval)
+    }
+
+    /// Whether the minimum payout interval blocks this payout right now
+    fn _too_frequent(env: &Env, payout: &ScheduledPayout) -> bool {
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::IntervalOverride(payout.payout_id))
+        {
+            return false;
+        }
+        env.ledger().timestamp() < Self::_next_payout_allowed_at(env, &payout.recipient)
+    }
+
+    /// Mark a payout Failed without paying; reserved amounts return to pending
+    fn _fail_payout(env: &Env, mut payout: ScheduledPayout, reason: FailureReason) {
+        payout.status = PayoutStatus::Failed;
+        payout.failure_reason = reason;
+        payout.failed_at = Some(env.ledger().timestamp());
+        Self::_save_payout(env, &payout);
+        Self::_dequeue_due(env, &payout);
+        Self::_release_reservation(env, &payout);
+
+        env.events().publish(
+            (symbol_short!("payout"), symbol_short!("failed")),
+            (payout.payout_id, payout.recipient),
+        );
+    }
+
+    fn _active_destination(env: &Env, record: &PayoutDestination) -> Option<Address> {
+        if env.ledger().timestamp() >= record.active_at {
+            Some(record.destination.clone())
+        } else {
+            record.previous.clone()
+        }
+    }
+
+    /// Address that receives funds paid to `publisher`
+    fn _payee(env: &Env, publisher: &Address) -> Address {
+        env.storage()
+            .persistent()
+            .get::<DataKey, PayoutDestination>(&DataKey::PayoutDestination(publisher.clone()))
+            .and_then(|record| Self::_active_destination(env, &record))
+            .unwrap_or(publisher.clone())
+    }
+
+    fn _is_expired(env: &Env, payout: &ScheduledPayout) -> bool {
+        payout
+            .expires_at
+            .is_some_and(|expiry| env.ledger().timestamp() >= expiry)
+    }
+
+    fn _require_payout_token(env: &Env, token: &Address) {
+        let default_token: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        if *token == default_token {
+            return;
+        }
+        let tokens: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::PayoutTokens)
+            .unwrap_or(Vec::new(env));
+        if !tokens.contains(token) {
+            panic!("token not allowed");
+        }
+    }
+
+    fn _require_not_paused(env: &Env) {
+        if env.storage().instance().get(&DataKey::Paused).unwrap_or(false) {
+            panic!("paused");
+        }
+    }
+
+    fn _get_earnings(env: &Env, publisher: &Address, token: &Address) -> Option<PublisherEarnings> {
+        if let Some(earnings) = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PublisherEarnings(publisher.clone(), token.clone()))
+        {
+            return Some(earnings);
+        }
+
+        // Fall back to the pre-multi-token record for the default token
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        if *token != token_addr {
+            return None;
+        }
+        env.storage()
+            .persistent()
+            .get::<LegacyDataKey, LegacyPublisherEarnings>(&LegacyDataKey::PublisherEarnings(publisher.clone()))
+            .map(|legacy| PublisherEarnings {
+                publisher: legacy.publisher,
+                token: token_addr,
+                pending_amount: legacy.pending_amount,
+                reserved_amount: 0,
+                total_paid: legacy.total_paid,
+                last_payout: legacy.last_payout,
+                payout_count: 0,
+                debt: 0,
+            })
+    }
+
+    fn _load_earnings(env: &Env, publisher: &Address, token: &Address) -> PublisherEarnings {
+        Self::_get_earnings(env, publisher, token)
+            .unwrap_or(PublisherEarnings {
+                publisher: publisher.clone(),
+                token: token.clone(),
+                pending_amount: 0,
+                reserved_amount: 0,
+                total_paid: 0,
+                last_payout: 0,
+                payout_count: 0,
+                debt: 0,
+            })
+    }
+
+    fn _save_earnings(env: &Env, earnings: &PublisherEarnings) {
+        let key = DataKey::PublisherEarnings(earnings.publisher.clone(), earnings.token.clone());
+        let ttl = Self::_earnings_ttl(env);
+
+        let tokens_key = DataKey::PublisherTokens(earnings.publisher.clone());
+        let mut tokens: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&tokens_key)
+            .unwrap_or(Vec::new(env));
+        if !tokens.contains(&earnings.token) {
+            tokens.push_back(earnings.token.clone());
+            env.storage().persistent().set(&tokens_key, &tokens);
+        }
+        env.storage().persistent().extend_ttl(&tokens_key, ttl, ttl);
+
+        env.storage().persistent().set(&key, earnings);
+        env.storage().persistent().extend_ttl(&key, ttl, ttl);
+
+        // Earnings are only usable while the publisher's status can be read
+        let status_key = DataKey::PublisherStatus(earnings.publisher.clone());
+        if env.storage().persistent().has(&status_key) {
+            env.storage().persistent().extend_ttl(&status_key, ttl, ttl);
+        }
+
+        // The record now lives under the per-token key
+        env.storage()
+            .persistent()
+            .remove(&LegacyDataKey::PublisherEarnings(earnings.publisher.clone()));
+    }
+
+    fn _earnings_ttl(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::EarningsTtl)
+            .unwrap_or(PERSISTENT_BUMP_AMOUNT)
+    }
+
+    /// Read a payout, upgrading records stored in the pre-memo layout
+    fn _load_payout(env: &Env, payout_id: u64) -> Option<ScheduledPayout> {
+        let mut raw: Map<Symbol, Val> = env.storage().persistent().get(&DataKey::Payout(payout_id))?;
+        if raw.contains_key(Symbol::new(env, "memo")) {
+            // Records written before failure tracking lack its fields
+            if !raw.contains_key(Symbol::new(env, "failed_at")) {
+                raw.set(Symbol::new(env, "failed_at"), ().into_val(env));
+                raw.set(Symbol::new(env, "failure_reason"), FailureReason::None.into_val(env));
+            }
+            return Some(ScheduledPayout::try_from_val(env, &raw.to_val()).unwrap());
+        }
+
+        let legacy = LegacyScheduledPayout::try_from_val(env, &raw.to_val()).unwrap();
+        let mut payout = Self::_new_payout(env, &legacy.recipient, &legacy.token, legacy.amount, legacy.execute_after);
+        payout.payout_id = legacy.payout_id;
+        payout.scheduled_at = legacy.scheduled_at;
+        payout.status = legacy.status;
+        payout.campaign_id = legacy.campaign_id;
+        payout.executed_at = legacy.executed_at;
+        Some(payout)
+    }
+
+    /// Every payout write goes through here, so the stats always follow the
+    /// stored status and amounts.
+    fn _save_payout(env: &Env, payout: &ScheduledPayout) {
+        let previous = Self::_load_payout(env, payout.payout_id);
+        Self::_update_stats(env, |stats| {
+            if let Some(previous) = &previous {
+                Self::_apply_stats(stats, previous, false);
+            }
+            Self::_apply_stats(stats, payout, true);
+        });
+
+        Self::_update_campaign(env, previous.as_ref(), payout);
+
+        let owed = Self::_outstanding(payout) - previous.as_ref().map_or(0, Self::_outstanding);
+        Self::_add_liability(env, &payout.token, owed);
+
+        env.storage().persistent().set(&DataKey::Payout(payout.payout_id), payout);
+        Self::_bump_payout(env, payout);
+    }
+
+    /// Move a payout's amount between its campaign's status totals, indexing it on first save
+    fn _update_campaign(env: &Env, previous: Option<&ScheduledPayout>, payout: &ScheduledPayout) {
+        let campaign_id = payout.campaign_id.unwrap_or(0);
+        let totals_key = DataKey::CampaignTotals(campaign_id);
+        let mut totals: CampaignPayoutTotals = env.storage().persistent().get(&totals_key).unwrap_or_default();
+
+        match previous {
+            Some(previous) => Self::_apply_campaign_totals(&mut totals, previous, -previous.amount),
+            None => {
+                let ids_key = DataKey::CampaignPayouts(campaign_id);
+                let mut ids: Vec<u64> = env
+                    .storage()
+                    .persistent()
+                    .get(&ids_key)
+                    .unwrap_or(Vec::new(env));
+                ids.push_back(payout.payout_id);
+                env.storage().persistent().set(&ids_key, &ids);
+                Self::_extend_until(env, &ids_key, payout.execute_after);
+                totals.payout_count += 1;
+            }
+        }
+        Self::_apply_campaign_totals(&mut totals, payout, payout.amount);
+
+        // Saving the payout again reads these, so keep them alive as long as the payout
+        env.storage().persistent().set(&totals_key, &totals);
+        Self::_extend_until(env, &totals_key, payout.execute_after);
+    }
+
+    fn _apply_campaign_totals(totals: &mut CampaignPayoutTotals, payout: &ScheduledPayout, amount: i128) {
+        let total = match payout.status {
+            PayoutStatus::PendingApproval | PayoutStatus::Scheduled | PayoutStatus::Processing => &mut totals.scheduled,
+            PayoutStatus::Completed => &mut totals.executed,
+            PayoutStatus::Cancelled => &mut totals.cancelled,
+            PayoutStatus::Failed => &mut totals.failed,
+        };
+        *total = (*total + amount).max(0);
+    }
+
+    /// Amount a payout still owes: its full amount until it completes, fails or is cancelled
+    fn _outstanding(payout: &ScheduledPayout) -> i128 {
+        match payout.status {
+            PayoutStatus::PendingApproval | PayoutStatus::Scheduled | PayoutStatus::Processing => payout.amount,
+            _ => 0,
+        }
+    }
+
+    fn _add_liability(env: &Env, token: &Address, delta: i128) {
+        if delta != 0 {
+            let liabilities = (Self::_liabilities(env, token) + delta).max(0);
+            env.storage()
+                .instance()
+                .set(&DataKey::TotalScheduledLiability(token.clone()), &liabilities);
+        }
+    }
+
+    fn _liabilities(env: &Env, token: &Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalScheduledLiability(token.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Read-modify-write of the contract-wide stats; every counter change goes
+    /// through here.
+    fn _update_stats(env: &Env, update: impl FnOnce(&mut PayoutStats)) {
+        let mut stats: PayoutStats = env.storage().instance().get(&DataKey::PayoutStats).unwrap_or_default();
+        update(&mut stats);
+        env.storage().instance().set(&DataKey::PayoutStats, &stats);
+    }
+
+    /// Add (or remove) one payout's contribution to the stats. Removal saturates
+    /// so records predating the stats cannot drive counters negative.
+    fn _apply_stats(stats: &mut PayoutStats, payout: &ScheduledPayout, add: bool) {
+        let amount = if add { payout.amount } else { -payout.amount };
+        let adjust = |count: &mut u32| {
+            *count = if add { *count + 1 } else { count.saturating_sub(1) };
+        };
+        let adjust_amount = |total: &mut i128, delta: i128| {
+            *total = (*total + delta).max(0);
+        };
+
+        match payout.status {
+            PayoutStatus::PendingApproval => {
+                adjust(&mut stats.pending_approval_count);
+                adjust_amount(&mut stats.total_scheduled_amount, amount);
+            }
+            PayoutStatus::Scheduled | PayoutStatus::Processing => {
+                adjust(&mut stats.scheduled_count);
+                adjust_amount(&mut stats.total_scheduled_amount, amount);
+            }
+            PayoutStatus::Completed => {
+                adjust(&mut stats.completed_count);
+                adjust_amount(&mut stats.total_executed_amount, amount);
+                let withheld = if add { payout.withheld } else { -payout.withheld };
+                adjust_amount(&mut stats.total_withheld, withheld);
+            }
+            PayoutStatus::Failed => {
+                adjust(&mut stats.failed_count);
+                adjust_amount(&mut stats.total_failed_amount, amount);
+            }
+            PayoutStatus::Cancelled => {
+                adjust(&mut stats.cancelled_count);
+                adjust_amount(&mut stats.total_cancelled_amount, amount);
+            }
+        }
+    }
+
+    fn _bump_payout(env: &Env, payout: &ScheduledPayout) {
+        Self::_extend_until(env, &DataKey::Payout(payout.payout_id), payout.execute_after);
+
+        // Execution reads the recipient's status, so keep it alive as long as the payout
+        let status_key = DataKey::PublisherStatus(payout.recipient.clone());
+        if env.storage().persistent().has(&status_key) {
+            Self::_extend_until(env, &status_key, payout.execute_after);
+        }
+    }
+
+    /// Keep an entry alive for the standard bump *beyond* `timestamp`,
+    /// so far-future payouts are not archived before they become due.
+    fn _extend_until<K: IntoVal<Env, Val>>(env: &Env, key: &K, timestamp: u64) {
+        let ledgers_until = timestamp.saturating_sub(env.ledger().timestamp()) / LEDGER_SECONDS;
+        let extend_to = (ledgers_until.min(u32::MAX as u64) as u32)
+            .saturating_add(PERSISTENT_BUMP_AMOUNT)
+            .min(env.storage().max_ttl());
+        env.storage().persistent().extend_ttl(key, extend_to, extend_to);
+    }
+
+    /// Amount of a stream vested so far: linear between start and end, clamped to the total
+    fn _vested(env: &Env, stream: &PayoutStream) -> i128 {
+        let now = env.ledger().timestamp();
+        if now <= stream.start_at {
+            return 0;
+        }
+        if now >= stream.end_at {
+            return stream.total_amount;
+        }
+        stream
+            .total_amount
+            .checked_mul((now - stream.start_at) as i128)
+            .expect("overflow")
+            / (stream.end_at - stream.start_at) as i128
+    }
+
+    fn _save_stream(env: &Env, stream: &PayoutStream) {
+        let key = StreamKey::Stream(stream.stream_id);
+        env.storage().persistent().set(&key, stream);
+        Self::_extend_until(env, &key, stream.end_at);
+    }
+
+    /// Move a recurring schedule past an installment that was not paid now
+    fn _advance_recurring(env: &Env, recurring: &mut RecurringPayout) {
+        recurring.next_due += recurring.interval;
+        recurring.remaining -= 1;
+        Self::_save_recurring(env, recurring);
+    }
+
+    fn _save_recurring(env: &Env, recurring: &RecurringPayout) {
+        let key = DataKey::Recurring(recurring.recurring_id);
+        env.storage().persistent().set(&key, recurring);
+        Self::_extend_until(env, &key, recurring.next_due);
+    }
+
+    fn _bucket_index(env: &Env) -> u32 {
+        (env.ledger().timestamp() / SECONDS_PER_BUCKET) as u32
+    }
+
+    fn _record_paid_bucket(env: &Env, publisher: &Address, amount: i128) {
+        let key = DataKey::PublisherPaidBucket(publisher.clone(), Self::_bucket_index(env));
+        let paid: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(paid + amount));
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Work out and record a publisher's withholding share of `amount`.
+    /// Returns the withheld amount (rounded up in favour of the withholding account).
+    fn _apply_withholding(env: &Env, publisher: &Address, amount: i128) -> i128 {
+        let bps: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Withholding(publisher.clone()))
+            .unwrap_or(0);
+        let withheld = (amount * bps as i128 + BPS_DENOMINATOR - 1) / BPS_DENOMINATOR;
+
+        if withheld > 0 {
+            if !env.storage().instance().has(&DataKey::WithholdingAccount) {
+                panic!("withholding account not set");
+            }
+            let key = DataKey::TotalWithheld(publisher.clone());
+            let total: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+            let ttl = Self::_earnings_ttl(env);
+            env.storage().persistent().set(&key, &(total + withheld));
+            env.storage().persistent().extend_ttl(&key, ttl, ttl);
+        }
+        withheld
+    }
+
+    /// Token balance payouts may spend; reclaimed funds are held for the treasury
+    fn _available_balance(env: &Env, token_addr: &Address) -> i128 {
+        let reclaimed: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ReclaimedBalance(token_addr.clone()))
+            .unwrap_or(0);
+        token::Client::new(env, token_addr).balance(&env.current_contract_address()) - reclaimed
+    }
+
+    /// Transfer `amount` out: the withheld part to the withholding account, the rest to the payee
+    fn _disburse(env: &Env, token_addr: &Address, payee: &Address, amount: i128, withheld: i128) {
+        let token_client = token::Client::new(env, token_addr);
+        // Reclaimed funds are held for the treasury; payouts cannot spend them
+        let reclaimed: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ReclaimedBalance(token_addr.clone()))
+            .unwrap_or(0);
+        if reclaimed > 0 && token_client.balance(&env.current_contract_address()) - amount < reclaimed {
+            panic!("insufficient funds");
+        }
+        if withheld > 0 {
+            let account: Address = env.storage().instance().get(&DataKey::WithholdingAccount).unwrap();
+            token_client.transfer(&env.current_contract_address(), &account, &withheld);
+        }
+        token_client.transfer(&env.current_contract_address(), payee, &(amount - withheld));
+    }
+
+    /// The configured approver, who must not also be the admin
+    fn _require_approver(env: &Env, approver: &Address) {
+        approver.require_auth();
+        let stored_approver: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PayoutApprover)
+            .expect("approver not set");
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *approver != stored_approver || *approver == admin {
+            panic!("unauthorized");
+        }
+    }
+
+    fn _require_pending_stream(env: &Env, stream_id: u64) -> PayoutStream {
+        let stream: PayoutStream = env
+            .storage()
+            .persistent()
+            .get(&StreamKey::Stream(stream_id))
+            .expect("stream not found");
+        if !env.storage().persistent().has(&StreamKey::PendingApproval(stream_id)) {
+            panic!("stream not pending approval");
+        }
+        stream
+    }
+
+    fn _load_pending_approval(env: &Env, approver: &Address, payout_id: u64) -> ScheduledPayout {
+        Self::_require_approver(env, approver);
+
+        let payout = Self::_load_payout(env, payout_id).expect("payout not found");
+        if payout.status != PayoutStatus::PendingApproval {
+            panic!("payout not pending approval");
+        }
+        payout
     }
 }
 